@@ -1,9 +1,11 @@
-use super::{enums::Error, file::FileData};
+//! Import and export of the character appearance (the bytes after the `FACE` marker).
+use super::{constants::APPEARANCE_BYTES_AMOUNT, enums::Error, file::FileData};
 use std::{
     fs::{self, File},
     io::Read,
 };
 
+/// Writes the appearance bytes of the character to `path`.
 pub fn export(file_data: &FileData, path: &str) -> Result<(), Error> {
     let mut export_bytes = Vec::new();
     export_bytes.extend_from_slice(
@@ -13,14 +15,15 @@ pub fn export(file_data: &FileData, path: &str) -> Result<(), Error> {
     fs::write(path, &export_bytes).map_err(Error::IoError)
 }
 
+/// Overwrites the appearance of the character with the bytes stored in `path`.
 pub fn import(file_data: &mut FileData, path: &str) -> Result<(), Error> {
     // Read the exported file into a vector of bytes
     let mut file = File::open(path).map_err(Error::IoError)?;
     let mut bytes = Vec::new();
 
     file.read_to_end(&mut bytes).map_err(Error::IoError)?;
-    if bytes.len() != 0xEB {
-        return Err(Error::CustomError("Not correct size"));
+    if bytes.len() != APPEARANCE_BYTES_AMOUNT {
+        return Err(Error::InvalidAppearanceSize { size: bytes.len() });
     }
     let start = file_data.offsets.appearance;
     for i in start.0..=start.1 {
@@ -33,13 +36,13 @@ pub fn import(file_data: &mut FileData, path: &str) -> Result<(), Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data_handling::constants::APPEARANCE_BYTES_AMOUNT;
-    use std::{fs::File, io::Read, path::PathBuf};
+    use crate::constants::APPEARANCE_BYTES_AMOUNT;
+    use std::{fs::File, io::Read};
 
     #[test]
     fn test_export() {
         //TESTSAVE0
-        let file_data = FileData::build("saves/testsave0", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave0").unwrap();
         export(&file_data, "saves/testexport0").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -53,7 +56,7 @@ mod tests {
         assert_eq!(bytes, file_data.bytes[start.0..=start.1]);
 
         //TESTSAVE1
-        let file_data = FileData::build("saves/testsave1", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave1").unwrap();
         export(&file_data, "saves/testexport1").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -67,7 +70,7 @@ mod tests {
         assert_eq!(bytes, file_data.bytes[start.0..=start.1]);
 
         //TESTSAVE2
-        let file_data = FileData::build("saves/testsave2", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave2").unwrap();
         export(&file_data, "saves/testexport2").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -81,7 +84,7 @@ mod tests {
         assert_eq!(bytes, file_data.bytes[start.0..=start.1]);
 
         //TESTSAVE3
-        let file_data = FileData::build("saves/testsave3", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave3").unwrap();
         export(&file_data, "saves/testexport3").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -98,7 +101,7 @@ mod tests {
     #[test]
     fn test_import() {
         //TESTSAVE0
-        let mut file_data = FileData::build("saves/testsave0", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
         import(&mut file_data, "saves/testexport3").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -112,7 +115,7 @@ mod tests {
         assert_eq!(bytes, file_data.bytes[start.0..=start.1]);
 
         //TESTSAVE1
-        let mut file_data = FileData::build("saves/testsave1", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave1").unwrap();
         import(&mut file_data, "saves/testexport2").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -126,7 +129,7 @@ mod tests {
         assert_eq!(bytes, file_data.bytes[start.0..=start.1]);
 
         //TESTSAVE2
-        let mut file_data = FileData::build("saves/testsave2", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave2").unwrap();
         import(&mut file_data, "saves/testexport1").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -140,7 +143,7 @@ mod tests {
         assert_eq!(bytes, file_data.bytes[start.0..=start.1]);
 
         //TESTSAVE3
-        let mut file_data = FileData::build("saves/testsave3", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave3").unwrap();
         import(&mut file_data, "saves/testexport0").unwrap();
 
         // Read the exported file into a vector of bytes
@@ -157,7 +160,10 @@ mod tests {
         let result = import(&mut file_data, "saves/testexportempty");
         assert!(result.is_err());
         if let Err(error) = result {
-            assert_eq!(error.to_string(), "Save error: Not correct size");
+            assert_eq!(
+                error.to_string(),
+                "An appearance must be 235 bytes long, got 0."
+            );
         }
     }
 }
@@ -1,5 +1,7 @@
+//! Weapons, armors and items stored in an inventory slot.
 use super::{
-    enums::{ArticleType, Error, Imprint, SlotShape, TypeFamily},
+    constants::{MAX_INVENTORY_AMOUNT, MAX_STORAGE_AMOUNT, MAX_UPGRADE_LEVEL},
+    enums::{ArticleType, Error, Imprint, Location, SlotShape, TypeFamily},
     file::FileData,
     inventory::{get_info_armor, get_info_item, get_info_weapon},
     slots::Slot,
@@ -7,6 +9,35 @@ use super::{
 use serde::{Deserialize, Serialize};
 use serde_json::{self, json, Value};
 
+//Quicksilver Bullets and Blood Vials, the storage holds up to MAX_STORAGE_AMOUNT of them besides
+//the materials
+const STORAGE_STACKS: [u32; 2] = [900, 1000];
+
+//Items the inventory holds fewer than MAX_INVENTORY_AMOUNT of, and how many. The Blood Vials
+//aren't here, the ones picked up past 20 stay in the inventory, and neither are the Quicksilver
+//Bullets, whose cap the Formless Oedon rune raises
+const INVENTORY_CAPS: [(u32, u32); 19] = [
+    (1100, 10), //Antidote
+    (1101, 10), //Sedatives
+    (1110, 10), //Beast Blood Pellet
+    (1120, 10), //Blue Elixir
+    (1200, 10), //Molotov Cocktail
+    (1201, 10), //Delayed Molotov
+    (1210, 20), //Poison Knife
+    (1220, 10), //Oil Urn
+    (1230, 20), //Pebble
+    (1240, 20), //Throwing Knife
+    (1250, 10), //Rope Molotov Cocktail
+    (1251, 10), //Delayed Rope Molotov
+    (1260, 10), //Pungent Blood Cocktail
+    (1270, 10), //Numbing Mist
+    (1300, 10), //Fire Paper
+    (1320, 10), //Bolt Paper
+    (1330, 10), //Bone Marrow Ash
+    (2030, 3),  //Lead Elixir
+    (2090, 3),  //Shaman Bone Blade
+];
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ItemInfo {
     pub item_name: String,
@@ -15,7 +46,7 @@ pub struct ItemInfo {
     pub extra_info: Option<Value>,
 }
 
-//Describes the imprint and the upgrade level of a weapon
+///Describes the imprint and the upgrade level of a weapon
 pub struct WeaponMods {
     pub upgrade_level: u8,
     pub imprint: Option<Imprint>,
@@ -30,7 +61,7 @@ impl TryFrom<u32> for WeaponMods {
             0 | 80000 => None,
             10000 => Some(Imprint::Uncanny),
             20000 => Some(Imprint::Lost),
-            _ => return Err(Error::CustomError("ERROR: Invalid second_part")),
+            _ => return Err(Error::InvalidWeaponMods { second_part }),
         };
         Ok(WeaponMods {
             upgrade_level,
@@ -54,6 +85,23 @@ pub struct Article {
 }
 
 impl Article {
+    /// Most copies of the article a stack at `location` holds.
+    pub fn max_amount(&self, location: Location) -> u32 {
+        match location {
+            Location::Storage
+                if self.article_type == ArticleType::Material
+                    || STORAGE_STACKS.contains(&self.id) =>
+            {
+                MAX_STORAGE_AMOUNT
+            }
+            Location::Storage => MAX_INVENTORY_AMOUNT,
+            Location::Inventory => INVENTORY_CAPS
+                .iter()
+                .find(|(id, _)| *id == self.id)
+                .map_or(MAX_INVENTORY_AMOUNT, |(_, cap)| *cap),
+        }
+    }
+
     pub fn transform(
         &mut self,
         file_data: &mut FileData,
@@ -78,35 +126,28 @@ impl Article {
         is_storage: bool,
     ) -> Result<(), Error> {
         if new_id.len() != 3 {
-            return Err(Error::CustomError(
-                "ERROR: 'new_id' argument must be 3B long.",
-            ));
+            return Err(Error::InvalidIdLength {
+                expected: 3,
+                found: new_id.len(),
+            });
         }
 
         //ID
         let id = u32::from_le_bytes([new_id[0], new_id[1], new_id[2], 0]);
 
-        let info;
-        let article_type;
-        let type_family;
         //INFO & ARTICLE_TYPE
-        if let Ok((new_info, new_article_type)) = get_info_item(id, &file_data.resources_path) {
-            info = new_info;
-            article_type = new_article_type;
-            type_family = article_type.into();
-        } else {
-            return Err(Error::CustomError(
-                "ERROR: Failed to find info for the item.",
-            ));
-        }
+        let (info, article_type) = get_info_item(id)?;
+        let type_family = article_type.into();
 
         let i;
         match file_data.find_article_offset(self.number, self.id, self.type_family, is_storage) {
             Some(offset) => i = offset,
             None => {
-                return Err(Error::CustomError(
-                    "ERROR: The Article was not found in the inventory.",
-                ))
+                return Err(Error::ArticleNotFound {
+                    number: self.number,
+                    id: self.id,
+                    location: Location::from(is_storage),
+                })
             }
         }
 
@@ -125,7 +166,7 @@ impl Article {
 
         //Set amount to 1 if the item is a key or a chalice
         if (article_type == ArticleType::Key) || (article_type == ArticleType::Chalice) {
-            let amount = vec![0x01, 0x00, 0x00, 0x00];
+            let amount = [0x01, 0x00, 0x00, 0x00];
             for j in i + 12..=i + 15 {
                 file_data.bytes[j] = amount[j - i - 12];
             }
@@ -148,18 +189,21 @@ impl Article {
         is_storage: bool,
     ) -> Result<(), Error> {
         if new_id.len() != 4 {
-            return Err(Error::CustomError(
-                "ERROR: 'new_id' argument must be 4B long.",
-            ));
+            return Err(Error::InvalidIdLength {
+                expected: 4,
+                found: new_id.len(),
+            });
         }
 
         let i;
         match file_data.find_article_offset(self.number, self.id, self.type_family, is_storage) {
             Some(offset) => i = offset,
             None => {
-                return Err(Error::CustomError(
-                    "ERROR: The Article was not found in the inventory.",
-                ))
+                return Err(Error::ArticleNotFound {
+                    number: self.number,
+                    id: self.id,
+                    location: Location::from(is_storage),
+                })
             }
         }
         //Take the first and second part to search later
@@ -174,29 +218,19 @@ impl Article {
         let id = u32::from_le_bytes([new_id[0], new_id[1], new_id[2], new_id[3]]);
         let result;
         let second_part;
-        let info;
-        let article_type;
-        let type_family;
 
         if self.article_type == ArticleType::Armor {
             second_part = u32::from_le_bytes([new_id[0], new_id[1], new_id[2], 0x10]);
             byte_count = 3;
-            result = get_info_armor(id, &file_data.resources_path);
+            result = get_info_armor(id);
         } else {
             second_part = u32::from_le_bytes([new_id[0], new_id[1], new_id[2], new_id[3]]);
-            result = get_info_weapon(id, &file_data.resources_path);
+            result = get_info_weapon(id);
         }
 
         //INFO & ARTICLE_TYPE
-        if let Ok((new_info, new_article_type)) = result {
-            info = new_info;
-            article_type = new_article_type;
-            type_family = article_type.into();
-        } else {
-            return Err(Error::CustomError(
-                "ERROR: Failed to find info for the article.",
-            ));
-        }
+        let (info, article_type) = result?;
+        let type_family = article_type.into();
         //Update data only if the article is valid
 
         //SECOND PART
@@ -216,9 +250,10 @@ impl Article {
         }
 
         if !found {
-            return Err(Error::CustomError(
-                "ERROR: The Article was not found above the inventory.",
-            ));
+            return Err(Error::SlotsNotFound {
+                first_part: u32::from_le_bytes([query[0], query[1], query[2], query[3]]),
+                second_part: u32::from_le_bytes([query[4], query[5], query[6], query[7]]),
+            });
         } else {
             //Update the article id
             for j in index + 4..index + 4 + byte_count {
@@ -231,7 +266,7 @@ impl Article {
         self.second_part = second_part;
         self.article_type = article_type;
         self.type_family = type_family;
-        return Ok(());
+        Ok(())
     }
 
     pub fn is_armor(&self) -> bool {
@@ -253,12 +288,9 @@ impl Article {
         upgrade_level: Option<u8>,
     ) -> Result<Article, Error> {
         if !self.is_weapon() {
-            return Err(Error::CustomError("ERROR: The article must be a weapon."));
+            return Err(Error::NotAWeapon { id: self.id });
         }
-        let mut weapon_mods = match WeaponMods::try_from(self.second_part) {
-            Ok(wm) => wm,
-            Err(_) => return Err(Error::CustomError("ERROR: Invalid second_part")),
-        };
+        let mut weapon_mods = WeaponMods::try_from(self.second_part)?;
 
         let mut new_second_part = (self.second_part / 100000) * 100000;
         if let Some(extra_info) = &mut self.info.extra_info {
@@ -267,17 +299,18 @@ impl Article {
                 weapon_mods.imprint = imp;
             }
             if let Some(upg) = upgrade_level {
-                if upg > 10 {
-                    return Err(Error::CustomError(
-                        "ERROR: Upgrade level cannot be bigger than 10.",
-                    ));
+                if upg > MAX_UPGRADE_LEVEL {
+                    return Err(Error::InvalidUpgradeLevel { level: upg });
                 }
                 extra_info["upgrade_level"] = json!(upg);
                 weapon_mods.upgrade_level = upg;
             }
             scale_weapon_info(extra_info);
         } else {
-            return Err(Error::CustomError("ERROR: The weapon has no extrainfo."));
+            return Err(Error::ArticleInfoNotFound {
+                id: self.id,
+                family: self.type_family,
+            });
         }
         new_second_part += match weapon_mods.imprint {
             None => 0,
@@ -293,9 +326,11 @@ impl Article {
         match file_data.find_article_offset(self.number, self.id, self.type_family, false) {
             Some(offset) => index = offset,
             None => {
-                return Err(Error::CustomError(
-                    "ERROR: The Article was not found in the inventory.",
-                ))
+                return Err(Error::ArticleNotFound {
+                    number: self.number,
+                    id: self.id,
+                    location: Location::Inventory,
+                })
             }
         }
         for i in index + 8..=index + 11 {
@@ -315,9 +350,10 @@ impl Article {
             }
         }
         if !found {
-            return Err(Error::CustomError(
-                "ERROR: The weapon was not found above the inventory.",
-            ));
+            return Err(Error::SlotsNotFound {
+                first_part: self.first_part,
+                second_part: self.second_part,
+            });
         }
 
         self.id = new_second_part;
@@ -352,18 +388,22 @@ impl Article {
                 }
 
                 if !found {
-                    Err(Error::CustomError(
-                        "ERROR: Failed to find the article in the file data.",
-                    ))
+                    Err(Error::SlotsNotFound {
+                        first_part: self.first_part,
+                        second_part: self.second_part,
+                    })
                 } else {
                     slot.shape = new_shape;
                     Ok(())
                 }
             } else {
-                Err(Error::CustomError("ERROR: Invalid slot_index."))
+                Err(Error::InvalidSlotIndex {
+                    id: self.id,
+                    index: slot_index,
+                })
             }
         } else {
-            Err(Error::CustomError("ERROR: Article does not have slots."))
+            Err(Error::NoSlots { id: self.id })
         }
     }
 }
@@ -378,29 +418,33 @@ pub fn scale_weapon_info(extra_info: &mut Value) {
         let damage: String = serde_json::from_value(v.clone()).unwrap();
         let base_damage: String = serde_json::from_value(base_damages[k].clone()).unwrap();
 
-        let mut damage: u32 = match damage.parse::<u32>() {
-            Ok(num) => num,
-            Err(_) => continue, //If the damage is "-" (n/a), skip
-        };
+        //If the damage is "-" (n/a), skip
+        if damage.parse::<u32>().is_err() {
+            continue;
+        }
 
         let base_damage: u32 = match base_damage.parse::<u32>() {
             Ok(num) => num,
             Err(_) => continue, //If the damage is "-" (n/a), skip
         };
 
-        if upgrade_level == 10 {
-            damage = base_damage * 2;
-        } else {
-            damage = base_damage + (base_damage / 10) * upgrade_level;
-        }
-        *v = json!(damage.to_string());
+        *v = json!(upgrade_damage(base_damage, upgrade_level).to_string());
+    }
+}
+
+/// Base damage of a weapon at `upgrade_level`, a tenth more per level and double at +10.
+pub fn upgrade_damage(base_damage: u32, upgrade_level: u32) -> u32 {
+    if upgrade_level == 10 {
+        base_damage * 2
+    } else {
+        base_damage + (base_damage / 10) * upgrade_level
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data_handling::utils::test_utils::{build_file_data, build_save_data, check_bytes};
+    use crate::utils::test_utils::{build_file_data, build_save_data, check_bytes};
 
     #[test]
     fn article_transform_item() {
@@ -425,7 +469,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: Failed to find info for the item."
+                "Failed to find info for the item 13417386."
             );
         }
         article
@@ -475,7 +519,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: The Article was not found in the inventory."
+                "The article 7012 (number 0xff) was not found in the inventory."
             );
         }
 
@@ -562,7 +606,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: The Article was not found above the inventory."
+                "Failed to find the slots of the article 0x80800051 0xd59f80."
             );
         }
 
@@ -592,7 +636,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: Failed to find info for the article."
+                "Failed to find info for the weapon 3721100000."
             );
         }
         article
@@ -680,7 +724,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: The Article was not found in the inventory."
+                "The article 220000 (number 0xff) was not found in the inventory."
             );
         }
     }
@@ -861,10 +905,7 @@ mod tests {
         let result = article.set_imprint_and_upgrade(&mut save.file, None, None);
         assert!(result.is_err());
         if let Err(error) = result {
-            assert_eq!(
-                error.to_string(),
-                "Save error: ERROR: The article must be a weapon."
-            );
+            assert_eq!(error.to_string(), "The article 230000 must be a weapon.");
         }
 
         let article = &mut save
@@ -877,7 +918,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: Upgrade level cannot be bigger than 10."
+                "Upgrade level 11 cannot be bigger than 10."
             );
         }
 
@@ -887,7 +928,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: The weapon has no extrainfo."
+                "Failed to find info for the weapon 7000100."
             );
         }
 
@@ -895,7 +936,10 @@ mod tests {
         let result = article.set_imprint_and_upgrade(&mut save.file, None, None);
         assert!(result.is_err());
         if let Err(error) = result {
-            assert_eq!(error.to_string(), "Save error: ERROR: Invalid second_part");
+            assert_eq!(
+                error.to_string(),
+                "Invalid weapon mods in the second part 0xffffffff."
+            );
         }
 
         let article = &mut save
@@ -909,7 +953,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: The Article was not found in the inventory."
+                "The article 7100000 (number 0xee) was not found in the inventory."
             );
         }
 
@@ -924,7 +968,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: The weapon was not found above the inventory."
+                "Failed to find the slots of the article 0x80800051 0xd5ca78."
             );
         }
     }
@@ -951,7 +995,7 @@ mod tests {
         let result = hunter_axe.change_slot_shape(&mut save.file, 500, SlotShape::Closed);
         assert!(result.is_err());
         if let Err(error) = result {
-            assert_eq!(error.to_string(), "Save error: ERROR: Invalid slot_index.");
+            assert_eq!(error.to_string(), "The article 5000300 has no slot 500.");
         }
         assert_eq!(*hunter_axe, hunter_axe_reference);
 
@@ -962,7 +1006,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: ERROR: Failed to find the article in the file data."
+                "Failed to find the slots of the article 0x808001d0 0x4c4c6c."
             );
         }
         assert_eq!(*hunter_axe, hunter_axe_reference);
@@ -971,10 +1015,7 @@ mod tests {
         let result = consumable.change_slot_shape(&mut save.file, 0, SlotShape::Closed);
         assert!(result.is_err());
         if let Err(error) = result {
-            assert_eq!(
-                error.to_string(),
-                "Save error: ERROR: Article does not have slots."
-            );
+            assert_eq!(error.to_string(), "The article 100 has no slots.");
         }
 
         assert_eq!(
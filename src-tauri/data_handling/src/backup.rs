@@ -0,0 +1,294 @@
+//! Timestamped snapshots of a save, kept in a `<save>.backups` folder next to it.
+//!
+//! A snapshot is named `<milliseconds>-<hash>.bak`. The hash is taken from the
+//! contents, so opening the same file twice doesn't fill the folder with copies.
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    enums::Error,
+    file::FileData,
+    history::{diff, ByteChange},
+};
+
+/// How many snapshots are kept per save and for how long.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Retention {
+    pub keep: usize,
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention {
+            keep: 10,
+            max_age_days: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Backup {
+    pub id: String,
+    pub path: String,
+    pub timestamp: u64, //Milliseconds since the unix epoch
+    pub hash: String,
+    pub size: u64,
+}
+
+impl Backup {
+    fn from_path(path: &Path) -> Option<Backup> {
+        if path.extension()? != "bak" {
+            return None;
+        }
+        let id = path.file_stem()?.to_str()?.to_string();
+        let (timestamp, hash) = id.split_once('-')?;
+
+        Some(Backup {
+            timestamp: timestamp.parse().ok()?,
+            hash: hash.to_string(),
+            size: fs::metadata(path).ok()?.len(),
+            path: path.to_str()?.to_string(),
+            id,
+        })
+    }
+}
+
+/// Folder where the snapshots of `save_path` are stored.
+pub fn backup_dir(save_path: &str) -> PathBuf {
+    let mut dir = PathBuf::from(save_path).into_os_string();
+    dir.push(".backups");
+    PathBuf::from(dir)
+}
+
+///FNV-1a, stable between runs so the names can be compared across sessions
+pub fn hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Every snapshot of `save_path`, newest first.
+pub fn list(save_path: &str) -> Result<Vec<Backup>, Error> {
+    let dir = backup_dir(save_path);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<Backup> = fs::read_dir(dir)?
+        .filter_map(|entry| Backup::from_path(&entry.ok()?.path()))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.timestamp));
+    Ok(backups)
+}
+
+/// Stores `bytes` as a new snapshot of `save_path` and applies the retention policy.
+/// Returns `None` if a snapshot with the same contents already exists.
+pub fn create(
+    save_path: &str,
+    bytes: &[u8],
+    retention: &Retention,
+) -> Result<Option<Backup>, Error> {
+    let hash = hash(bytes);
+    if list(save_path)?.iter().any(|b| b.hash == hash) {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(save_path);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    let path = dir.join(format!("{}-{}.bak", timestamp, hash));
+    fs::write(&path, bytes)?;
+
+    prune(save_path, retention)?;
+    Ok(Backup::from_path(&path))
+}
+
+/// Deletes the snapshots the retention policy doesn't keep, the newest one is never deleted.
+pub fn prune(save_path: &str, retention: &Retention) -> Result<Vec<Backup>, Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60));
+
+    let mut removed = Vec::new();
+    for (i, backup) in list(save_path)?.into_iter().enumerate() {
+        let age = now.saturating_sub(Duration::from_millis(backup.timestamp));
+        let too_old = matches!(max_age, Some(max) if age > max);
+        if i > 0 && (i >= retention.keep || too_old) {
+            fs::remove_file(&backup.path)?;
+            removed.push(backup);
+        }
+    }
+    Ok(removed)
+}
+
+pub fn find(save_path: &str, id: &str) -> Result<Backup, Error> {
+    list(save_path)?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or(Error::BackupNotFound { id: id.to_string() })
+}
+
+/// Byte ranges that differ between the snapshot `id` (before) and `current` (after).
+pub fn diff_with(save_path: &str, id: &str, current: &[u8]) -> Result<Vec<ByteChange>, Error> {
+    let backup = find(save_path, id)?;
+    let bytes = fs::read(&backup.path)?;
+    if bytes.len() != current.len() {
+        return Ok(vec![ByteChange {
+            offset: 0,
+            before: bytes,
+            after: current.to_vec(),
+        }]);
+    }
+    Ok(diff(&bytes, current))
+}
+
+/// Overwrites `save_path` with the snapshot `id`, through the same checked write as
+/// [`FileData::save`]. The snapshot must parse, and the current contents are snapshotted
+/// first so the restore can be reverted.
+pub fn restore(save_path: &str, id: &str, retention: &Retention) -> Result<(), Error> {
+    let backup = find(save_path, id)?;
+    let file = FileData::from_bytes(fs::read(&backup.path)?)?;
+    if let Ok(current) = fs::read(save_path) {
+        create(save_path, &current, retention)?;
+    }
+    file.save(save_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Copies a test save to its own folder so the snapshots of each test don't mix
+    fn copy_save(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("bbse_backup_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("userdata0000");
+        fs::copy("saves/testsave0", &path).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash(b""), "cbf29ce484222325");
+        assert_eq!(hash(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_create_and_list() {
+        let path = copy_save("create");
+        let bytes = fs::read(&path).unwrap();
+        let retention = Retention::default();
+
+        let first = create(&path, &bytes, &retention).unwrap().unwrap();
+        assert_eq!(first.hash, hash(&bytes));
+        assert_eq!(first.size, bytes.len() as u64);
+        //Same contents, skipped
+        assert!(create(&path, &bytes, &retention).unwrap().is_none());
+
+        let mut edited = bytes.clone();
+        edited[0x08] ^= 0xFF;
+        std::thread::sleep(Duration::from_millis(2));
+        let second = create(&path, &edited, &retention).unwrap().unwrap();
+
+        let backups = list(&path).unwrap();
+        assert_eq!(backups, vec![second, first]);
+    }
+
+    #[test]
+    fn test_prune() {
+        let path = copy_save("prune");
+        let mut bytes = fs::read(&path).unwrap();
+        let keep_all = Retention {
+            keep: 100,
+            max_age_days: None,
+        };
+        for i in 0..5 {
+            bytes[0x08] = i;
+            create(&path, &bytes, &keep_all).unwrap();
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        assert_eq!(list(&path).unwrap().len(), 5);
+
+        let newest = list(&path).unwrap()[0].clone();
+        let removed = prune(
+            &path,
+            &Retention {
+                keep: 2,
+                max_age_days: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(removed.len(), 3);
+        assert_eq!(list(&path).unwrap()[0], newest);
+
+        //Everything is older than 0 days but the newest snapshot is always kept
+        prune(
+            &path,
+            &Retention {
+                keep: 2,
+                max_age_days: Some(0),
+            },
+        )
+        .unwrap();
+        assert_eq!(list(&path).unwrap(), vec![newest]);
+    }
+
+    #[test]
+    fn test_diff_and_restore() {
+        let path = copy_save("restore");
+        let original = fs::read(&path).unwrap();
+        let retention = Retention::default();
+        let backup = create(&path, &original, &retention).unwrap().unwrap();
+
+        let mut edited = original.clone();
+        edited[0x08] = 0x01;
+        edited[0x09] = 0x02;
+        fs::write(&path, &edited).unwrap();
+
+        let changes = diff_with(&path, &backup.id, &edited).unwrap();
+        assert_eq!(
+            changes,
+            vec![ByteChange {
+                offset: 0x08,
+                before: original[0x08..0x0A].to_vec(),
+                after: vec![0x01, 0x02],
+            }]
+        );
+
+        restore(&path, &backup.id, &retention).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), original);
+        //The edited file was snapshotted before being overwritten
+        assert!(list(&path).unwrap().iter().any(|b| b.hash == hash(&edited)));
+
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        let result = restore(&path, "missing", &retention);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "The backup missing does not exist.");
+        }
+
+        //A snapshot that isn't a save is never written over it
+        let broken = create(&path, &original[..0x100], &retention)
+            .unwrap()
+            .unwrap();
+        assert!(restore(&path, &broken.id, &retention).is_err());
+        assert_eq!(fs::read(&path).unwrap(), original);
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+    }
+}
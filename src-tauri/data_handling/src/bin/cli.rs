@@ -0,0 +1,560 @@
+// Headless front end for scripted save editing.
+// Every command prints its result as JSON on stdout, errors are printed as JSON on stderr
+// and the process exits with a non-zero code.
+use data_handling::{
+    appearance, backup,
+    enums::{
+        ArticleType, EquipSlot, Error, Imprint, Location, Origin, SlotShape, TypeFamily,
+        UpgradeType,
+    },
+    equipment::Equipment,
+    file::FileData,
+    patch, presets,
+    save::SaveData,
+    sheet,
+    stats::Attributes,
+};
+use serde_json::{json, Value};
+use std::{env, path::Path, process};
+
+const USAGE: &str =
+    "Usage: bbse-cli <SAVE> <COMMAND> [ARGS...] [--storage] [--force] [--output <PATH>]
+
+Commands:
+  info                               Print the whole parsed save
+  stats                              Print the character stats
+  set-stat <NAME> <VALUE>            Edit a stat by name (Vitality, Echoes, Level...)
+  change-origin <ORIGIN>             Change the origin, 0 to 8 or a name such as LoneSurvivor,
+                                     raising the attributes to its starting ones
+  derived-stats                      Print the level, Health, Stamina and level up cost of the attributes
+  defense                            Print the defenses of the equipped armor and what each piece
+                                     changes
+  equipment                          Print the article and inventory position of every equipment slot
+  equip <SLOT> <TYPE> <INDEX>        Equip an article of the inventory in a slot (leftHand1, rightHand1,
+                                     leftHand2, rightHand2, head, chest, hands, legs)
+  unequip <SLOT>                     Leave a slot empty
+  belt                               Print the quick items, item shortcuts (slots 0 to 5) and bullets
+  assign-quick-item <SLOT> <INDEX>   Put the consumable at INDEX on the belt
+  clear-quick-item <SLOT>            Empty a belt slot
+  assign-shortcut <SLOT> <INDEX>     Put the consumable at INDEX in an item shortcut
+  clear-shortcut <SLOT>              Empty an item shortcut
+  ready-belt                         Top up the Blood Vials and Quicksilver Bullets and fill the belt
+                                     with tools
+  set-attributes <VIT> <END> <STR> <SKL> <BLT> <ARC>
+                                     Set the attributes and the Level, Health and Stamina they lead to
+  respec <LEVEL> <VIT> <END> <STR> <SKL> <BLT> <ARC>
+                                     Redistribute the points at LEVEL, raising the attributes towards
+                                     the given ones without going below the origin
+  apply-preset <PRESETS> <NAME>      Apply the preset NAME from the PRESETS file
+  add-item <ID> <QUANTITY>           Add an item to the inventory (or the storage with --storage)
+  edit-item <ID> <QUANTITY>          Change the quantity of an item already in the inventory
+  add-weapon <ID> <LEVEL> [IMPRINT] [SHAPE...]
+                                     Add a weapon with its upgrade level, imprint (none, uncanny,
+                                     lost) and up to 5 slot shapes (radial, triangle, waning...)
+  add-armor <ID>                     Add an armor piece
+  create-upgrade <TYPE> <SHAPE> <SOURCE> [EFFECT...]
+                                     Create a gem or rune (gem, rune) with up to 6 effect ids,
+                                     the shape is Radial, Triangle, Waning, Circle, Droplet, - or Oath
+  remove-article <TYPE> <INDEX>      Remove an article (consumable, material, rightHand, armor...)
+  destroy-upgrade <ID>               Remove a gem or rune from the save, wherever it is
+  collect-orphaned-upgrades          Remove the gems and runes that are in no slot
+  validate                           Report everything the game can't produce, by severity
+  validate-upgrades                  List the gems and runes the game can't drop and why
+  legitimize-upgrade <ID>            Drop the effects of a gem or rune that break a rule
+  move-article <TYPE> <INDEX> <QUANTITY>
+                                     Move an article to the storage (or from it with --storage)
+  set-username <NAME>                Change the character name
+  get-flag <OFFSET>                  Print the flag byte at OFFSET from the flags aob
+  set-flag <OFFSET> <VALUE>          Overwrite the flag byte at OFFSET from the flags aob
+  get-flag-bit <OFFSET> <BIT>        Print the bit BIT, 0 to 7, of the flag byte at OFFSET
+  set-flag-bit <OFFSET> <BIT> <0|1>  Set or clear one bit of a flag byte, keeping the others
+  toggle-flag-bit <OFFSET> <BIT>     Flip one bit of a flag byte
+  kill-boss <NAME>                   Mark a boss as dead, changing only the bits of its flags
+  revive-boss <NAME>                 Mark a boss as alive, changing only the bits of its flags
+  flags                              Print every named flag and the state it is in
+  set-named-flag <NAME> <STATE>      Put the named flag NAME in STATE, if its prerequisites are met
+  teleport <X> <Y> <Z> <AREA> <BLOCK>
+                                     Move the character to the given coordinates and map
+  export-appearance <PATH>           Write the appearance bytes to PATH
+  import-appearance <PATH>           Read the appearance bytes from PATH
+  export-sheet <PATH>                Write the character sheet (JSON) to PATH
+  import-sheet <PATH>                Apply the character sheet at PATH to the save
+  diff <OTHER>                       Print what changed from <SAVE> to OTHER, region by region
+  make-patch <OTHER> <PATH>          Write the changes from <SAVE> to OTHER as a patch to PATH
+  apply-patch <PATH>                 Apply the patch at PATH, --force skips the conflict checks
+  backups                            List the snapshots of <SAVE>, newest first
+  diff-backup <ID>                   Print the byte ranges that differ between a snapshot and <SAVE>
+  restore-backup <ID>                Overwrite <SAVE> with a snapshot
+
+Commands that modify the save write it back to <SAVE>, or to --output if given.";
+
+struct Args {
+    save_path: String,
+    command: String,
+    params: Vec<String>,
+    is_storage: bool,
+    force: bool,
+    output: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut positional = Vec::new();
+    let mut is_storage = false;
+    let mut force = false;
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--storage" => is_storage = true,
+            "--force" => force = true,
+            "--output" | "-o" => match args.next() {
+                Some(path) => output = Some(path),
+                None => return Err("--output requires a path".to_string()),
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.len() < 2 {
+        return Err(USAGE.to_string());
+    }
+    let save_path = positional.remove(0);
+    let command = positional.remove(0);
+
+    Ok(Args {
+        save_path,
+        command,
+        params: positional,
+        is_storage,
+        force,
+        output,
+    })
+}
+
+fn param<T: std::str::FromStr>(params: &[String], index: usize, name: &str) -> Result<T, String> {
+    let raw = params
+        .get(index)
+        .ok_or_else(|| format!("Missing argument <{}>", name))?;
+    parse_number(raw).ok_or_else(|| format!("Invalid value for <{}>: {}", name, raw))
+}
+
+//The six attributes starting at params[from]
+fn attributes(params: &[String], from: usize) -> Result<Attributes, String> {
+    let mut values = [0; 6];
+    for (i, (value, name)) in values
+        .iter_mut()
+        .zip(["VIT", "END", "STR", "SKL", "BLT", "ARC"])
+        .enumerate()
+    {
+        *value = param(params, from + i, name)?;
+    }
+    Ok(Attributes::from(values))
+}
+
+//Accepts both decimal and 0x prefixed hexadecimal numbers
+fn parse_number<T: std::str::FromStr>(raw: &str) -> Option<T> {
+    match raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16)
+            .ok()
+            .and_then(|n| n.to_string().parse().ok()),
+        None => raw.parse().ok(),
+    }
+}
+
+fn run(args: Args) -> Result<Value, CliError> {
+    let mut save = SaveData::build(&args.save_path)?;
+    let params = &args.params;
+    let is_storage = args.is_storage;
+
+    let result = match args.command.as_str() {
+        "info" => return Ok(serde_json::to_value(&save)?),
+        "stats" => return Ok(serde_json::to_value(&save.stats)?),
+        "derived-stats" => return Ok(serde_json::to_value(save.derived_stats()?)?),
+        "defense" => return Ok(serde_json::to_value(save.defense()?)?),
+        "equipment" => {
+            let slots: Vec<Value> = EquipSlot::ALL
+                .into_iter()
+                .map(|slot| {
+                    json!({
+                        "slot": slot,
+                        "position": save.equipment.positions[slot as usize],
+                        "article": save.equipment.article_in(slot, &save.inventory),
+                    })
+                })
+                .collect();
+            return Ok(json!(slots));
+        }
+        "belt" => {
+            let consumables = save.inventory.articles.get(&ArticleType::Consumable);
+            let article_at = |handle: u32| {
+                consumables.and_then(|items| items.iter().find(|a| a.first_part == handle))
+            };
+            let equipment = &save.equipment;
+            return Ok(json!({
+                "quick_items": equipment.quick_items,
+                "selected_quick_item": equipment.selected_quick_item,
+                "bullets": Equipment::bullets(&save.inventory),
+                "shortcuts": equipment
+                    .shortcuts
+                    .iter()
+                    .zip(equipment.shortcut_handles)
+                    .map(|(position, handle)| {
+                        json!({ "position": position, "article": article_at(handle) })
+                    })
+                    .collect::<Vec<_>>(),
+            }));
+        }
+        "validate" => return Ok(serde_json::to_value(save.validate()?)?),
+        "validate-upgrades" => return Ok(serde_json::to_value(save.validate_upgrades()?)?),
+        "get-flag-bit" => {
+            let offset: usize = param(params, 0, "OFFSET")?;
+            let bit: u8 = param(params, 1, "BIT")?;
+            let value = save.file.get_flag_bit(offset, bit)?;
+            return Ok(json!({ "offset": offset, "bit": bit, "value": value }));
+        }
+        "flags" => return Ok(serde_json::to_value(save.flags()?)?),
+        "get-flag" => {
+            let offset: usize = param(params, 0, "OFFSET")?;
+            return Ok(json!({ "offset": offset, "value": save.file.get_flag(offset) }));
+        }
+        "diff" => {
+            let other = open_other(params)?;
+            return Ok(json!(patch::diff(&save, &other)?));
+        }
+        "make-patch" => {
+            let other = open_other(params)?;
+            let path = params.get(1).ok_or("Missing argument <PATH>")?;
+            let patch = patch::export(&save, &other, path)?;
+            return Ok(json!({ "exported": path, "changes": patch.changes.len() }));
+        }
+        "backups" => return Ok(json!(backup::list(&args.save_path)?)),
+        "diff-backup" => {
+            let id = params.first().ok_or("Missing argument <ID>")?;
+            let changes = backup::diff_with(&args.save_path, id, &save.file.bytes)?;
+            return Ok(json!(changes));
+        }
+        "restore-backup" => {
+            let id = params.first().ok_or("Missing argument <ID>")?;
+            backup::restore(&args.save_path, id, &Default::default())?;
+            return Ok(json!({ "restored": id }));
+        }
+        "export-appearance" => {
+            let path = params.first().ok_or("Missing argument <PATH>")?;
+            appearance::export(&save.file, path)?;
+            return Ok(json!({ "exported": path }));
+        }
+        "export-sheet" => {
+            let path = params.first().ok_or("Missing argument <PATH>")?;
+            sheet::export(&save, path)?;
+            return Ok(json!({ "exported": path }));
+        }
+        "set-stat" => {
+            let name = params.first().ok_or("Missing argument <NAME>")?;
+            let value: u32 = param(params, 1, "VALUE")?;
+            json!(save.edit_stat(name, value)?)
+        }
+        "change-origin" => {
+            let origin = parse_origin(params.first().ok_or("Missing argument <ORIGIN>")?)?;
+            json!(save.change_origin(origin)?)
+        }
+        "set-attributes" => json!(save.set_attributes(&attributes(params, 0)?)?),
+        "respec" => {
+            let level: u32 = param(params, 0, "LEVEL")?;
+            json!(save.respec(&attributes(params, 1)?, level)?)
+        }
+        "apply-preset" => {
+            let path = params.first().ok_or("Missing argument <PRESETS>")?;
+            let name = params.get(1).ok_or("Missing argument <NAME>")?;
+            let preset = presets::find(Path::new(path), name)?;
+            json!(save.apply_preset(&preset)?)
+        }
+        "add-item" => {
+            let id: u32 = param(params, 0, "ID")?;
+            let quantity: u32 = param(params, 1, "QUANTITY")?;
+            let inventory = match is_storage {
+                true => &mut save.storage,
+                false => &mut save.inventory,
+            };
+            inventory.add_item(&mut save.file, id, quantity, is_storage)?;
+            json!(inventory
+                .articles
+                .values()
+                .flatten()
+                .filter(|a| a.id == id)
+                .last())
+        }
+        "edit-item" => {
+            let id: u32 = param(params, 0, "ID")?;
+            let quantity: u32 = param(params, 1, "QUANTITY")?;
+            let inventory = match is_storage {
+                true => &mut save.storage,
+                false => &mut save.inventory,
+            };
+            //edit_item needs the first byte of the slot, look it up from the parsed inventory
+            let number = inventory
+                .articles
+                .values()
+                .flatten()
+                .find(|a| a.type_family == TypeFamily::Item && a.id == id)
+                .map(|a| a.number)
+                .ok_or_else(|| format!("The item {} is not in the inventory", id))?;
+            inventory.edit_item(&mut save.file, number, id, quantity, is_storage)?;
+            json!({ "id": id, "amount": quantity })
+        }
+        "add-weapon" => {
+            let id: u32 = param(params, 0, "ID")?;
+            let level: u8 = param(params, 1, "LEVEL")?;
+            let imprint = match params.get(2).map(|p| p.to_lowercase()).as_deref() {
+                None | Some("none") => None,
+                Some("uncanny") => Some(Imprint::Uncanny),
+                Some("lost") => Some(Imprint::Lost),
+                Some(other) => return Err(format!("Invalid value for <IMPRINT>: {}", other).into()),
+            };
+            let shapes = params
+                .iter()
+                .skip(3)
+                .map(|name| parse_shape(name))
+                .collect::<Result<Vec<SlotShape>, String>>()?;
+            let location = Location::from(is_storage);
+            json!(save.add_weapon(location, id, imprint, level, &shapes)?)
+        }
+        "add-armor" => {
+            let id: u32 = param(params, 0, "ID")?;
+            json!(save.add_armor(Location::from(is_storage), id)?)
+        }
+        "create-upgrade" => {
+            let upgrade_type = match params.first().map(|p| p.to_lowercase()).as_deref() {
+                Some("gem") => UpgradeType::Gem,
+                Some("rune") => UpgradeType::Rune,
+                Some(other) => return Err(format!("Invalid value for <TYPE>: {}", other).into()),
+                None => return Err("Missing argument <TYPE>".into()),
+            };
+            let shape = params.get(1).ok_or("Missing argument <SHAPE>")?;
+            let source: u32 = param(params, 2, "SOURCE")?;
+            if params.len() > 9 {
+                return Err("A gem or rune has at most 6 effects".into());
+            }
+            //The effects that aren't given are empty
+            let mut effects = [0xFFFFFFFF; 6];
+            for i in 3..params.len() {
+                effects[i - 3] = param(params, i, "EFFECT")?;
+            }
+            let location = Location::from(is_storage);
+            json!(save.create_upgrade(location, upgrade_type, shape, effects, source)?)
+        }
+        "move-article" => {
+            let name = params.first().ok_or("Missing argument <TYPE>")?;
+            let article_type = ArticleType::try_from(name.as_str())?;
+            let index: usize = param(params, 1, "INDEX")?;
+            let quantity: u32 = param(params, 2, "QUANTITY")?;
+            let from = Location::from(is_storage);
+            json!(save.move_article(from, article_type, index, quantity)?)
+        }
+        "equip" => {
+            let slot =
+                EquipSlot::try_from(params.first().ok_or("Missing argument <SLOT>")?.as_str())?;
+            let name = params.get(1).ok_or("Missing argument <TYPE>")?;
+            let article_type = ArticleType::try_from(name.as_str())?;
+            let index: usize = param(params, 2, "INDEX")?;
+            json!(save.equip(slot, article_type, index)?)
+        }
+        "unequip" => {
+            let slot =
+                EquipSlot::try_from(params.first().ok_or("Missing argument <SLOT>")?.as_str())?;
+            json!(save.unequip(slot)?)
+        }
+        "assign-quick-item" => {
+            let slot: usize = param(params, 0, "SLOT")?;
+            let index: usize = param(params, 1, "INDEX")?;
+            json!(save.assign_quick_item(slot, index)?)
+        }
+        "clear-quick-item" => json!(save.clear_quick_item(param(params, 0, "SLOT")?)?),
+        "assign-shortcut" => {
+            let slot: usize = param(params, 0, "SLOT")?;
+            let index: usize = param(params, 1, "INDEX")?;
+            json!(save.assign_shortcut(slot, index)?)
+        }
+        "clear-shortcut" => json!(save.clear_shortcut(param(params, 0, "SLOT")?)?),
+        "ready-belt" => json!(save.ready_belt()?),
+        "remove-article" => {
+            let name = params.first().ok_or("Missing argument <TYPE>")?;
+            let article_type = ArticleType::try_from(name.as_str())?;
+            let index: usize = param(params, 1, "INDEX")?;
+            json!(save.remove_article(Location::from(is_storage), article_type, index)?)
+        }
+        "destroy-upgrade" => {
+            let id: u32 = param(params, 0, "ID")?;
+            json!(save.destroy_upgrade(id)?)
+        }
+        "collect-orphaned-upgrades" => json!(save.collect_orphaned_upgrades()?),
+        "legitimize-upgrade" => {
+            let id: u32 = param(params, 0, "ID")?;
+            json!(save.legitimize_upgrade(id)?)
+        }
+        "set-username" => {
+            let name = params.first().ok_or("Missing argument <NAME>")?;
+            save.username.set(&mut save.file, name.to_string())?;
+            json!(save.username)
+        }
+        "set-flag" => {
+            let offset: usize = param(params, 0, "OFFSET")?;
+            let value: u8 = param(params, 1, "VALUE")?;
+            save.file.set_flag(offset, value);
+            json!({ "offset": offset, "value": save.file.get_flag(offset) })
+        }
+        "set-flag-bit" => {
+            let offset: usize = param(params, 0, "OFFSET")?;
+            let bit: u8 = param(params, 1, "BIT")?;
+            let value: u8 = param(params, 2, "VALUE")?;
+            save.file.set_flag_bit(offset, bit, value != 0)?;
+            json!({ "offset": offset, "value": save.file.get_flag(offset) })
+        }
+        "toggle-flag-bit" => {
+            let offset: usize = param(params, 0, "OFFSET")?;
+            let bit: u8 = param(params, 1, "BIT")?;
+            let value = save.file.toggle_flag_bit(offset, bit)?;
+            json!({ "offset": offset, "bit": bit, "value": value })
+        }
+        "kill-boss" | "revive-boss" => {
+            let name = params.first().ok_or("Missing argument <NAME>")?;
+            json!(save.set_boss_dead(name, args.command == "kill-boss")?)
+        }
+        "set-named-flag" => {
+            let name = params.first().ok_or("Missing argument <NAME>")?;
+            let state = params.get(1).ok_or("Missing argument <STATE>")?;
+            json!(save.set_named_flag(name, state)?)
+        }
+        "teleport" => {
+            let x: f32 = param(params, 0, "X")?;
+            let y: f32 = param(params, 1, "Y")?;
+            let z: f32 = param(params, 2, "Z")?;
+            let area: u8 = param(params, 3, "AREA")?;
+            let block: u8 = param(params, 4, "BLOCK")?;
+            save.position
+                .teleport(&mut save.file, x, y, z, [area, block]);
+            json!(save.position)
+        }
+        "import-appearance" => {
+            let path = params.first().ok_or("Missing argument <PATH>")?;
+            appearance::import(&mut save.file, path)?;
+            json!({ "imported": path })
+        }
+        "apply-patch" => {
+            let path = params.first().ok_or("Missing argument <PATH>")?;
+            let patch = patch::import(&mut save, path, args.force)?;
+            json!({ "stats": patch.stats.len(), "changes": patch.changes.len() })
+        }
+        "import-sheet" => {
+            let path = params.first().ok_or("Missing argument <PATH>")?;
+            json!(sheet::import(&mut save, path)?)
+        }
+        other => {
+            return Err(CliError::Usage(format!(
+                "Unknown command: {}\n\n{}",
+                other, USAGE
+            )))
+        }
+    };
+
+    let output = args.output.as_deref().unwrap_or(&args.save_path);
+    save.save(output)?;
+
+    Ok(json!({ "saved": output, "result": result }))
+}
+
+//Slot shapes by name, ignoring the case
+fn parse_shape(name: &str) -> Result<SlotShape, String> {
+    let shapes = [
+        SlotShape::Closed,
+        SlotShape::Radial,
+        SlotShape::Triangle,
+        SlotShape::Waning,
+        SlotShape::Circle,
+        SlotShape::Droplet,
+    ];
+    shapes
+        .into_iter()
+        .find(|shape| format!("{:?}", shape).eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Invalid value for <SHAPE>: {}", name))
+}
+
+fn parse_origin(raw: &str) -> Result<Origin, String> {
+    if let Some(value) = parse_number::<u32>(raw) {
+        return Origin::try_from(value).map_err(|e| e.to_string());
+    }
+    let name: String = raw.split_whitespace().collect();
+    (0..9)
+        .filter_map(|value| Origin::try_from(value).ok())
+        .find(|origin| format!("{:?}", origin).eq_ignore_ascii_case(&name))
+        .ok_or_else(|| format!("Invalid value for <ORIGIN>: {}", raw))
+}
+
+//The second save of diff and make-patch, read without making a backup of it
+fn open_other(params: &[String]) -> Result<SaveData, CliError> {
+    let path = params.first().ok_or("Missing argument <OTHER>")?;
+    Ok(SaveData::from_file(FileData::from_bytes(std::fs::read(
+        path,
+    )?)?)?)
+}
+
+//Usage mistakes are reported with the same shape as the library errors
+enum CliError {
+    Usage(String),
+    Save(Error),
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::Usage(message)
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(message: &str) -> Self {
+        CliError::Usage(message.to_string())
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(error: Error) -> Self {
+        CliError::Save(error)
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(error: std::io::Error) -> Self {
+        CliError::Save(error.into())
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(error: serde_json::Error) -> Self {
+        CliError::Save(error.into())
+    }
+}
+
+impl CliError {
+    fn to_json(&self) -> Value {
+        match self {
+            CliError::Usage(message) => json!({ "kind": "Usage", "message": message }),
+            CliError::Save(error) => json!(error),
+        }
+    }
+}
+
+fn main() {
+    let result = parse_args().map_err(CliError::Usage).and_then(run);
+
+    match result {
+        Ok(value) => println!("{}", value),
+        Err(error) => {
+            eprintln!("{}", json!({ "error": error.to_json() }));
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Boss flags, used to check and change which bosses are dead.
+use super::{enums::Error, file::FileData, resources};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Flag {
+    rel_offset: usize,
+    dead_value: u8,
+    alive_value: u8,
+    current_value: u8,
+}
+
+impl Flag {
+    //Bits of the byte the flag owns, the others belong to events sharing it
+    fn bits(&self) -> u8 {
+        self.current_value & (self.dead_value | self.alive_value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Boss {
+    name: String,
+    flags: Vec<Flag>,
+}
+
+impl Boss {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    ///A boss is dead when the bits of every one of its flags have the dead value
+    pub fn is_dead(&self) -> bool {
+        self.flags.iter().all(|f| f.bits() == f.dead_value)
+    }
+
+    ///Offsets and values of the flags that are neither dead nor alive. Only the bits of the
+    ///dead and alive values are compared, the others belong to events sharing the byte
+    pub fn unexpected_flags(&self) -> Vec<(usize, u8)> {
+        self.flags
+            .iter()
+            .filter(|f| f.bits() != f.dead_value && f.bits() != f.alive_value)
+            .map(|f| (f.rel_offset, f.bits()))
+            .collect()
+    }
+
+    pub fn kill(&mut self, file: &mut FileData) -> Result<(), Error> {
+        self.set_dead(file, true)
+    }
+
+    pub fn revive(&mut self, file: &mut FileData) -> Result<(), Error> {
+        self.set_dead(file, false)
+    }
+
+    ///Writes the dead or alive value of every flag bit by bit, the bits of the byte that
+    ///aren't part of either value are left as they are
+    pub fn set_dead(&mut self, file: &mut FileData, dead: bool) -> Result<(), Error> {
+        for f in &self.flags {
+            let value = match dead {
+                true => f.dead_value,
+                false => f.alive_value,
+            };
+            for bit in 0..8 {
+                let mask = 1 << bit;
+                if (f.dead_value | f.alive_value) & mask != 0 {
+                    file.set_flag_bit(f.rel_offset, bit, value & mask != 0)?;
+                }
+            }
+        }
+        //Read back once every flag is written, some of them may share a byte
+        for f in &mut self.flags {
+            f.current_value = file.try_get_flag(f.rel_offset)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the state of every boss in the embedded bosses list.
+pub fn new(file: &FileData) -> Result<Vec<Boss>, Error> {
+    let bosses_str = resources::BOSSES;
+
+    let mut bosses: Vec<Boss> = serde_json::from_str(bosses_str)?;
+    for b in &mut bosses {
+        for f in &mut b.flags {
+            f.current_value = file.try_get_flag(f.rel_offset)?;
+        }
+    }
+
+    Ok(bosses)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::build_save_data;
+
+    #[test]
+    fn bosses_kill_and_revive() {
+        let mut save = build_save_data("testsave0");
+        let file = &mut save.file;
+        let boss = save
+            .bosses
+            .iter_mut()
+            .find(|b| b.name() == "Cleric Beast")
+            .unwrap();
+
+        //Cleric Beast is the bit 3 (value 8) of its byte, the other bits are unrelated events
+        let offset = boss.flags[0].rel_offset;
+        file.set_flag(offset, 0b1010_0001);
+        boss.kill(file).unwrap();
+        assert!(boss.is_dead());
+        assert_eq!(file.get_flag(offset), 0b1010_1001);
+        assert!(boss.unexpected_flags().is_empty());
+
+        boss.revive(file).unwrap();
+        assert!(!boss.is_dead());
+        assert_eq!(file.get_flag(offset), 0b1010_0001);
+        assert_eq!(boss.flags[0].current_value, 0b1010_0001);
+    }
+
+    #[test]
+    fn bosses_keep_other_bits() {
+        let mut save = build_save_data("testsave0");
+        let file = &mut save.file;
+        for boss in &mut save.bosses {
+            for other_bits in [0x00, 0xFF, 0b0101_1010] {
+                for f in &boss.flags {
+                    file.set_flag(f.rel_offset, other_bits);
+                }
+                for dead in [true, false, true] {
+                    boss.set_dead(file, dead).unwrap();
+                    assert_eq!(boss.is_dead(), dead, "{}", boss.name);
+                    for f in &boss.flags {
+                        let owned = f.dead_value | f.alive_value;
+                        let byte = file.get_flag(f.rel_offset);
+                        assert_eq!(byte & !owned, other_bits & !owned, "{}", boss.name);
+                        let expected = if dead { f.dead_value } else { f.alive_value };
+                        assert_eq!(byte & owned, expected, "{}", boss.name);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Distances between the regions of a save, most of them relative to the username.
+//Distance between the username and the beginning of the inventory
+pub const USERNAME_TO_INV_OFFSET: usize = 469;
+
+//Distance between the username and the inventory positions of the equipped articles
+pub const USERNAME_TO_EQUIPPED_POSITIONS: usize = 297;
+
+//Distance between the username and the handles of the equipped articles
+pub const USERNAME_TO_EQUIPPED_HANDLES: usize = 389;
+
+//Distance between the username and the inventory positions of the quick items on the belt
+pub const USERNAME_TO_QUICK_ITEMS: usize = 34365;
+
+//Distance between the username and the inventory position of the selected quick item
+pub const USERNAME_TO_SELECTED_QUICK_ITEM: usize = 34409;
+
+//Distance between the username and the handles and positions of the item shortcuts
+pub const USERNAME_TO_ITEM_SHORTCUTS: usize = 34413;
+
+//Distance between the username and the beginning of the key items inventory
+pub const USERNAME_TO_KEY_INV_OFFSET: usize = 32201;
+
+//Distance between the username and the beginning of the storage inventory
+pub const INV_TO_STORAGE_OFFSET: usize = 34268;
+
+// Distance between the username and the first inventory counter
+pub const USERNAME_TO_FIRST_INVENTORY_COUNTER: usize = 453;
+
+// Distance between the username and the second inventory counter
+pub const USERNAME_TO_SECOND_INVENTORY_COUNTER: usize = 34257;
+
+// Distance between the username and the first inventory counter
+pub const USERNAME_TO_FIRST_STORAGE_COUNTER: usize = 34737;
+
+// Distance between the username and the second inventory counter
+pub const USERNAME_TO_SECOND_STORAGE_COUNTER: usize = 68541;
+
+// Distance between the username and the aob used for flags
+pub const USERNAME_TO_AOB: usize = 68545;
+
+// Distance between the username and the isz glitch bytes
+pub const USERNAME_TO_ISZ_GLITCH: usize = 72082;
+
+//Distance from the start of the save to the first gem/rune
+pub const START_TO_UPGRADE: usize = 84;
+
+//Bytes of an unused entry among the upgrades and slots blocks, those are 40B and 60B
+pub const EMPTY_ENTRY: [u8; 8] = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF];
+
+//Distance between the end of the upgrades and slots blocks and the username
+pub const BLOCKS_END_TO_USERNAME: usize = 151;
+
+//Durability of a new weapon when there is no other copy of it, the game restores it when resting
+pub const DEFAULT_DURABILITY: u32 = 100;
+
+//Amount of bytes used to store the character appearance
+pub const APPEARANCE_BYTES_AMOUNT: usize = 0xEB;
+
+//Amount of empty slots that can be detected while parsing the inventory before considering it finished
+pub const MAX_EMPTY_INV_SLOTS: usize = 20;
+
+//Amount of edits kept in the history before the oldest ones are dropped
+pub const HISTORY_LIMIT: usize = 100;
+
+//Id of an empty effect of a gem or rune
+pub const NO_EFFECT: u32 = 0xFFFFFFFF;
+
+//Effects of the oath runes start at 2100000, the others at 1100000
+pub const OATH_EFFECTS_START: u32 = 2_000_000;
+
+//Highest value an attribute can be leveled up to
+pub const MAX_ATTRIBUTE: u32 = 99;
+
+//Highest upgrade level of a weapon
+pub const MAX_UPGRADE_LEVEL: u8 = 10;
+
+//Most copies of an item the inventory holds, some items stop earlier
+pub const MAX_INVENTORY_AMOUNT: u32 = 99;
+
+//Most copies of materials, Blood Vials and Quicksilver Bullets the storage holds, the others stop at 99
+pub const MAX_STORAGE_AMOUNT: u32 = 600;
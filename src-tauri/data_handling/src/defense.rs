@@ -0,0 +1,223 @@
+//! Defenses, resistances and beasthood of the equipped armor, and how wearing every other armor
+//! piece would change them. Only what the armor grants is counted, not the level nor the
+//! attributes.
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
+
+use super::{
+    article::Article,
+    enums::{ArmorSlot, ArticleType, Error, TypeFamily},
+    save::SaveData,
+};
+
+//Where armors.json keeps each value, in the order of Defense. Beasthood isn't in a group
+const FIELDS: [(&str, &str); 11] = [
+    ("physicalDefense", "physical"),
+    ("physicalDefense", "blunt"),
+    ("physicalDefense", "thrust"),
+    ("physicalDefense", "blood"),
+    ("elementalDefense", "arcane"),
+    ("elementalDefense", "fire"),
+    ("elementalDefense", "bolt"),
+    ("resistance", "slowPoison"),
+    ("resistance", "rapidPoison"),
+    ("resistance", "frenzy"),
+    ("beasthood", ""),
+];
+
+/// Defenses, resistances and beasthood. Differences between two sets can be negative.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Defense {
+    pub physical: i32,
+    pub blunt: i32,
+    pub thrust: i32,
+    pub blood: i32,
+    pub arcane: i32,
+    pub fire: i32,
+    pub bolt: i32,
+    pub slow_poison: i32,
+    pub rapid_poison: i32,
+    pub frenzy: i32,
+    pub beasthood: i32,
+}
+
+impl Defense {
+    pub fn values(&self) -> [i32; 11] {
+        [
+            self.physical,
+            self.blunt,
+            self.thrust,
+            self.blood,
+            self.arcane,
+            self.fire,
+            self.bolt,
+            self.slow_poison,
+            self.rapid_poison,
+            self.frenzy,
+            self.beasthood,
+        ]
+    }
+
+    /// Defense of an armor piece, from the values of armors.json in its extra info.
+    pub fn of_armor(article: &Article) -> Result<Defense, Error> {
+        let extra_info = match &article.info.extra_info {
+            Some(extra_info) if article.article_type == ArticleType::Armor => extra_info,
+            Some(_) => return Err(Error::NotAnArmor { id: article.id }),
+            None => {
+                return Err(Error::ArticleInfoNotFound {
+                    id: article.id,
+                    family: TypeFamily::Armor,
+                })
+            }
+        };
+        let mut values = [0; 11];
+        for (value, (group, name)) in values.iter_mut().zip(FIELDS) {
+            let field = match name {
+                "" => &extra_info[group],
+                _ => &extra_info[group][name],
+            };
+            *value = field.as_i64().unwrap_or(0) as i32;
+        }
+        Ok(Defense::from(values))
+    }
+}
+
+impl From<[i32; 11]> for Defense {
+    fn from(values: [i32; 11]) -> Self {
+        Defense {
+            physical: values[0],
+            blunt: values[1],
+            thrust: values[2],
+            blood: values[3],
+            arcane: values[4],
+            fire: values[5],
+            bolt: values[6],
+            slow_poison: values[7],
+            rapid_poison: values[8],
+            frenzy: values[9],
+            beasthood: values[10],
+        }
+    }
+}
+
+impl Add for Defense {
+    type Output = Defense;
+    fn add(self, other: Defense) -> Defense {
+        let mut values = self.values();
+        for (value, other) in values.iter_mut().zip(other.values()) {
+            *value += other;
+        }
+        Defense::from(values)
+    }
+}
+
+impl Sub for Defense {
+    type Output = Defense;
+    fn sub(self, other: Defense) -> Defense {
+        let mut values = self.values();
+        for (value, other) in values.iter_mut().zip(other.values()) {
+            *value -= other;
+        }
+        Defense::from(values)
+    }
+}
+
+/// What wearing an armor piece of the inventory instead of the one in its slot would lead to.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ArmorSwap {
+    pub id: u32,
+    pub name: String,
+    pub index: usize,
+    pub slot: ArmorSlot,
+    pub equipped: bool,
+    pub armor: Defense, //Of the whole set with this piece worn
+    pub change: Defense,
+}
+
+/// Result of [`defense`], `armor` adds up the equipped pieces.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DefenseReport {
+    pub armor: Defense,
+    pub swaps: Vec<ArmorSwap>,
+}
+
+/// Defense of the equipped armor, and the change every other armor piece of the inventory would
+/// make in place of the one in its slot.
+pub fn defense(save: &SaveData) -> Result<DefenseReport, Error> {
+    let mut worn = [Defense::default(); 4];
+    for (defense, slot) in worn.iter_mut().zip([
+        ArmorSlot::Head,
+        ArmorSlot::Chest,
+        ArmorSlot::Hands,
+        ArmorSlot::Legs,
+    ]) {
+        if let Some(armor) = save.equipment.armor_in(slot, &save.inventory) {
+            *defense = Defense::of_armor(armor)?;
+        }
+    }
+    let armor = worn.iter().fold(Defense::default(), |sum, d| sum + *d);
+
+    let mut swaps = Vec::new();
+    for article in save
+        .inventory
+        .articles
+        .get(&ArticleType::Armor)
+        .into_iter()
+        .flatten()
+    {
+        let slot = ArmorSlot::of(article.id)?;
+        let change = Defense::of_armor(article)? - worn[slot as usize];
+        swaps.push(ArmorSwap {
+            id: article.id,
+            name: article.info.item_name.clone(),
+            index: article.index,
+            slot,
+            equipped: save.equipment.armor[slot as usize] == article.first_part,
+            armor: armor + change,
+            change,
+        });
+    }
+
+    Ok(DefenseReport { armor, swaps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::build_save_data;
+
+    #[test]
+    fn defense_report() {
+        //testsave7, a new Milquetoast in the Foreign set
+        let save = build_save_data("testsave7");
+        let report = save.defense().unwrap();
+        //Black Hood, Foreign Garb, Sullied Bandage and Foreign Trousers
+        let armor = save.inventory.articles[&ArticleType::Armor]
+            .iter()
+            .filter(|a| save.equipment.armor.contains(&a.first_part))
+            .map(|a| Defense::of_armor(a).unwrap())
+            .fold(Defense::default(), |sum, d| sum + d);
+        assert_eq!(report.armor, armor);
+        assert!(report.armor.physical > 0);
+        let worn: Vec<_> = report.swaps.iter().filter(|s| s.equipped).collect();
+        assert_eq!(worn.len(), 4);
+        assert!(worn.iter().all(|s| s.change == Defense::default()));
+
+        //testsave2 wears nothing on its head, a hat adds all of its defense
+        let save = build_save_data("testsave2");
+        let report = save.defense().unwrap();
+        let hat = &save.inventory.articles[&ArticleType::Armor]
+            .iter()
+            .find(|a| a.info.item_name == "Hunter Hat")
+            .unwrap();
+        let swap = report.swaps.iter().find(|s| s.id == hat.id).unwrap();
+        assert_eq!(swap.slot, ArmorSlot::Head);
+        assert!(!swap.equipped);
+        assert_eq!(swap.change, Defense::of_armor(hat).unwrap());
+        assert_eq!(swap.armor, report.armor + swap.change);
+
+        let result = Defense::of_armor(&save.inventory.articles[&ArticleType::RightHand][0]);
+        assert_eq!(result.unwrap_err().kind(), "NotAnArmor");
+    }
+}
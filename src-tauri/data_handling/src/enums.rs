@@ -0,0 +1,1047 @@
+//! Shared enums and the crate error type.
+use super::constants::{APPEARANCE_BYTES_AMOUNT, MAX_ATTRIBUTE};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::{fmt, io};
+
+/// Error returned by every fallible operation of the crate.
+///
+/// It serializes as `{ "kind": ..., "message": ..., "details": { ... } }`, where
+/// `kind` is the variant name and `details` holds its fields, so callers on the other
+/// side of a Tauri command can react to specific failures.
+#[derive(Debug)]
+pub enum Error {
+    IoError(io::Error),
+    JsonError(serde_json::Error),
+    EmptyFile,
+    //A region of the save could not be located
+    OffsetNotFound {
+        region: &'static str,
+    },
+    //The save ends before a region that should be in it
+    TruncatedFile {
+        region: &'static str,
+        offset: usize,
+        size: usize,
+    },
+    InvalidArticleType {
+        name: String,
+    },
+    ArticleNotFound {
+        number: u8,
+        id: u32,
+        location: Location,
+    },
+    //The id is not in the embedded resources
+    ArticleInfoNotFound {
+        id: u32,
+        family: TypeFamily,
+    },
+    NoArticlesOfType {
+        article_type: ArticleType,
+    },
+    InvalidArticleIndex {
+        article_type: ArticleType,
+        index: usize,
+    },
+    KeyItem {
+        id: u32,
+    },
+    NotAWeapon {
+        id: u32,
+    },
+    NotAnArmor {
+        id: u32,
+    },
+    InvalidEquipSlot {
+        name: String,
+    },
+    //The article is not of the type the slot takes
+    WrongEquipSlot {
+        id: u32,
+        slot: EquipSlot,
+    },
+    //There is no bare fist or [CUT] armor in the inventory to leave the slot empty with
+    NothingToUnequipWith {
+        slot: EquipSlot,
+    },
+    InvalidBeltSlot {
+        slot: usize,
+    },
+    InvalidWeaponMods {
+        second_part: u32,
+    },
+    InvalidUpgradeLevel {
+        level: u8,
+    },
+    InvalidIdLength {
+        expected: usize,
+        found: usize,
+    },
+    //The block with the slots of a weapon or armor could not be found
+    SlotsNotFound {
+        first_part: u32,
+        second_part: u32,
+    },
+    NoSlots {
+        id: u32,
+    },
+    InvalidSlotIndex {
+        id: u32,
+        index: usize,
+    },
+    SlotOccupied {
+        id: u32,
+        index: usize,
+    },
+    SlotEmpty {
+        id: u32,
+        index: usize,
+    },
+    InvalidShape {
+        bytes: [u8; 4],
+    },
+    InvalidShapeName {
+        name: String,
+        upgrade_type: UpgradeType,
+    },
+    InvalidUpgradeType {
+        byte: u8,
+    },
+    UpgradeNotFound {
+        id: u32,
+    },
+    NoUpgradesOfType {
+        upgrade_type: UpgradeType,
+    },
+    InvalidUpgradeIndex {
+        upgrade_type: UpgradeType,
+        index: usize,
+    },
+    EffectNotFound {
+        id: u32,
+    },
+    InvalidEffectIndex {
+        index: usize,
+    },
+    InventoryFull {
+        location: Location,
+    },
+    InvalidUsernameLength {
+        length: usize,
+    },
+    InvalidAppearanceSize {
+        size: usize,
+    },
+    NothingToUndo,
+    NothingToRedo,
+    BackupNotFound {
+        id: String,
+    },
+    //The written file doesn't parse back to the edited model
+    VerificationFailed {
+        section: &'static str,
+    },
+    //A character sheet or patch written by a different version of the format
+    UnsupportedVersion {
+        format: &'static str,
+        version: u32,
+        supported: u32,
+    },
+    //The bytes a patch replaces are not the ones in the save
+    PatchConflict {
+        region: &'static str,
+        offset: isize,
+    },
+    //Growing a region would push data past the end of the save
+    SaveFull {
+        needed: usize,
+    },
+    InvalidQuantity {
+        id: u32,
+        quantity: u32,
+        available: u32,
+    },
+    //Merging into a stack would take it past the most the location holds
+    StackFull {
+        id: u32,
+        quantity: u32,
+        room: u32,
+        location: Location,
+    },
+    //None of the effects of the upgrade can be kept
+    UnfixableUpgrade {
+        id: u32,
+    },
+    StatNotFound {
+        name: String,
+    },
+    InvalidAttribute {
+        name: String,
+        value: u32,
+    },
+    //The attribute is lower than what the origin starts with
+    AttributeBelowOrigin {
+        name: String,
+        value: u32,
+        min: u32,
+    },
+    InvalidOrigin {
+        value: u32,
+    },
+    InvalidLevel {
+        level: u32,
+        min: u32,
+        max: u32,
+    },
+    PresetNotFound {
+        name: String,
+    },
+    FlagNotFound {
+        name: String,
+    },
+    BossNotFound {
+        name: String,
+    },
+    //Flag bits go from 0 to 7
+    InvalidFlagBit {
+        bit: u8,
+    },
+    InvalidFlagState {
+        name: String,
+        state: String,
+    },
+    //A flag the one being set depends on isn't in the state it needs
+    FlagPrerequisite {
+        name: String,
+        prerequisite: String,
+        state: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoError(err) => write!(f, "I/0 error: {}", err),
+            Error::JsonError(err) => write!(f, "JSON SERDES error: {}", err),
+            Error::EmptyFile => write!(f, "The selected file is empty."),
+            Error::OffsetNotFound { region } => write!(f, "Failed to find the {}.", region),
+            Error::ArticleNotFound {
+                number,
+                id,
+                location,
+            } => write!(
+                f,
+                "The article {} (number {:#04x}) was not found in the {}.",
+                id, number, location
+            ),
+            Error::ArticleInfoNotFound { id, family } => {
+                write!(f, "Failed to find info for the {} {}.", family, id)
+            }
+            Error::NoArticlesOfType { article_type } => {
+                write!(f, "There are no articles of type {:?}.", article_type)
+            }
+            Error::InvalidArticleIndex {
+                article_type,
+                index,
+            } => write!(
+                f,
+                "There is no {:?} article at index {}.",
+                article_type, index
+            ),
+            Error::KeyItem { id } => write!(f, "The key item {} cannot be edited.", id),
+            Error::NotAWeapon { id } => write!(f, "The article {} must be a weapon.", id),
+            Error::NotAnArmor { id } => write!(f, "The article {} must be an armor.", id),
+            Error::InvalidEquipSlot { name } => write!(f, "Invalid equipment slot {}.", name),
+            Error::WrongEquipSlot { id, slot } => {
+                write!(f, "The article {} cannot be equipped in the {} slot.", id, slot)
+            }
+            Error::NothingToUnequipWith { slot } => write!(
+                f,
+                "The inventory has nothing to leave the {} slot empty with.",
+                slot
+            ),
+            Error::InvalidBeltSlot { slot } => write!(f, "Invalid belt slot {}.", slot),
+            Error::InvalidWeaponMods { second_part } => {
+                write!(
+                    f,
+                    "Invalid weapon mods in the second part {:#x}.",
+                    second_part
+                )
+            }
+            Error::InvalidUpgradeLevel { level } => {
+                write!(f, "Upgrade level {} cannot be bigger than 10.", level)
+            }
+            Error::InvalidIdLength { expected, found } => write!(
+                f,
+                "The new id must be {} bytes long, got {}.",
+                expected, found
+            ),
+            Error::SlotsNotFound {
+                first_part,
+                second_part,
+            } => write!(
+                f,
+                "Failed to find the slots of the article {:#x} {:#x}.",
+                first_part, second_part
+            ),
+            Error::NoSlots { id } => write!(f, "The article {} has no slots.", id),
+            Error::InvalidSlotIndex { id, index } => {
+                write!(f, "The article {} has no slot {}.", id, index)
+            }
+            Error::SlotOccupied { id, index } => {
+                write!(
+                    f,
+                    "The slot {} of the article {} already has a gem.",
+                    index, id
+                )
+            }
+            Error::SlotEmpty { id, index } => {
+                write!(
+                    f,
+                    "The slot {} of the article {} does not have a gem.",
+                    index, id
+                )
+            }
+            Error::InvalidShape { bytes } => write!(f, "Invalid shape {:02x?}.", bytes),
+            Error::InvalidShapeName { name, upgrade_type } => {
+                write!(f, "Invalid shape \"{}\" for a {:?}.", name, upgrade_type)
+            }
+            Error::InvalidUpgradeType { byte } => write!(f, "Invalid upgrade type {:#04x}.", byte),
+            Error::UpgradeNotFound { id } => write!(f, "Failed to find the upgrade {:#x}.", id),
+            Error::NoUpgradesOfType { upgrade_type } => {
+                write!(f, "There are no upgrades of type {:?}.", upgrade_type)
+            }
+            Error::InvalidUpgradeIndex {
+                upgrade_type,
+                index,
+            } => write!(f, "There is no {:?} at index {}.", upgrade_type, index),
+            Error::EffectNotFound { id } => write!(f, "Failed to find info for the effect {}.", id),
+            Error::InvalidEffectIndex { index } => write!(f, "There is no effect {}.", index),
+            Error::InventoryFull { location } => write!(f, "The {} is full.", location),
+            Error::InvalidUsernameLength { length } => write!(
+                f,
+                "The new username must have between 1 and 16 characters, got {}.",
+                length
+            ),
+            Error::TruncatedFile {
+                region,
+                offset,
+                size,
+            } => write!(
+                f,
+                "The save is truncated, the {} ends at {:#x} but the file is only {:#x} bytes long.",
+                region, offset, size
+            ),
+            Error::InvalidArticleType { name } => write!(f, "Invalid article type {}.", name),
+            Error::NothingToUndo => write!(f, "There are no edits to undo."),
+            Error::NothingToRedo => write!(f, "There are no edits to redo."),
+            Error::BackupNotFound { id } => write!(f, "The backup {} does not exist.", id),
+            Error::VerificationFailed { section } => write!(
+                f,
+                "The written save doesn't match the edited {}, the original file was left untouched.",
+                section
+            ),
+            Error::UnsupportedVersion {
+                format,
+                version,
+                supported,
+            } => write!(
+                f,
+                "The {} has version {}, only version {} is supported.",
+                format, version, supported
+            ),
+            Error::PatchConflict { region, offset } => write!(
+                f,
+                "The patch doesn't match this save, the {} differs at offset {}.",
+                region, offset
+            ),
+            Error::SaveFull { needed } => write!(
+                f,
+                "There is no room left in the save, {} more bytes are needed.",
+                needed
+            ),
+            Error::InvalidQuantity {
+                id,
+                quantity,
+                available,
+            } => write!(
+                f,
+                "Cannot move {} of the article {}, there are {}.",
+                quantity, id, available
+            ),
+            Error::StackFull {
+                id,
+                quantity,
+                room,
+                location,
+            } => write!(
+                f,
+                "Cannot move {} of the article {}, its stack in the {} only has room for {} more.",
+                quantity, id, location, room
+            ),
+            Error::UnfixableUpgrade { id } => write!(
+                f,
+                "The upgrade {:#x} has no effect the game can drop, it cannot be fixed.",
+                id
+            ),
+            Error::StatNotFound { name } => write!(f, "Failed to find the stat {}.", name),
+            Error::InvalidAttribute { name, value } => write!(
+                f,
+                "{} must be between 1 and {}, got {}.",
+                name, MAX_ATTRIBUTE, value
+            ),
+            Error::AttributeBelowOrigin { name, value, min } => write!(
+                f,
+                "{} cannot be lower than {} for this origin, got {}.",
+                name, min, value
+            ),
+            Error::InvalidOrigin { value } => write!(f, "Invalid origin {}.", value),
+            Error::InvalidLevel { level, min, max } => write!(
+                f,
+                "The level must be between {} and {}, got {}.",
+                min, max, level
+            ),
+            Error::PresetNotFound { name } => write!(f, "There is no preset named {}.", name),
+            Error::FlagNotFound { name } => write!(f, "There is no flag named {}.", name),
+            Error::BossNotFound { name } => write!(f, "There is no boss named {}.", name),
+            Error::InvalidFlagBit { bit } => {
+                write!(f, "A flag bit must be between 0 and 7, got {}.", bit)
+            }
+            Error::InvalidFlagState { name, state } => {
+                write!(f, "The flag {} has no state {}.", name, state)
+            }
+            Error::FlagPrerequisite {
+                name,
+                prerequisite,
+                state,
+            } => write!(
+                f,
+                "{} needs {} to be {} first.",
+                name, prerequisite, state
+            ),
+            Error::InvalidAppearanceSize { size } => write!(
+                f,
+                "An appearance must be {} bytes long, got {}.",
+                APPEARANCE_BYTES_AMOUNT, size
+            ),
+        }
+    }
+}
+
+impl Error {
+    //Name of the variant, used as the "kind" field when serialized
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::IoError(_) => "IoError",
+            Error::JsonError(_) => "JsonError",
+            Error::EmptyFile => "EmptyFile",
+            Error::OffsetNotFound { .. } => "OffsetNotFound",
+            Error::ArticleNotFound { .. } => "ArticleNotFound",
+            Error::ArticleInfoNotFound { .. } => "ArticleInfoNotFound",
+            Error::NoArticlesOfType { .. } => "NoArticlesOfType",
+            Error::InvalidArticleIndex { .. } => "InvalidArticleIndex",
+            Error::KeyItem { .. } => "KeyItem",
+            Error::NotAWeapon { .. } => "NotAWeapon",
+            Error::NotAnArmor { .. } => "NotAnArmor",
+            Error::InvalidEquipSlot { .. } => "InvalidEquipSlot",
+            Error::WrongEquipSlot { .. } => "WrongEquipSlot",
+            Error::NothingToUnequipWith { .. } => "NothingToUnequipWith",
+            Error::InvalidBeltSlot { .. } => "InvalidBeltSlot",
+            Error::InvalidWeaponMods { .. } => "InvalidWeaponMods",
+            Error::InvalidUpgradeLevel { .. } => "InvalidUpgradeLevel",
+            Error::InvalidIdLength { .. } => "InvalidIdLength",
+            Error::SlotsNotFound { .. } => "SlotsNotFound",
+            Error::NoSlots { .. } => "NoSlots",
+            Error::InvalidSlotIndex { .. } => "InvalidSlotIndex",
+            Error::SlotOccupied { .. } => "SlotOccupied",
+            Error::SlotEmpty { .. } => "SlotEmpty",
+            Error::InvalidShape { .. } => "InvalidShape",
+            Error::InvalidShapeName { .. } => "InvalidShapeName",
+            Error::InvalidUpgradeType { .. } => "InvalidUpgradeType",
+            Error::UpgradeNotFound { .. } => "UpgradeNotFound",
+            Error::NoUpgradesOfType { .. } => "NoUpgradesOfType",
+            Error::InvalidUpgradeIndex { .. } => "InvalidUpgradeIndex",
+            Error::EffectNotFound { .. } => "EffectNotFound",
+            Error::InvalidEffectIndex { .. } => "InvalidEffectIndex",
+            Error::InventoryFull { .. } => "InventoryFull",
+            Error::InvalidUsernameLength { .. } => "InvalidUsernameLength",
+            Error::InvalidAppearanceSize { .. } => "InvalidAppearanceSize",
+            Error::TruncatedFile { .. } => "TruncatedFile",
+            Error::InvalidArticleType { .. } => "InvalidArticleType",
+            Error::NothingToUndo => "NothingToUndo",
+            Error::NothingToRedo => "NothingToRedo",
+            Error::BackupNotFound { .. } => "BackupNotFound",
+            Error::VerificationFailed { .. } => "VerificationFailed",
+            Error::UnsupportedVersion { .. } => "UnsupportedVersion",
+            Error::PatchConflict { .. } => "PatchConflict",
+            Error::SaveFull { .. } => "SaveFull",
+            Error::InvalidQuantity { .. } => "InvalidQuantity",
+            Error::StackFull { .. } => "StackFull",
+            Error::UnfixableUpgrade { .. } => "UnfixableUpgrade",
+            Error::StatNotFound { .. } => "StatNotFound",
+            Error::InvalidAttribute { .. } => "InvalidAttribute",
+            Error::AttributeBelowOrigin { .. } => "AttributeBelowOrigin",
+            Error::InvalidOrigin { .. } => "InvalidOrigin",
+            Error::InvalidLevel { .. } => "InvalidLevel",
+            Error::PresetNotFound { .. } => "PresetNotFound",
+            Error::FlagNotFound { .. } => "FlagNotFound",
+            Error::BossNotFound { .. } => "BossNotFound",
+            Error::InvalidFlagBit { .. } => "InvalidFlagBit",
+            Error::InvalidFlagState { .. } => "InvalidFlagState",
+            Error::FlagPrerequisite { .. } => "FlagPrerequisite",
+        }
+    }
+
+    //Fields of the variant, the offsets and ids involved in the failure
+    pub fn details(&self) -> Value {
+        match self {
+            Error::IoError(err) => json!({ "io_kind": format!("{:?}", err.kind()) }),
+            Error::JsonError(err) => json!({ "line": err.line(), "column": err.column() }),
+            Error::EmptyFile => json!({}),
+            Error::OffsetNotFound { region } => json!({ "region": region }),
+            Error::ArticleNotFound {
+                number,
+                id,
+                location,
+            } => json!({ "number": number, "id": id, "location": location }),
+            Error::ArticleInfoNotFound { id, family } => json!({ "id": id, "family": family }),
+            Error::NoArticlesOfType { article_type } => json!({ "article_type": article_type }),
+            Error::InvalidArticleIndex {
+                article_type,
+                index,
+            } => json!({ "article_type": article_type, "index": index }),
+            Error::KeyItem { id }
+            | Error::NotAWeapon { id }
+            | Error::NotAnArmor { id }
+            | Error::NoSlots { id } => {
+                json!({ "id": id })
+            }
+            Error::InvalidEquipSlot { name } => json!({ "name": name }),
+            Error::WrongEquipSlot { id, slot } => json!({ "id": id, "slot": slot }),
+            Error::NothingToUnequipWith { slot } => json!({ "slot": slot }),
+            Error::InvalidBeltSlot { slot } => json!({ "slot": slot }),
+            Error::InvalidWeaponMods { second_part } => json!({ "second_part": second_part }),
+            Error::InvalidUpgradeLevel { level } => json!({ "level": level }),
+            Error::InvalidIdLength { expected, found } => {
+                json!({ "expected": expected, "found": found })
+            }
+            Error::SlotsNotFound {
+                first_part,
+                second_part,
+            } => json!({ "first_part": first_part, "second_part": second_part }),
+            Error::InvalidSlotIndex { id, index }
+            | Error::SlotOccupied { id, index }
+            | Error::SlotEmpty { id, index } => json!({ "id": id, "index": index }),
+            Error::InvalidShape { bytes } => json!({ "bytes": bytes }),
+            Error::InvalidShapeName { name, upgrade_type } => {
+                json!({ "name": name, "upgrade_type": upgrade_type })
+            }
+            Error::InvalidUpgradeType { byte } => json!({ "byte": byte }),
+            Error::UpgradeNotFound { id } | Error::EffectNotFound { id } => json!({ "id": id }),
+            Error::NoUpgradesOfType { upgrade_type } => json!({ "upgrade_type": upgrade_type }),
+            Error::InvalidUpgradeIndex {
+                upgrade_type,
+                index,
+            } => json!({ "upgrade_type": upgrade_type, "index": index }),
+            Error::InvalidEffectIndex { index } => json!({ "index": index }),
+            Error::InventoryFull { location } => json!({ "location": location }),
+            Error::InvalidUsernameLength { length } => json!({ "length": length }),
+            Error::InvalidAppearanceSize { size } => json!({ "size": size }),
+            Error::TruncatedFile {
+                region,
+                offset,
+                size,
+            } => json!({ "region": region, "offset": offset, "size": size }),
+            Error::InvalidArticleType { name } => json!({ "name": name }),
+            Error::NothingToUndo | Error::NothingToRedo => json!({}),
+            Error::BackupNotFound { id } => json!({ "id": id }),
+            Error::VerificationFailed { section } => json!({ "section": section }),
+            Error::UnsupportedVersion {
+                format,
+                version,
+                supported,
+            } => json!({ "format": format, "version": version, "supported": supported }),
+            Error::PatchConflict { region, offset } => {
+                json!({ "region": region, "offset": offset })
+            }
+            Error::SaveFull { needed } => json!({ "needed": needed }),
+            Error::InvalidQuantity {
+                id,
+                quantity,
+                available,
+            } => json!({ "id": id, "quantity": quantity, "available": available }),
+            Error::StackFull {
+                id,
+                quantity,
+                room,
+                location,
+            } => json!({ "id": id, "quantity": quantity, "room": room, "location": location }),
+            Error::UnfixableUpgrade { id } => json!({ "id": id }),
+            Error::StatNotFound { name }
+            | Error::PresetNotFound { name }
+            | Error::FlagNotFound { name }
+            | Error::BossNotFound { name } => json!({ "name": name }),
+            Error::InvalidFlagBit { bit } => json!({ "bit": bit }),
+            Error::InvalidFlagState { name, state } => json!({ "name": name, "state": state }),
+            Error::FlagPrerequisite {
+                name,
+                prerequisite,
+                state,
+            } => json!({ "name": name, "prerequisite": prerequisite, "state": state }),
+            Error::InvalidAttribute { name, value } => json!({ "name": name, "value": value }),
+            Error::AttributeBelowOrigin { name, value, min } => {
+                json!({ "name": name, "value": value, "min": min })
+            }
+            Error::InvalidOrigin { value } => json!({ "value": value }),
+            Error::InvalidLevel { level, min, max } => {
+                json!({ "level": level, "min": min, "max": max })
+            }
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Error", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::JsonError(err)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Hash, Eq, Copy)]
+pub enum ArticleType {
+    Consumable,
+    Material,
+    Key,
+    Chalice,
+    RightHand,
+    LeftHand,
+    Armor,
+}
+
+impl From<ArticleType> for TypeFamily {
+    fn from(val: ArticleType) -> Self {
+        match val {
+            ArticleType::Consumable => TypeFamily::Item,
+            ArticleType::Material => TypeFamily::Item,
+            ArticleType::Key => TypeFamily::Item,
+            ArticleType::Chalice => TypeFamily::Item,
+            ArticleType::RightHand => TypeFamily::Weapon,
+            ArticleType::LeftHand => TypeFamily::Weapon,
+            ArticleType::Armor => TypeFamily::Armor,
+        }
+    }
+}
+impl TryFrom<&str> for ArticleType {
+    type Error = Error;
+
+    fn try_from(string: &str) -> Result<ArticleType, Error> {
+        match string {
+            "consumable" => Ok(ArticleType::Consumable),
+            "material" => Ok(ArticleType::Material),
+            "key" => Ok(ArticleType::Key),
+            "chalice" => Ok(ArticleType::Chalice),
+            "rightHand" => Ok(ArticleType::RightHand),
+            "leftHand" => Ok(ArticleType::LeftHand),
+            "armor" => Ok(ArticleType::Armor),
+            _ => Err(Error::InvalidArticleType {
+                name: string.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum TypeFamily {
+    Armor,
+    Item,
+    Weapon,
+}
+
+impl fmt::Display for TypeFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeFamily::Armor => write!(f, "armor"),
+            TypeFamily::Item => write!(f, "item"),
+            TypeFamily::Weapon => write!(f, "weapon"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum Imprint {
+    Uncanny,
+    Lost,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum SlotShape {
+    Closed,
+    Radial,
+    Triangle,
+    Waning,
+    Circle,
+    Droplet,
+}
+impl TryFrom<&[u8; 4]> for SlotShape {
+    type Error = Error;
+    fn try_from(bytes: &[u8; 4]) -> Result<Self, Self::Error> {
+        match bytes {
+            [0x00, 0x00, 0x00, 0x80] => Ok(Self::Closed),
+            [0x01, 0x00, 0x00, 0x00] => Ok(Self::Radial),
+            [0x02, 0x00, 0x00, 0x00] => Ok(Self::Triangle),
+            [0x04, 0x00, 0x00, 0x00] => Ok(Self::Waning),
+            [0x08, 0x00, 0x00, 0x00] => Ok(Self::Circle),
+            [0x3F, 0x00, 0x00, 0x00] => Ok(Self::Droplet),
+            _ => Err(Error::InvalidShape { bytes: *bytes }),
+        }
+    }
+}
+impl From<SlotShape> for [u8; 4] {
+    fn from(val: SlotShape) -> Self {
+        match val {
+            SlotShape::Closed => [0x00, 0x00, 0x00, 0x80],
+            SlotShape::Radial => [0x01, 0x00, 0x00, 0x00],
+            SlotShape::Triangle => [0x02, 0x00, 0x00, 0x00],
+            SlotShape::Waning => [0x04, 0x00, 0x00, 0x00],
+            SlotShape::Circle => [0x08, 0x00, 0x00, 0x00],
+            SlotShape::Droplet => [0x3F, 0x00, 0x00, 0x00],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy, Hash)]
+pub enum UpgradeType {
+    Gem,
+    Rune,
+}
+
+impl TryFrom<u8> for UpgradeType {
+    type Error = Error;
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        match number {
+            0x01 => Ok(UpgradeType::Gem),
+            0x02 => Ok(UpgradeType::Rune),
+            _ => Err(Error::InvalidUpgradeType { byte: number }),
+        }
+    }
+}
+
+impl From<UpgradeType> for u8 {
+    fn from(upgrade_type: UpgradeType) -> Self {
+        match upgrade_type {
+            UpgradeType::Gem => 0x01,
+            UpgradeType::Rune => 0x02,
+        }
+    }
+}
+
+/// Rules of the game a gem or rune can break, see [`Upgrade::validate`].
+///
+/// [`Upgrade::validate`]: crate::upgrades::Upgrade::validate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum UpgradeRule {
+    //The effect is unknown or belongs to the other upgrade type
+    WrongEffectType,
+    NoEffects,
+    TooManyEffects,
+    //Gems have at most one curse and it comes after the other effects
+    MisplacedCurse,
+    DuplicateEffect,
+    //Every effect of a rune comes from the same rune
+    MixedRune,
+    WrongShape,
+    //The effect doesn't drop on gems of this shape
+    ShapeEffect,
+    //The effect belongs to a unique gem or the unique gem doesn't drop with it
+    WrongSource,
+}
+
+/// Origin picked when the character was created, it sets the starting attributes.
+/// Stored in the Origin stat in this order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum Origin {
+    Milquetoast,
+    LoneSurvivor,
+    TroubledChildhood,
+    ViolentPast,
+    Professional,
+    MilitaryVeteran,
+    NobleScion,
+    CruelFate,
+    WasteOfSkin,
+}
+
+impl TryFrom<u32> for Origin {
+    type Error = Error;
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Origin::Milquetoast),
+            1 => Ok(Origin::LoneSurvivor),
+            2 => Ok(Origin::TroubledChildhood),
+            3 => Ok(Origin::ViolentPast),
+            4 => Ok(Origin::Professional),
+            5 => Ok(Origin::MilitaryVeteran),
+            6 => Ok(Origin::NobleScion),
+            7 => Ok(Origin::CruelFate),
+            8 => Ok(Origin::WasteOfSkin),
+            _ => Err(Error::InvalidOrigin { value }),
+        }
+    }
+}
+
+impl From<Origin> for u32 {
+    fn from(origin: Origin) -> Self {
+        origin as u32
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Milquetoast => write!(f, "Milquetoast"),
+            Origin::LoneSurvivor => write!(f, "Lone Survivor"),
+            Origin::TroubledChildhood => write!(f, "Troubled Childhood"),
+            Origin::ViolentPast => write!(f, "Violent Past"),
+            Origin::Professional => write!(f, "Professional"),
+            Origin::MilitaryVeteran => write!(f, "Military Veteran"),
+            Origin::NobleScion => write!(f, "Noble Scion"),
+            Origin::CruelFate => write!(f, "Cruel Fate"),
+            Origin::WasteOfSkin => write!(f, "Waste of Skin"),
+        }
+    }
+}
+
+/// How sure the legitimacy report is that the game can't produce a finding.
+/// Ordered from the least to the most severe.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Copy)]
+pub enum Severity {
+    //Unusual, but the value may be shared with something the report doesn't know about
+    Warning,
+    Error,
+}
+
+/// Checks of the save-wide legitimacy report, see [`legitimacy::validate`].
+///
+/// [`legitimacy::validate`]: crate::legitimacy::validate
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum Check {
+    AttributeAboveMax,
+    //The attribute is lower than what the origin starts with, or the origin is unknown
+    AttributeBelowOrigin,
+    //The level isn't the one the attributes add up to
+    LevelMismatch,
+    HealthMismatch,
+    StaminaMismatch,
+    UpgradeLevelAboveMax,
+    //The weapon has no imprinted version
+    ImpossibleImprint,
+    DuplicateKeyItem,
+    //The boss flag is neither its dead nor its alive value
+    InvalidBossFlag,
+    QuantityAboveCap,
+    Upgrade(UpgradeRule),
+}
+
+/// Armor slots, in the order the save stores them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum ArmorSlot {
+    Head,
+    Chest,
+    Hands,
+    Legs,
+}
+
+impl ArmorSlot {
+    /// Slot of the armor with `id`, the thousands digit of the id.
+    pub fn of(id: u32) -> Result<ArmorSlot, Error> {
+        match (id / 1000) % 10 {
+            0 => Ok(ArmorSlot::Head),
+            1 => Ok(ArmorSlot::Chest),
+            2 => Ok(ArmorSlot::Hands),
+            3 => Ok(ArmorSlot::Legs),
+            _ => Err(Error::NotAnArmor { id }),
+        }
+    }
+}
+
+/// What a named event flag is about.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum FlagCategory {
+    NpcQuest,
+    Shortcut,
+    Lamp,
+    ItemPickup,
+    Dialogue,
+    Multiplayer,
+}
+
+/// Equipment slots, in the order the save stores them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum EquipSlot {
+    LeftHand1,
+    RightHand1,
+    LeftHand2,
+    RightHand2,
+    Head,
+    Chest,
+    Hands,
+    Legs,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 8] = [
+        EquipSlot::LeftHand1,
+        EquipSlot::RightHand1,
+        EquipSlot::LeftHand2,
+        EquipSlot::RightHand2,
+        EquipSlot::Head,
+        EquipSlot::Chest,
+        EquipSlot::Hands,
+        EquipSlot::Legs,
+    ];
+
+    /// Type of the articles the slot takes.
+    pub fn article_type(self) -> ArticleType {
+        match self {
+            EquipSlot::LeftHand1 | EquipSlot::LeftHand2 => ArticleType::LeftHand,
+            EquipSlot::RightHand1 | EquipSlot::RightHand2 => ArticleType::RightHand,
+            _ => ArticleType::Armor,
+        }
+    }
+
+    /// The armor slot, none for the hands.
+    pub fn armor_slot(self) -> Option<ArmorSlot> {
+        match self {
+            EquipSlot::Head => Some(ArmorSlot::Head),
+            EquipSlot::Chest => Some(ArmorSlot::Chest),
+            EquipSlot::Hands => Some(ArmorSlot::Hands),
+            EquipSlot::Legs => Some(ArmorSlot::Legs),
+            _ => None,
+        }
+    }
+}
+
+impl From<ArmorSlot> for EquipSlot {
+    fn from(slot: ArmorSlot) -> Self {
+        EquipSlot::ALL[4 + slot as usize]
+    }
+}
+
+impl fmt::Display for EquipSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquipSlot::LeftHand1 => write!(f, "left hand 1"),
+            EquipSlot::RightHand1 => write!(f, "right hand 1"),
+            EquipSlot::LeftHand2 => write!(f, "left hand 2"),
+            EquipSlot::RightHand2 => write!(f, "right hand 2"),
+            EquipSlot::Head => write!(f, "head"),
+            EquipSlot::Chest => write!(f, "chest"),
+            EquipSlot::Hands => write!(f, "hands"),
+            EquipSlot::Legs => write!(f, "legs"),
+        }
+    }
+}
+
+impl TryFrom<&str> for EquipSlot {
+    type Error = Error;
+
+    fn try_from(string: &str) -> Result<EquipSlot, Error> {
+        match string {
+            "leftHand1" => Ok(EquipSlot::LeftHand1),
+            "rightHand1" => Ok(EquipSlot::RightHand1),
+            "leftHand2" => Ok(EquipSlot::LeftHand2),
+            "rightHand2" => Ok(EquipSlot::RightHand2),
+            "head" => Ok(EquipSlot::Head),
+            "chest" => Ok(EquipSlot::Chest),
+            "hands" => Ok(EquipSlot::Hands),
+            "legs" => Ok(EquipSlot::Legs),
+            _ => Err(Error::InvalidEquipSlot {
+                name: string.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Copy)]
+pub enum Location {
+    Inventory,
+    Storage,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Inventory => write!(f, "inventory"),
+            Location::Storage => write!(f, "storage"),
+        }
+    }
+}
+
+impl From<bool> for Location {
+    fn from(is_storage: bool) -> Location {
+        match is_storage {
+            true => Location::Storage,
+            false => Location::Inventory,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_article_type_try_from_string() {
+        assert_eq!(
+            ArticleType::try_from("leftHand").unwrap(),
+            ArticleType::LeftHand
+        );
+        let result = ArticleType::try_from("error");
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "Invalid article type error.");
+        }
+    }
+
+    #[test]
+    fn test_upgrade_type_try_from_u8() {
+        let result = UpgradeType::try_from(255);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "Invalid upgrade type 0xff.");
+        }
+    }
+
+    #[test]
+    fn test_error_serialize() {
+        let error = Error::InventoryFull {
+            location: Location::Storage,
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "kind": "InventoryFull",
+                "message": "The storage is full.",
+                "details": { "location": "Storage" }
+            })
+        );
+    }
+}
@@ -0,0 +1,753 @@
+//! Articles the character has equipped. The save keeps every slot twice in a block right before
+//! the inventory: the position of the article in the inventory, then its handle, the first part
+//! of its inventory entry. An empty hand holds hidden fists and an empty armor slot an invisible
+//! [CUT] armor, both are entries of the inventory.
+//!
+//! Both lists have four more slots after the hands and three after the armor, unused in every
+//! known save.
+//!
+//! The consumables are kept after the end of the inventory. The belt has six quick items, stored
+//! as positions only and followed by the position of the one selected in game. Then come six
+//! item shortcuts, each with its handle and position, holding the bells, marks and lantern of
+//! the known saves.
+//!
+//! The bullets have no slot, the firearms shoot the Quicksilver Bullets of the inventory.
+use serde::{Deserialize, Serialize};
+
+use super::{
+    article::Article,
+    constants::{
+        USERNAME_TO_EQUIPPED_HANDLES, USERNAME_TO_EQUIPPED_POSITIONS, USERNAME_TO_ITEM_SHORTCUTS,
+        USERNAME_TO_QUICK_ITEMS, USERNAME_TO_SELECTED_QUICK_ITEM,
+    },
+    enums::{ArmorSlot, ArticleType, EquipSlot, Error, Location},
+    file::FileData,
+    inventory::Inventory,
+};
+
+//The positions are stored from the number of the first inventory slot, plus one
+const FIRST_POSITION: u32 = 0x41;
+
+//Position of an unused slot
+const NO_POSITION: u32 = 0xFFFFFFFF;
+
+//Id of the hidden fists an empty hand holds
+const FIST_ID: u32 = 1000000;
+
+//Id of the [CUT] head an empty head wears, the ones of the other slots follow every 1000
+const CUT_ARMOR_ID: u32 = 900000;
+
+//Id of the Quicksilver Bullets
+const BULLETS_ID: u32 = 900;
+
+//Slots of the belt, and of the item shortcuts
+pub const BELT_SLOTS: usize = 6;
+
+//Blood Vials and Quicksilver Bullets, with the amount a character carries without runes
+const READY_SUPPLIES: [(u32, u32); 2] = [(1000, 20), (900, 20)];
+
+//Molotov Cocktail, Throwing Knife, Pungent Blood Cocktail, Beast Blood Pellet, Antidote and
+//Numbing Mist, in the order of the belt, with the amount added when missing
+const READY_TOOLS: [(u32, u32); BELT_SLOTS] = [
+    (1200, 10),
+    (1240, 20),
+    (1260, 10),
+    (1110, 5),
+    (1100, 10),
+    (1270, 5),
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Equipment {
+    //Inventory positions of the equipped articles, in the order of EquipSlot
+    pub positions: [Option<usize>; 8],
+    //Handles of the left and right hand 1, then of the left and right hand 2
+    pub weapons: [u32; 4],
+    //Handles of the head, chest, hands and legs
+    pub armor: [u32; 4],
+    //Inventory positions of the quick items on the belt
+    pub quick_items: [Option<usize>; BELT_SLOTS],
+    //Inventory position of the quick item selected in game
+    pub selected_quick_item: Option<usize>,
+    //Inventory positions of the item shortcuts
+    pub shortcuts: [Option<usize>; BELT_SLOTS],
+    //Handles of the item shortcuts, 0 when empty
+    pub shortcut_handles: [u32; BELT_SLOTS],
+}
+
+impl Equipment {
+    /// Reads the positions and handles of the equipped articles and of the belt.
+    pub fn new(file: &FileData) -> Equipment {
+        let mut equipment = Equipment::default();
+        for slot in EquipSlot::ALL {
+            let offset = slot_offset(slot);
+            equipment.positions[slot as usize] =
+                read_position(file, USERNAME_TO_EQUIPPED_POSITIONS + offset);
+            *equipment.handle_mut(slot) =
+                file.get_number((USERNAME_TO_EQUIPPED_HANDLES + offset) as isize, 4);
+        }
+        for slot in 0..BELT_SLOTS {
+            equipment.quick_items[slot] = read_position(file, USERNAME_TO_QUICK_ITEMS + slot * 8);
+            equipment.shortcuts[slot] =
+                read_position(file, USERNAME_TO_ITEM_SHORTCUTS + slot * 8 + 4);
+            equipment.shortcut_handles[slot] =
+                file.get_number((USERNAME_TO_ITEM_SHORTCUTS + slot * 8) as isize, 4);
+        }
+        equipment.selected_quick_item = read_position(file, USERNAME_TO_SELECTED_QUICK_ITEM);
+        equipment
+    }
+
+    /// Handle of the article in `slot`.
+    pub fn handle(&self, slot: EquipSlot) -> u32 {
+        match slot.armor_slot() {
+            Some(armor) => self.armor[armor as usize],
+            None => self.weapons[slot as usize],
+        }
+    }
+
+    fn handle_mut(&mut self, slot: EquipSlot) -> &mut u32 {
+        match slot.armor_slot() {
+            Some(armor) => &mut self.armor[armor as usize],
+            None => &mut self.weapons[slot as usize],
+        }
+    }
+
+    /// The article in `slot`, none if its handle isn't among the articles of the inventory,
+    /// such as the fists of an empty hand.
+    pub fn article_in<'a>(&self, slot: EquipSlot, inventory: &'a Inventory) -> Option<&'a Article> {
+        let handle = self.handle(slot);
+        inventory
+            .articles
+            .get(&slot.article_type())?
+            .iter()
+            .find(|a| a.first_part == handle)
+    }
+
+    /// The armor worn in `slot`, see [`Equipment::article_in`].
+    pub fn armor_in<'a>(&self, slot: ArmorSlot, inventory: &'a Inventory) -> Option<&'a Article> {
+        self.article_in(EquipSlot::from(slot), inventory)
+    }
+
+    /// The bullets the firearms shoot, none if the inventory has no Quicksilver Bullets.
+    pub fn bullets(inventory: &Inventory) -> Option<&Article> {
+        find_consumable(inventory, BULLETS_ID)
+    }
+
+    /// Whether a slot, the belt or a shortcut holds the inventory entry of `article`.
+    pub fn is_equipped(&self, file: &FileData, article: &Article) -> bool {
+        let position = match entry_of(file, article) {
+            Ok((position, _)) => Some(position),
+            Err(_) => return false,
+        };
+        self.positions
+            .iter()
+            .chain(&self.quick_items)
+            .chain(&self.shortcuts)
+            .any(|p| *p == position)
+    }
+
+    /// Empties every slot, belt slot and shortcut holding the inventory entry of `article`, so
+    /// it can leave the inventory. The hands and armor fall back to the fists and [CUT] armor.
+    pub fn release(&mut self, file: &mut FileData, article: &Article) -> Result<(), Error> {
+        let position = match entry_of(file, article) {
+            Ok((position, _)) => Some(position),
+            Err(_) => return Ok(()),
+        };
+        for slot in EquipSlot::ALL {
+            if self.positions[slot as usize] == position {
+                self.unequip(file, slot)?;
+                //The fists and [CUT] armor themselves have nothing to fall back to
+                if self.positions[slot as usize] == position {
+                    return Err(Error::NothingToUnequipWith { slot });
+                }
+            }
+        }
+        for slot in 0..BELT_SLOTS {
+            if self.quick_items[slot] == position {
+                self.clear_quick_item(file, slot)?;
+            }
+            if self.shortcuts[slot] == position {
+                self.clear_shortcut(file, slot)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes every slot, the belt and the shortcuts to the file, the reverse of
+    /// [`Equipment::new`].
+    pub fn write(&self, file: &mut FileData) {
+        for slot in EquipSlot::ALL {
+            let offset = slot_offset(slot);
+            write_position(
+                file,
+                USERNAME_TO_EQUIPPED_POSITIONS + offset,
+                self.positions[slot as usize],
+            );
+            file.edit(
+                (USERNAME_TO_EQUIPPED_HANDLES + offset) as isize,
+                4,
+                1,
+                self.handle(slot),
+            );
+        }
+        for slot in 0..BELT_SLOTS {
+            let offset = USERNAME_TO_ITEM_SHORTCUTS + slot * 8;
+            write_position(
+                file,
+                USERNAME_TO_QUICK_ITEMS + slot * 8,
+                self.quick_items[slot],
+            );
+            file.edit(offset as isize, 4, 1, self.shortcut_handles[slot]);
+            write_position(file, offset + 4, self.shortcuts[slot]);
+        }
+        write_position(
+            file,
+            USERNAME_TO_SELECTED_QUICK_ITEM,
+            self.selected_quick_item,
+        );
+    }
+
+    /// Equips the article at `index` among the ones of `article_type` in the inventory. It has
+    /// to be of the type the slot takes, and an armor has to be worn on that part of the body.
+    pub fn equip(
+        &mut self,
+        file: &mut FileData,
+        inventory: &Inventory,
+        slot: EquipSlot,
+        article_type: ArticleType,
+        index: usize,
+    ) -> Result<(), Error> {
+        let article = inventory
+            .articles
+            .get(&article_type)
+            .and_then(|articles| articles.get(index))
+            .ok_or(Error::InvalidArticleIndex {
+                article_type,
+                index,
+            })?;
+        let fits = match slot.armor_slot() {
+            Some(armor) => {
+                article_type == ArticleType::Armor && ArmorSlot::of(article.id)? == armor
+            }
+            None => article_type == slot.article_type(),
+        };
+        if !fits {
+            return Err(Error::WrongEquipSlot {
+                id: article.id,
+                slot,
+            });
+        }
+
+        let (position, handle) = entry_of(file, article)?;
+        self.set(file, slot, position, handle);
+        Ok(())
+    }
+
+    /// Leaves `slot` empty, holding the hidden fists or wearing the [CUT] armor of the inventory.
+    pub fn unequip(&mut self, file: &mut FileData, slot: EquipSlot) -> Result<(), Error> {
+        let (entries, nth) = match slot.armor_slot() {
+            Some(armor) => {
+                let id = CUT_ARMOR_ID + 1000 * armor as u32;
+                let entries = find_entries(file, |first_part, second_part| {
+                    first_part >> 24 == 0x90 && second_part & 0x00FFFFFF == id
+                });
+                (entries, 0)
+            }
+            None => {
+                let entries = find_entries(file, |first_part, second_part| {
+                    first_part >> 24 == 0x80 && second_part == FIST_ID
+                });
+                //The saves have four fists, each hand slot usually holds the one at its index
+                (entries, slot as usize)
+            }
+        };
+        let (position, handle) = *entries
+            .get(nth)
+            .or_else(|| entries.first())
+            .ok_or(Error::NothingToUnequipWith { slot })?;
+        self.set(file, slot, position, handle);
+        Ok(())
+    }
+
+    fn set(&mut self, file: &mut FileData, slot: EquipSlot, position: usize, handle: u32) {
+        let offset = slot_offset(slot);
+        write_position(
+            file,
+            USERNAME_TO_EQUIPPED_POSITIONS + offset,
+            Some(position),
+        );
+        file.edit(
+            (USERNAME_TO_EQUIPPED_HANDLES + offset) as isize,
+            4,
+            1,
+            handle,
+        );
+        self.positions[slot as usize] = Some(position);
+        *self.handle_mut(slot) = handle;
+    }
+
+    /// Puts the consumable at `index` among the ones of the inventory in the belt `slot`. An
+    /// item is only once on the belt, it leaves the slot it was in.
+    pub fn assign_quick_item(
+        &mut self,
+        file: &mut FileData,
+        inventory: &Inventory,
+        slot: usize,
+        index: usize,
+    ) -> Result<(), Error> {
+        check_belt_slot(slot)?;
+        let (position, _) = entry_of(file, consumable(inventory, index)?)?;
+        for other in 0..BELT_SLOTS {
+            if other != slot && self.quick_items[other] == Some(position) {
+                self.set_quick_item(file, other, None);
+            }
+        }
+        self.set_quick_item(file, slot, Some(position));
+        if self.selected_quick_item.is_none() {
+            self.select_quick_item(file, Some(position));
+        }
+        Ok(())
+    }
+
+    /// Empties the belt `slot`. When it held the selected item, the first one left is selected.
+    pub fn clear_quick_item(&mut self, file: &mut FileData, slot: usize) -> Result<(), Error> {
+        check_belt_slot(slot)?;
+        let cleared = self.quick_items[slot];
+        self.set_quick_item(file, slot, None);
+        if cleared.is_some() && self.selected_quick_item == cleared {
+            let next = self.quick_items.iter().flatten().next().copied();
+            self.select_quick_item(file, next);
+        }
+        Ok(())
+    }
+
+    /// Puts the consumable at `index` among the ones of the inventory in the shortcut `slot`,
+    /// see [`Equipment::assign_quick_item`].
+    pub fn assign_shortcut(
+        &mut self,
+        file: &mut FileData,
+        inventory: &Inventory,
+        slot: usize,
+        index: usize,
+    ) -> Result<(), Error> {
+        check_belt_slot(slot)?;
+        let (position, handle) = entry_of(file, consumable(inventory, index)?)?;
+        for other in 0..BELT_SLOTS {
+            if other != slot && self.shortcuts[other] == Some(position) {
+                self.set_shortcut(file, other, None);
+            }
+        }
+        self.set_shortcut(file, slot, Some((position, handle)));
+        Ok(())
+    }
+
+    /// Empties the shortcut `slot`.
+    pub fn clear_shortcut(&mut self, file: &mut FileData, slot: usize) -> Result<(), Error> {
+        check_belt_slot(slot)?;
+        self.set_shortcut(file, slot, None);
+        Ok(())
+    }
+
+    /// Tops the Blood Vials and Quicksilver Bullets up to what a character carries and fills
+    /// the belt with tools, adding the ones the inventory lacks.
+    pub fn ready_belt(
+        &mut self,
+        file: &mut FileData,
+        inventory: &mut Inventory,
+    ) -> Result<(), Error> {
+        for (id, amount) in READY_SUPPLIES {
+            match find_consumable(inventory, id) {
+                Some(item) if item.amount >= amount => {}
+                Some(item) => {
+                    let number = item.number;
+                    inventory.edit_item(file, number, id, amount, false)?;
+                }
+                None => {
+                    inventory.add_item(file, id, amount, false)?;
+                }
+            }
+        }
+        for (slot, (id, amount)) in READY_TOOLS.into_iter().enumerate() {
+            let index = match find_consumable(inventory, id) {
+                Some(item) => item.index,
+                None => {
+                    inventory.add_item(file, id, amount, false)?;
+                    find_consumable(inventory, id)
+                        .ok_or(Error::ArticleNotFound {
+                            number: 0,
+                            id,
+                            location: Location::Inventory,
+                        })?
+                        .index
+                }
+            };
+            self.assign_quick_item(file, inventory, slot, index)?;
+        }
+        let first = self.quick_items[0];
+        self.select_quick_item(file, first);
+        Ok(())
+    }
+
+    fn set_quick_item(&mut self, file: &mut FileData, slot: usize, position: Option<usize>) {
+        write_position(file, USERNAME_TO_QUICK_ITEMS + slot * 8, position);
+        self.quick_items[slot] = position;
+    }
+
+    fn select_quick_item(&mut self, file: &mut FileData, position: Option<usize>) {
+        write_position(file, USERNAME_TO_SELECTED_QUICK_ITEM, position);
+        self.selected_quick_item = position;
+    }
+
+    fn set_shortcut(&mut self, file: &mut FileData, slot: usize, entry: Option<(usize, u32)>) {
+        let offset = USERNAME_TO_ITEM_SHORTCUTS + slot * 8;
+        let (position, handle) = match entry {
+            Some((position, handle)) => (Some(position), handle),
+            None => (None, 0),
+        };
+        file.edit(offset as isize, 4, 1, handle);
+        write_position(file, offset + 4, position);
+        self.shortcuts[slot] = position;
+        self.shortcut_handles[slot] = handle;
+    }
+}
+
+fn check_belt_slot(slot: usize) -> Result<(), Error> {
+    if slot < BELT_SLOTS {
+        Ok(())
+    } else {
+        Err(Error::InvalidBeltSlot { slot })
+    }
+}
+
+fn consumable(inventory: &Inventory, index: usize) -> Result<&Article, Error> {
+    let article_type = ArticleType::Consumable;
+    inventory
+        .articles
+        .get(&article_type)
+        .and_then(|articles| articles.get(index))
+        .ok_or(Error::InvalidArticleIndex {
+            article_type,
+            index,
+        })
+}
+
+fn find_consumable(inventory: &Inventory, id: u32) -> Option<&Article> {
+    inventory
+        .articles
+        .get(&ArticleType::Consumable)?
+        .iter()
+        .find(|a| a.id == id)
+}
+
+fn read_position(file: &FileData, rel_offset: usize) -> Option<usize> {
+    match file.get_number(rel_offset as isize, 4) {
+        NO_POSITION => None,
+        position => position.checked_sub(FIRST_POSITION).map(|p| p as usize),
+    }
+}
+
+fn write_position(file: &mut FileData, rel_offset: usize, position: Option<usize>) {
+    let value = position.map_or(NO_POSITION, |p| FIRST_POSITION + p as u32);
+    file.edit(rel_offset as isize, 4, 1, value);
+}
+
+//Distance between the start of both lists and the entry of the slot
+fn slot_offset(slot: EquipSlot) -> usize {
+    match slot.armor_slot() {
+        Some(armor) => (8 + armor as usize) * 4,
+        None => slot as usize * 4,
+    }
+}
+
+//Position and handle of the inventory entry of an article
+fn entry_of(file: &FileData, article: &Article) -> Result<(usize, u32), Error> {
+    find_entries(file, |first_part, _| first_part == article.first_part)
+        .into_iter()
+        .next()
+        .ok_or(Error::ArticleNotFound {
+            number: article.number,
+            id: article.id,
+            location: Location::Inventory,
+        })
+}
+
+//Position and first part of the inventory entries whose first and second parts match
+fn find_entries<F: Fn(u32, u32) -> bool>(file: &FileData, is_match: F) -> Vec<(usize, u32)> {
+    let part = |at: usize| {
+        u32::from_le_bytes([
+            file.bytes[at],
+            file.bytes[at + 1],
+            file.bytes[at + 2],
+            file.bytes[at + 3],
+        ])
+    };
+    let (start, end) = file.offsets.inventory;
+    (start..end)
+        .step_by(16)
+        .enumerate()
+        .map(|(position, i)| (position, part(i + 4), part(i + 8)))
+        .filter(|&(_, first_part, second_part)| is_match(first_part, second_part))
+        .map(|(position, first_part, _)| (position, first_part))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::build_save_data;
+
+    #[test]
+    fn equipment_armor() {
+        //testsave7, a new character in the Foreign set
+        let save = build_save_data("testsave7");
+        let names: Vec<_> = [
+            ArmorSlot::Head,
+            ArmorSlot::Chest,
+            ArmorSlot::Hands,
+            ArmorSlot::Legs,
+        ]
+        .into_iter()
+        .map(|slot| {
+            let armor = save.equipment.armor_in(slot, &save.inventory).unwrap();
+            assert_eq!(ArmorSlot::of(armor.id).unwrap(), slot);
+            armor.info.item_name.as_str()
+        })
+        .collect();
+        assert_eq!(
+            names,
+            [
+                "Black Hood",
+                "Foreign Garb",
+                "Sullied Bandage",
+                "Foreign Trousers"
+            ]
+        );
+
+        //testsave2 wears nothing on its head
+        let save = build_save_data("testsave2");
+        let head = save
+            .equipment
+            .armor_in(ArmorSlot::Head, &save.inventory)
+            .unwrap();
+        assert_eq!(head.info.item_name, "[CUT] Head");
+    }
+
+    #[test]
+    fn equipment_weapons() {
+        let save = build_save_data("testsave2");
+        assert_eq!(
+            save.equipment.positions,
+            [20, 157, 28, 3, 13, 22, 148, 124].map(Some)
+        );
+        for slot in EquipSlot::ALL {
+            let handle = save.equipment.handle(slot);
+            let entries = find_entries(&save.file, |first_part, _| first_part == handle);
+            assert_eq!(
+                entries[0].0,
+                save.equipment.positions[slot as usize].unwrap()
+            );
+        }
+
+        let left = save
+            .equipment
+            .article_in(EquipSlot::LeftHand1, &save.inventory)
+            .unwrap();
+        assert_eq!(left.article_type, ArticleType::LeftHand);
+        assert!(save
+            .equipment
+            .article_in(EquipSlot::RightHand1, &save.inventory)
+            .is_some());
+        //The right hand 2 holds the fists, they aren't parsed as articles
+        assert!(save
+            .equipment
+            .article_in(EquipSlot::RightHand2, &save.inventory)
+            .is_none());
+    }
+
+    #[test]
+    fn equipment_equip_and_unequip() {
+        let mut save = build_save_data("testsave2");
+        let hats = &save.inventory.articles[&ArticleType::Armor];
+        let hat = hats
+            .iter()
+            .find(|a| a.info.item_name == "Hunter Hat")
+            .unwrap()
+            .clone();
+
+        //A hat only goes on the head, and armor doesn't go in the hands
+        for slot in [EquipSlot::Chest, EquipSlot::LeftHand1] {
+            let result = save.equipment.equip(
+                &mut save.file,
+                &save.inventory,
+                slot,
+                ArticleType::Armor,
+                hat.index,
+            );
+            assert_eq!(result.unwrap_err().kind(), "WrongEquipSlot");
+        }
+        let result = save.equipment.equip(
+            &mut save.file,
+            &save.inventory,
+            EquipSlot::LeftHand1,
+            ArticleType::RightHand,
+            0,
+        );
+        assert_eq!(result.unwrap_err().kind(), "WrongEquipSlot");
+        let result = save.equipment.equip(
+            &mut save.file,
+            &save.inventory,
+            EquipSlot::Head,
+            ArticleType::Armor,
+            999,
+        );
+        assert_eq!(result.unwrap_err().kind(), "InvalidArticleIndex");
+
+        save.equipment
+            .equip(
+                &mut save.file,
+                &save.inventory,
+                EquipSlot::Head,
+                ArticleType::Armor,
+                hat.index,
+            )
+            .unwrap();
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+        let head = save.equipment.armor_in(ArmorSlot::Head, &save.inventory);
+        assert_eq!(head.unwrap().info.item_name, "Hunter Hat");
+
+        //Unequipping goes back to the [CUT] armor and the fists
+        save.equipment
+            .unequip(&mut save.file, EquipSlot::Head)
+            .unwrap();
+        save.equipment
+            .unequip(&mut save.file, EquipSlot::RightHand1)
+            .unwrap();
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+        let head = save.equipment.armor_in(ArmorSlot::Head, &save.inventory);
+        assert_eq!(head.unwrap().info.item_name, "[CUT] Head");
+        assert_eq!(
+            save.equipment.positions[EquipSlot::RightHand1 as usize],
+            Some(1)
+        );
+        assert!(save
+            .equipment
+            .article_in(EquipSlot::RightHand1, &save.inventory)
+            .is_none());
+    }
+
+    #[test]
+    fn equipment_belt() {
+        let save = build_save_data("testsave9");
+        let equipment = &save.equipment;
+        assert_eq!(
+            equipment.quick_items,
+            [Some(0x2B), None, None, None, None, None]
+        );
+        assert_eq!(equipment.selected_quick_item, Some(0x2B));
+        assert_eq!(
+            equipment.shortcuts,
+            [
+                Some(0x10),
+                Some(0x18),
+                Some(0x1A),
+                Some(0x19),
+                None,
+                Some(0x2C)
+            ]
+        );
+        assert_eq!(
+            equipment.shortcut_handles,
+            [0xB0000578, 0xB00000C8, 0xB00000CD, 0xB000006F, 0, 0xB0000584]
+        );
+        let antidote = find_entries(&save.file, |first_part, _| first_part == 0xB000044C);
+        assert_eq!(antidote[0].0, 0x2B);
+
+        //A new character has nothing on its belt
+        let save = build_save_data("testsave7");
+        assert_eq!(save.equipment.quick_items, [None; BELT_SLOTS]);
+        assert_eq!(save.equipment.selected_quick_item, None);
+        assert_eq!(save.equipment.shortcuts, [None; BELT_SLOTS]);
+        assert_eq!(save.equipment.shortcut_handles, [0; BELT_SLOTS]);
+    }
+
+    #[test]
+    fn equipment_assign_and_clear_belt() {
+        let mut save = build_save_data("testsave9");
+        let bell = find_consumable(&save.inventory, 200).unwrap().clone();
+        let antidote = find_consumable(&save.inventory, 1100).unwrap().clone();
+        let bell_position = entry_of(&save.file, &bell).unwrap().0;
+
+        let result = save.equipment.assign_quick_item(
+            &mut save.file,
+            &save.inventory,
+            BELT_SLOTS,
+            bell.index,
+        );
+        assert_eq!(result.unwrap_err().kind(), "InvalidBeltSlot");
+        let result = save
+            .equipment
+            .assign_quick_item(&mut save.file, &save.inventory, 1, 999);
+        assert_eq!(result.unwrap_err().kind(), "InvalidArticleIndex");
+
+        save.equipment
+            .assign_quick_item(&mut save.file, &save.inventory, 2, bell.index)
+            .unwrap();
+        assert_eq!(save.equipment.quick_items[2], Some(bell_position));
+        //Assigning it again moves it
+        save.equipment
+            .assign_quick_item(&mut save.file, &save.inventory, 3, bell.index)
+            .unwrap();
+        assert_eq!(save.equipment.quick_items[2], None);
+        assert_eq!(save.equipment.quick_items[3], Some(bell_position));
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        //Clearing the selected Antidote selects the bell
+        save.equipment.clear_quick_item(&mut save.file, 0).unwrap();
+        assert_eq!(save.equipment.quick_items[0], None);
+        assert_eq!(save.equipment.selected_quick_item, Some(bell_position));
+        save.equipment.clear_quick_item(&mut save.file, 3).unwrap();
+        assert_eq!(save.equipment.selected_quick_item, None);
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        save.equipment
+            .assign_shortcut(&mut save.file, &save.inventory, 4, antidote.index)
+            .unwrap();
+        assert_eq!(save.equipment.shortcut_handles[4], 0xB000044C);
+        save.equipment.clear_shortcut(&mut save.file, 0).unwrap();
+        assert_eq!(save.equipment.shortcuts[0], None);
+        assert_eq!(save.equipment.shortcut_handles[0], 0);
+        let result = save.equipment.clear_shortcut(&mut save.file, BELT_SLOTS);
+        assert_eq!(result.unwrap_err().kind(), "InvalidBeltSlot");
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+    }
+
+    #[test]
+    fn equipment_ready_belt() {
+        let mut save = build_save_data("testsave7");
+        save.equipment
+            .ready_belt(&mut save.file, &mut save.inventory)
+            .unwrap();
+        for (id, amount) in READY_SUPPLIES {
+            assert!(find_consumable(&save.inventory, id).unwrap().amount >= amount);
+        }
+        for (slot, (id, _)) in READY_TOOLS.into_iter().enumerate() {
+            let tool = find_consumable(&save.inventory, id).unwrap();
+            let position = entry_of(&save.file, tool).unwrap().0;
+            assert_eq!(save.equipment.quick_items[slot], Some(position));
+        }
+        assert_eq!(
+            save.equipment.selected_quick_item,
+            save.equipment.quick_items[0]
+        );
+
+        //The amounts and the belt are read back from the file
+        let reparsed = crate::save::SaveData::from_file(save.file.clone()).unwrap();
+        assert_eq!(reparsed.equipment, save.equipment);
+        for (id, amount) in READY_SUPPLIES {
+            assert_eq!(
+                find_consumable(&reparsed.inventory, id).unwrap().amount,
+                amount
+            );
+        }
+    }
+}
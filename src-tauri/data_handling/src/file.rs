@@ -0,0 +1,511 @@
+//! Raw save bytes and the low level helpers every other module edits them with.
+use serde::{Deserialize, Serialize};
+
+use super::{
+    backup::{self, Retention},
+    constants::{USERNAME_TO_AOB, USERNAME_TO_ISZ_GLITCH},
+    enums::{Error, Location, TypeFamily},
+    offsets::Offsets,
+};
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// The bytes of a save and the offsets of its regions.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FileData {
+    pub bytes: Vec<u8>,
+    pub offsets: Offsets,
+}
+
+impl FileData {
+    /// Reads the save at `path`, locates its regions and snapshots it with the default retention.
+    pub fn build(path: &str) -> Result<FileData, Error> {
+        Self::build_with_retention(path, &Retention::default())
+    }
+
+    /// Same as [`FileData::build`], keeping the snapshots `retention` allows.
+    pub fn build_with_retention(path: &str, retention: &Retention) -> Result<FileData, Error> {
+        // Open the save file
+        let mut file = fs::File::open(path).map_err(Error::IoError)?;
+
+        // Read the entire file into a vector of bytes
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(Error::IoError)?;
+
+        let file_data = Self::from_bytes(bytes)?;
+
+        // Create a backup, skipped if this exact file was already backed up
+        backup::create(path, &file_data.bytes, retention)?;
+
+        Ok(file_data)
+    }
+
+    /// Locates the regions of a save that is already in memory, no backup is made.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<FileData, Error> {
+        if bytes.is_empty() {
+            return Err(Error::EmptyFile);
+        }
+
+        //Search the offsets
+        let offsets = Offsets::build(&bytes)?;
+
+        Ok(FileData { bytes, offsets })
+    }
+
+    ///offset_from_username is value_offset-username_offset
+    pub fn get_number(&self, offset_from_username: isize, length: usize) -> u32 {
+        let value_offset = (self.offsets.username as isize + offset_from_username) as usize;
+        let value_bytes = &self.bytes[value_offset..value_offset + length];
+
+        let mut value: u32 = 0;
+        let base: u32 = 256;
+
+        for (index, byte) in value_bytes.iter().enumerate().rev() {
+            value += *byte as u32 * (base.pow(index as u32));
+        }
+
+        value
+    }
+
+    pub fn get_flag(&self, offset_from_aob: usize) -> u8 {
+        let value_offset = self.offsets.username + USERNAME_TO_AOB + offset_from_aob;
+
+        self.bytes[value_offset]
+    }
+
+    /// Like [`FileData::get_flag`], but fails if the flag is past the end of the save.
+    pub fn try_get_flag(&self, offset_from_aob: usize) -> Result<u8, Error> {
+        let value_offset = self.offsets.username + USERNAME_TO_AOB + offset_from_aob;
+
+        self.bytes
+            .get(value_offset)
+            .copied()
+            .ok_or(Error::TruncatedFile {
+                region: "flags",
+                offset: value_offset + 1,
+                size: self.bytes.len(),
+            })
+    }
+
+    pub fn set_flag(&mut self, offset_from_aob: usize, new_value: u8) {
+        let value_offset = self.offsets.username + USERNAME_TO_AOB + offset_from_aob;
+
+        self.bytes[value_offset] = new_value;
+    }
+
+    /// Whether `bit`, 0 to 7 from the lowest, of the flag byte at `offset_from_aob` is set.
+    pub fn get_flag_bit(&self, offset_from_aob: usize, bit: u8) -> Result<bool, Error> {
+        let mask = flag_bit_mask(bit)?;
+        Ok(self.try_get_flag(offset_from_aob)? & mask != 0)
+    }
+
+    /// Sets or clears one bit of a flag byte, the other bits belong to other events and are kept.
+    pub fn set_flag_bit(
+        &mut self,
+        offset_from_aob: usize,
+        bit: u8,
+        value: bool,
+    ) -> Result<(), Error> {
+        let mask = flag_bit_mask(bit)?;
+        let byte = self.try_get_flag(offset_from_aob)?;
+        let new_value = match value {
+            true => byte | mask,
+            false => byte & !mask,
+        };
+        self.set_flag(offset_from_aob, new_value);
+        Ok(())
+    }
+
+    /// Flips one bit of a flag byte and returns its new value.
+    pub fn toggle_flag_bit(&mut self, offset_from_aob: usize, bit: u8) -> Result<bool, Error> {
+        let value = !self.get_flag_bit(offset_from_aob, bit)?;
+        self.set_flag_bit(offset_from_aob, bit, value)?;
+        Ok(value)
+    }
+
+    pub fn apply_mask(&mut self, offset_from_aob: usize, mask: u8) {
+        let value_offset = self.offsets.username + USERNAME_TO_AOB + offset_from_aob;
+
+        self.bytes[value_offset] &= mask;
+    }
+
+    pub fn edit(&mut self, rel_offset: isize, length: usize, times: usize, value: u32) {
+        let value_bytes = value.to_le_bytes();
+        let from_offset = (self.offsets.username as isize + rel_offset) as usize;
+
+        for i in 0..times {
+            let offset = i * 4;
+            for (j, b) in value_bytes[..length].iter().enumerate() {
+                self.bytes[from_offset + j + offset] = *b;
+            }
+        }
+    }
+
+    /// Replaces the `length` bytes at `offset` with `new_bytes`, moving everything after them.
+    /// The save keeps its size: the zeros at its end absorb the difference, and the offsets are
+    /// located again. Used for the entries before the username, which are 8B when empty,
+    /// 40B for an upgrade and 60B for a slots block.
+    ///
+    /// Some saves end in the middle of a string instead of zeros, growing them fails rather than
+    /// cut what's left of it.
+    pub fn resize(&mut self, offset: usize, length: usize, new_bytes: &[u8]) -> Result<(), Error> {
+        let size = self.bytes.len();
+        if new_bytes.len() > length {
+            let needed = new_bytes.len() - length;
+            if self.bytes[size - needed..].iter().any(|b| *b != 0) {
+                return Err(Error::SaveFull { needed });
+            }
+        }
+
+        let mut bytes = self.bytes.clone();
+        bytes.splice(offset..offset + length, new_bytes.iter().cloned());
+        bytes.resize(size, 0);
+        let mut offsets = Offsets::build(&bytes)?;
+
+        //The slots blocks are found by parse_equipped_gems, the new bounds are derived from the old ones
+        let delta = new_bytes.len() as isize - length as isize;
+        let shift = |i: usize| -> usize {
+            if i >= offset + length {
+                (i as isize + delta) as usize
+            } else if i >= offset + new_bytes.len() {
+                //Inside an entry that shrunk
+                offset + new_bytes.len() - 1
+            } else {
+                i
+            }
+        };
+        let (start, end) = self.offsets.equipped_gems;
+        offsets.equipped_gems = (shift(start), shift(end));
+
+        self.bytes = bytes;
+        self.offsets = offsets;
+        Ok(())
+    }
+
+    /// Writes the current bytes to `path`, see [`FileData::save_verified`].
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        self.save_verified(path, |_| Ok(()))
+    }
+
+    /// Writes the bytes to a temporary file next to `path` and reads it back, see
+    /// [`write_atomic`]. The written file is parsed and handed to `verify`, only if
+    /// everything succeeds it is renamed over `path`. On failure `path` is left untouched.
+    pub fn save_verified<F>(&self, path: &str, verify: F) -> Result<(), Error>
+    where
+        F: FnOnce(FileData) -> Result<(), Error>,
+    {
+        write_atomic(Path::new(path), &self.bytes, |temp_path| {
+            let written = fs::read(temp_path)?;
+            if written != self.bytes {
+                return Err(Error::VerificationFailed { section: "bytes" });
+            }
+            verify(Self::from_bytes(written)?)
+        })
+    }
+
+    pub fn find_article_offset(
+        &self,
+        index: u8,
+        id: u32,
+        type_family: TypeFamily,
+        is_storage: bool,
+    ) -> Option<usize> {
+        let found = |offset| -> bool {
+            let last_byte = match type_family {
+                TypeFamily::Armor | TypeFamily::Item => 0x00,
+                TypeFamily::Weapon => self.bytes[offset + 11],
+            };
+            let current_id = u32::from_le_bytes([
+                self.bytes[offset + 8],
+                self.bytes[offset + 9],
+                self.bytes[offset + 10],
+                last_byte,
+            ]);
+            (index == self.bytes[offset]) && (id == current_id)
+        };
+
+        let (inv, key) = match is_storage {
+            true => (self.offsets.storage, self.offsets.key_inventory),
+            false => (self.offsets.inventory, self.offsets.key_inventory),
+        };
+
+        //Search for the article in the inventory
+        let mut i = inv.0;
+        while (i <= inv.1 - 24) && (!found(i)) {
+            i += 16;
+        }
+
+        //If the article wasnt found, search for it in the key inventory
+        if !found(i) {
+            i = key.0;
+            while (i <= key.1 - 16) && (!found(i)) {
+                i += 16;
+            }
+        }
+
+        match found(i) {
+            true => Some(i),
+            false => None,
+        }
+    }
+
+    pub fn find_upgrade_offset(&self, id: u32) -> Option<usize> {
+        //Search for the upgrade
+        for i in (self.offsets.upgrades.0..self.offsets.upgrades.1).step_by(40) {
+            let current_id = u32::from_le_bytes([
+                self.bytes[i],
+                self.bytes[i + 1],
+                self.bytes[i + 2],
+                self.bytes[i + 3],
+            ]);
+            if id == current_id {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    ///If there is an empty slot return the index of the first byte of the first part
+    ///Or else return None
+    pub fn find_inv_empty_slot(&self, location: Location) -> Option<usize> {
+        let (start, end) = match location {
+            Location::Inventory => self.offsets.inventory,
+            Location::Storage => self.offsets.storage,
+        };
+        let empty = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        let mut buffer = [0; 12];
+        //-4 so it doesn't match the last slot
+        for i in (start..end - 4).step_by(16) {
+            buffer.copy_from_slice(&self.bytes[i + 4..=i + 15]);
+            if empty == buffer {
+                return Some(i + 4); //First byte of the first part of the slot
+            }
+        }
+        None
+    }
+
+    pub fn count_inv_empty_slots(&self, location: Location) -> usize {
+        let (start, end) = match location {
+            Location::Inventory => self.offsets.inventory,
+            Location::Storage => self.offsets.storage,
+        };
+        let empty = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        (start..end - 4)
+            .step_by(16)
+            .filter(|i| self.bytes[i + 4..=i + 15] == empty)
+            .count()
+    }
+
+    pub fn get_playtime(&self) -> u32 {
+        let time_bytes = [
+            self.bytes[0x08],
+            self.bytes[0x09],
+            self.bytes[0x0A],
+            self.bytes[0x0B],
+        ];
+
+        u32::from_le_bytes(time_bytes)
+    }
+
+    pub fn set_playtime(&mut self, new_playtime: [u8; 4]) {
+        for (i, j) in (0x08..=0x0B).enumerate() {
+            self.bytes[j] = new_playtime[i];
+        }
+    }
+
+    pub fn get_isz(&self) -> [u8; 2] {
+        [
+            self.bytes[USERNAME_TO_ISZ_GLITCH + self.offsets.username],
+            self.bytes[USERNAME_TO_ISZ_GLITCH + self.offsets.username + 1],
+        ]
+    }
+
+    pub fn fix_isz(&mut self) -> String {
+        let values = self.get_isz();
+        if values[0] == 0xFF {
+            if values[1] < 0xC0 {
+                self.bytes[USERNAME_TO_ISZ_GLITCH + self.offsets.username + 1] = 0x30;
+                return "Partial Isz glitch fix applied".to_string();
+            } else if values[1] == 0xC0 {
+                self.bytes[USERNAME_TO_ISZ_GLITCH + self.offsets.username + 1] = 0xFF;
+                return "Full Isz glitch fix applied".to_string();
+            }
+        }
+
+        "No Isz glitch, no changes have been made".to_string()
+    }
+}
+
+/// Writes `bytes` to a temporary file next to `path`, syncs it and hands its path to `verify`,
+/// only if everything succeeds it is renamed over `path`. On failure `path` is left untouched.
+pub(crate) fn write_atomic<F>(path: &Path, bytes: &[u8], verify: F) -> Result<(), Error>
+where
+    F: FnOnce(&Path) -> Result<(), Error>,
+{
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let write_and_check = || -> Result<(), Error> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        drop(file);
+        verify(&temp_path)
+    };
+
+    if let Err(error) = write_and_check() {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error);
+    }
+    fs::rename(&temp_path, path)?;
+
+    //Persist the rename, not possible on every platform
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+//Mask of a bit of a flag byte, 0 being the lowest one
+fn flag_bit_mask(bit: u8) -> Result<u8, Error> {
+    match bit {
+        0..=7 => Ok(1 << bit),
+        _ => Err(Error::InvalidFlagBit { bit }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::EMPTY_ENTRY, utils::test_utils::check_bytes};
+
+    #[test]
+    fn test_find_upgrade_offset() {
+        //testsave3
+        let file_data = FileData::build("saves/testsave3").unwrap();
+
+        //Does not exist
+        assert_eq!(file_data.find_upgrade_offset(0xFFFFFFFF), None);
+        //First one
+        assert_eq!(file_data.find_upgrade_offset(0xC08006B5), Some(0x54));
+        //Last one
+        assert_eq!(file_data.find_upgrade_offset(0xC0800715), Some(0xF54));
+        //Other ones
+        assert_eq!(file_data.find_upgrade_offset(0xC08006E5), Some(0x7D4));
+        assert_eq!(file_data.find_upgrade_offset(0xC08006D4), Some(0x52C));
+        assert_eq!(file_data.find_upgrade_offset(0xC08006C2), Some(0x25C));
+        assert_eq!(file_data.find_upgrade_offset(0x00000000), None);
+    }
+
+    #[test]
+    fn test_file_data_save() {
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        file_data.edit(500, 2, 10, 500);
+        file_data.save("saves/savetestsave").unwrap();
+        let file_data2 = FileData::build("saves/savetestsave").unwrap();
+        assert_eq!(file_data, file_data2);
+    }
+
+    #[test]
+    fn test_find_article_offset() {
+        let file_data = FileData::build("saves/testsave0").unwrap();
+        assert_eq!(
+            file_data
+                .find_article_offset(4, 1200, TypeFamily::Item, true)
+                .unwrap(),
+            0x10f28
+        );
+    }
+
+    #[test]
+    fn test_find_inv_empty_slot() {
+        let file_data = FileData::build("saves/testsave4").unwrap();
+        assert_eq!(
+            file_data.find_inv_empty_slot(Location::Inventory).unwrap(),
+            0xcfb8
+        );
+        assert_eq!(
+            file_data.find_inv_empty_slot(Location::Storage).unwrap(),
+            0x15304
+        );
+
+        let file_data = FileData::build("saves/testsave0").unwrap();
+        assert_eq!(
+            file_data.find_inv_empty_slot(Location::Inventory).unwrap(),
+            0x8cd0
+        );
+    }
+
+    #[test]
+    fn test_flag_bits() {
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        file_data.set_flag(1083, 0b1010_0001);
+        assert!(file_data.get_flag_bit(1083, 0).unwrap());
+        assert!(!file_data.get_flag_bit(1083, 3).unwrap());
+
+        file_data.set_flag_bit(1083, 3, true).unwrap();
+        assert_eq!(file_data.get_flag(1083), 0b1010_1001);
+        file_data.set_flag_bit(1083, 7, false).unwrap();
+        assert_eq!(file_data.get_flag(1083), 0b0010_1001);
+        assert!(!file_data.toggle_flag_bit(1083, 0).unwrap());
+        assert!(file_data.toggle_flag_bit(1083, 6).unwrap());
+        assert_eq!(file_data.get_flag(1083), 0b0110_1000);
+
+        let error = file_data.set_flag_bit(1083, 8, true).unwrap_err();
+        assert_eq!(error.kind(), "InvalidFlagBit");
+        let error = file_data
+            .get_flag_bit(file_data.bytes.len(), 0)
+            .unwrap_err();
+        assert_eq!(error.kind(), "TruncatedFile");
+    }
+
+    #[test]
+    fn test_resize() {
+        //testsave2 ends in zeros
+        let original = FileData::build("saves/testsave2").unwrap();
+        let mut file_data = original.clone();
+        let size = file_data.bytes.len();
+        let block = [0xAA; 60];
+
+        //First empty entry, turned into a slots block
+        assert!(check_bytes(&file_data, 0x900, &EMPTY_ENTRY));
+        file_data.resize(0x900, 8, &block).unwrap();
+        assert_eq!(file_data.bytes.len(), size);
+        assert_eq!(file_data.offsets.username, original.offsets.username + 52);
+        assert_eq!(
+            file_data.offsets.lced_offset,
+            original.offsets.lced_offset + 52
+        );
+        assert!(check_bytes(&file_data, 0x900, &block));
+        assert_eq!(file_data.bytes[0x93c..], original.bytes[0x908..size - 52]);
+
+        //And back
+        file_data.resize(0x900, 60, &EMPTY_ENTRY).unwrap();
+        assert_eq!(file_data.offsets, original.offsets);
+        assert!(file_data.bytes == original.bytes);
+
+        //Growing past the zeros
+        let error = file_data.resize(0x900, 8, &vec![0xAA; size]).unwrap_err();
+        assert_eq!(error.kind(), "SaveFull");
+        assert_eq!(file_data.offsets, original.offsets);
+    }
+
+    #[test]
+    fn test_resize_non_zero_end() {
+        //testsave1 ends in the middle of a string, growing would cut it
+        let original = FileData::build("saves/testsave1").unwrap();
+        let mut file_data = original.clone();
+        assert_ne!(file_data.bytes[file_data.bytes.len() - 1], 0);
+        let error = file_data.resize(0x9f0, 8, &[0xAA; 60]).unwrap_err();
+        assert_eq!(error.kind(), "SaveFull");
+        assert_eq!(error.details(), serde_json::json!({ "needed": 52 }));
+        assert!(file_data.bytes == original.bytes);
+        assert_eq!(file_data.offsets, original.offsets);
+    }
+}
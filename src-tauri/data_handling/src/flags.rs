@@ -0,0 +1,356 @@
+//! Named event flags, such as NPC quests, shortcuts, lamps, item pickups and dialogues, read from
+//! the embedded flags list so they can be queried and set by name instead of by offset.
+//!
+//! A flag spans one or more bytes from its offset and only owns the bits of its mask, the other
+//! bits belong to unrelated events sharing the bytes and are never written.
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bosses,
+    enums::{Error, FlagCategory},
+    file::FileData,
+    resources,
+};
+
+/// A state of a flag, with the value of every byte it spans.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FlagState {
+    pub name: String,
+    pub values: Vec<u8>,
+}
+
+/// A state another flag has to be in before setting a flag. `flag` can also name a boss of the
+/// bosses list, whose states are "Alive" and "Dead".
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Prerequisite {
+    pub flag: String,
+    pub state: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventFlag {
+    pub name: String,
+    pub category: FlagCategory,
+    pub info: String,
+    pub rel_offset: usize,
+    //Bits of every byte the flag owns
+    pub mask: Vec<u8>,
+    pub states: Vec<FlagState>,
+    pub prerequisites: Vec<Prerequisite>,
+}
+
+/// A flag along with the state the save has it in, none if its bits match no known state.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FlagStatus {
+    #[serde(flatten)]
+    pub flag: EventFlag,
+    pub state: Option<String>,
+}
+
+impl EventFlag {
+    /// Name of the state the bits of the flag are in.
+    pub fn state(&self, file: &FileData) -> Result<Option<&str>, Error> {
+        let mut bits = Vec::with_capacity(self.mask.len());
+        for (i, mask) in self.mask.iter().enumerate() {
+            bits.push(file.try_get_flag(self.rel_offset + i)? & mask);
+        }
+        Ok(self
+            .states
+            .iter()
+            .find(|s| {
+                s.values
+                    .iter()
+                    .zip(&self.mask)
+                    .map(|(v, m)| v & m)
+                    .eq(bits.iter().copied())
+            })
+            .map(|s| s.name.as_str()))
+    }
+
+    fn status(&self, file: &FileData) -> Result<FlagStatus, Error> {
+        Ok(FlagStatus {
+            state: self.state(file)?.map(str::to_string),
+            flag: self.clone(),
+        })
+    }
+}
+
+/// Every flag of the embedded flags list.
+pub fn list() -> Result<Vec<EventFlag>, Error> {
+    Ok(serde_json::from_str(resources::FLAGS)?)
+}
+
+//State of the flag or boss a prerequisite names
+fn prerequisite_state(
+    file: &FileData,
+    flags: &[EventFlag],
+    prerequisite: &Prerequisite,
+) -> Result<Option<String>, Error> {
+    if let Ok(flag) = find(flags, &prerequisite.flag) {
+        return Ok(flag.state(file)?.map(str::to_string));
+    }
+    let boss = bosses::new(file)?
+        .into_iter()
+        .find(|b| b.name().eq_ignore_ascii_case(&prerequisite.flag))
+        .ok_or_else(|| Error::FlagNotFound {
+            name: prerequisite.flag.clone(),
+        })?;
+    Ok(match (boss.is_dead(), boss.unexpected_flags().is_empty()) {
+        (true, _) => Some(String::from("Dead")),
+        (false, true) => Some(String::from("Alive")),
+        (false, false) => None,
+    })
+}
+
+fn find<'a>(flags: &'a [EventFlag], name: &str) -> Result<&'a EventFlag, Error> {
+    flags
+        .iter()
+        .find(|f| f.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| Error::FlagNotFound {
+            name: name.to_string(),
+        })
+}
+
+/// The state of every flag of `flags`.
+pub fn statuses(file: &FileData, flags: &[EventFlag]) -> Result<Vec<FlagStatus>, Error> {
+    flags.iter().map(|f| f.status(file)).collect()
+}
+
+/// The state of the flag called `name`.
+pub fn query(file: &FileData, flags: &[EventFlag], name: &str) -> Result<FlagStatus, Error> {
+    find(flags, name)?.status(file)
+}
+
+/// Puts the flag called `name` in `state`, writing only the bits of its mask. Nothing is
+/// written unless every prerequisite is in the state it needs.
+pub fn set(
+    file: &mut FileData,
+    flags: &[EventFlag],
+    name: &str,
+    state: &str,
+) -> Result<FlagStatus, Error> {
+    let flag = find(flags, name)?;
+    let values = &flag
+        .states
+        .iter()
+        .find(|s| s.name.eq_ignore_ascii_case(state))
+        .ok_or_else(|| Error::InvalidFlagState {
+            name: flag.name.clone(),
+            state: state.to_string(),
+        })?
+        .values;
+
+    for prerequisite in &flag.prerequisites {
+        let current = prerequisite_state(file, flags, prerequisite)?;
+        if current.as_deref() != Some(prerequisite.state.as_str()) {
+            return Err(Error::FlagPrerequisite {
+                name: flag.name.clone(),
+                prerequisite: prerequisite.flag.clone(),
+                state: prerequisite.state.clone(),
+            });
+        }
+    }
+
+    for (i, (value, mask)) in values.iter().zip(&flag.mask).enumerate() {
+        let offset = flag.rel_offset + i;
+        let byte = file.try_get_flag(offset)?;
+        file.set_flag(offset, (byte & !mask) | (value & mask));
+    }
+    flag.status(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::build_save_data;
+
+    #[test]
+    fn flags_resource() {
+        let flags = list().unwrap();
+        assert!(!flags.is_empty());
+        for flag in &flags {
+            assert_eq!(flags.iter().filter(|f| f.name == flag.name).count(), 1);
+            assert!(!flag.states.is_empty());
+            for state in &flag.states {
+                assert_eq!(state.values.len(), flag.mask.len(), "{}", flag.name);
+            }
+            for (value, mask) in flag
+                .states
+                .iter()
+                .flat_map(|s| s.values.iter().zip(&flag.mask))
+            {
+                assert_eq!(value & !mask, 0, "{}", flag.name);
+            }
+            for prerequisite in &flag.prerequisites {
+                match find(&flags, &prerequisite.flag) {
+                    Ok(other) => {
+                        assert!(other.states.iter().any(|s| s.name == prerequisite.state))
+                    }
+                    Err(_) => {
+                        let save = build_save_data("testsave0");
+                        assert!(save.bosses.iter().any(|b| b.name() == prerequisite.flag));
+                        assert!(["Alive", "Dead"].contains(&prerequisite.state.as_str()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flags_set_and_query() {
+        let mut save = build_save_data("testsave0");
+        let file = &mut save.file;
+        let flags = list().unwrap();
+        assert_eq!(
+            query(file, &flags, "Enable Blood addled")
+                .unwrap()
+                .state
+                .as_deref(),
+            Some("Disabled")
+        );
+        let status = set(file, &flags, "enable blood addled", "enabled").unwrap();
+        assert_eq!(status.state.as_deref(), Some("Enabled"));
+        assert_eq!(file.get_flag(4127), 162);
+        let status = query(file, &flags, "Enable Blood addled").unwrap();
+        assert_eq!(status.state.as_deref(), Some("Enabled"));
+
+        let result = set(file, &flags, "Enable Blood addled", "Hostile");
+        assert_eq!(result.unwrap_err().kind(), "InvalidFlagState");
+        let result = query(file, &flags, "Open every shortcut");
+        assert_eq!(result.unwrap_err().kind(), "FlagNotFound");
+
+        //Two flags sharing a byte, the door needs the lever
+        let flags: Vec<EventFlag> = serde_json::from_str(
+            r#"[
+                {"name": "Lever", "category": "Shortcut", "info": "", "rel_offset": 1083,
+                 "mask": [8], "states": [{"name": "Pulled", "values": [8]}],
+                 "prerequisites": []},
+                {"name": "Door", "category": "Shortcut", "info": "", "rel_offset": 1083,
+                 "mask": [3], "states": [{"name": "Closed", "values": [0]},
+                 {"name": "Open", "values": [2]}],
+                 "prerequisites": [{"flag": "Lever", "state": "Pulled"}]}
+            ]"#,
+        )
+        .unwrap();
+        file.set_flag(1083, 0xF1);
+        let result = set(file, &flags, "Door", "Open");
+        assert_eq!(result.unwrap_err().kind(), "FlagPrerequisite");
+        assert_eq!(file.get_flag(1083), 0xF1);
+        assert_eq!(query(file, &flags, "Door").unwrap().state, None);
+
+        set(file, &flags, "Lever", "Pulled").unwrap();
+        assert_eq!(file.get_flag(1083), 0xF9);
+        set(file, &flags, "Door", "Open").unwrap();
+        assert_eq!(file.get_flag(1083), 0xFA);
+        let states: Vec<_> = statuses(file, &flags)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.state)
+            .collect();
+        assert_eq!(
+            states,
+            [Some("Pulled".to_string()), Some("Open".to_string())]
+        );
+    }
+    #[test]
+    fn flags_set_keeps_other_bits() {
+        let mut save = build_save_data("testsave0");
+        let flags = list().unwrap();
+
+        //Every bit of the bytes is set but the ones of the flag
+        let file = &mut save.file;
+        file.set_flag(4127, 0xFD);
+        set(file, &flags, "Enable Blood addled", "Enabled").unwrap();
+        assert_eq!(file.get_flag(4127), 0xFF);
+        set(file, &flags, "Enable Blood addled", "Disabled").unwrap();
+        assert_eq!(file.get_flag(4127), 0xFD);
+
+        //Maria's dialogue clears a bit of the first byte and sets one of the second
+        file.set_flag(1083, 0xFF);
+        file.set_flag(1084, 0x40);
+        set(file, &flags, "Restore Maria's dialogue", "Restored").unwrap();
+        assert_eq!(file.get_flag(1083), 0xEF);
+        assert_eq!(file.get_flag(1084), 0x48);
+
+        //The doll's lullaby only sets its two bits
+        file.set_flag(6689, 0x01);
+        file.set_flag(6690, 0x96);
+        set(file, &flags, "Enable Doll's lullaby", "Enabled").unwrap();
+        assert_eq!(file.get_flag(6689), 0x09);
+        assert_eq!(file.get_flag(6690), 0x97);
+    }
+
+    #[test]
+    fn flags_match_old_writes() {
+        //Bytes the flags page wrote before the flags list, whole bytes from the offset
+        let old_writes: [(&str, usize, &[u8]); 3] = [
+            ("Restore Maria's dialogue", 1083, &[0, 8]),
+            ("Enable Doll's lullaby", 6689, &[8, 1]),
+            ("Enable Blood addled", 4127, &[162]),
+        ];
+        let flags = list().unwrap();
+        for i in 0..10 {
+            let save = build_save_data(&format!("testsave{}", i));
+            for (name, offset, values) in old_writes {
+                let mut file = save.file.clone();
+                let flag = find(&flags, name).unwrap();
+                let state = &flag.states.last().unwrap().name;
+                //Maria's dialogue can't be restored in the saves where she is dead
+                if let Err(error) = set(&mut file, &flags, name, state) {
+                    assert_eq!(error.kind(), "FlagPrerequisite");
+                    assert_eq!(file.bytes, save.file.bytes);
+                    continue;
+                }
+                for (j, value) in values.iter().enumerate() {
+                    let before = save.file.get_flag(offset + j);
+                    let after = file.get_flag(offset + j);
+                    match (name, j) {
+                        //The other bits of the doll's byte are her dialogue progress, one of
+                        //them at most is set and the old page cleared it
+                        ("Enable Doll's lullaby", 1) => {
+                            assert_eq!(after, value | before, "testsave{}", i);
+                            assert!(before.count_ones() <= 1, "testsave{}", i);
+                        }
+                        _ => assert_eq!(after, *value, "{} in testsave{}", name, i),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flags_boss_prerequisite() {
+        let mut save = build_save_data("testsave0");
+        let flags = list().unwrap();
+        let maria = save
+            .bosses
+            .iter()
+            .position(|b| b.name() == "Lady Maria")
+            .unwrap();
+
+        //The dialogue before fighting Maria can't come back once she is dead
+        save.bosses[maria].kill(&mut save.file).unwrap();
+        let result = set(
+            &mut save.file,
+            &flags,
+            "Restore Maria's dialogue",
+            "Restored",
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), "FlagPrerequisite");
+        assert_eq!(
+            error.to_string(),
+            "Restore Maria's dialogue needs Lady Maria to be Alive first."
+        );
+
+        save.bosses[maria].revive(&mut save.file).unwrap();
+        let status = set(
+            &mut save.file,
+            &flags,
+            "Restore Maria's dialogue",
+            "Restored",
+        )
+        .unwrap();
+        assert_eq!(status.state.as_deref(), Some("Restored"));
+    }
+}
@@ -0,0 +1,294 @@
+//! Journal of the edits made to a save, used to undo and redo them.
+//!
+//! Every entry stores the byte ranges that changed and the parsed model from the
+//! other side of the edit, so stepping through the history never has to re-parse the file.
+use serde::Serialize;
+
+use super::{
+    bosses::Boss,
+    constants::HISTORY_LIMIT,
+    equipment::Equipment,
+    inventory::Inventory,
+    offsets::Offsets,
+    position::Pos,
+    save::SaveData,
+    stats::{OriginInfo, Stat},
+    username::Username,
+};
+
+//Changes closer than this are stored as a single range
+const MERGE_GAP: usize = 8;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ByteChange {
+    pub offset: usize,
+    pub before: Vec<u8>,
+    pub after: Vec<u8>,
+}
+
+/// Everything in a [`SaveData`] that is derived from the bytes.
+#[derive(Clone)]
+pub struct Snapshot {
+    offsets: Offsets,
+    stats: Vec<Stat>,
+    origin: Option<OriginInfo>,
+    inventory: Inventory,
+    storage: Inventory,
+    equipment: Equipment,
+    username: Username,
+    bosses: Vec<Boss>,
+    playtime: u32,
+    position: Pos,
+}
+
+impl Snapshot {
+    pub fn take(save: &SaveData) -> Snapshot {
+        Snapshot {
+            offsets: save.file.offsets.clone(),
+            stats: save.stats.clone(),
+            origin: save.origin,
+            inventory: save.inventory.clone(),
+            storage: save.storage.clone(),
+            equipment: save.equipment.clone(),
+            username: save.username.clone(),
+            bosses: save.bosses.clone(),
+            playtime: save.playtime,
+            position: save.position.clone(),
+        }
+    }
+
+    pub fn restore(self, save: &mut SaveData) {
+        save.file.offsets = self.offsets;
+        save.stats = self.stats;
+        save.origin = self.origin;
+        save.inventory = self.inventory;
+        save.storage = self.storage;
+        save.equipment = self.equipment;
+        save.username = self.username;
+        save.bosses = self.bosses;
+        save.playtime = self.playtime;
+        save.position = self.position;
+    }
+}
+
+/// State of a save before an edit, created with [`SaveData::checkpoint`].
+pub struct Checkpoint {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) snapshot: Snapshot,
+}
+
+#[derive(Clone)]
+pub struct Edit {
+    pub label: String,
+    pub changes: Vec<ByteChange>,
+    //Model before the edit while it is in the undo stack, after it while in the redo stack
+    pub(crate) snapshot: Snapshot,
+}
+
+impl Edit {
+    ///Writes the before (undo) or after (redo) side of every change
+    pub fn apply(&self, bytes: &mut [u8], undo: bool) {
+        for change in &self.changes {
+            let values = if undo { &change.before } else { &change.after };
+            bytes[change.offset..change.offset + values.len()].copy_from_slice(values);
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub label: String,
+    pub ranges: usize,
+    pub bytes: usize,
+}
+
+impl From<&Edit> for HistoryEntry {
+    fn from(edit: &Edit) -> Self {
+        HistoryEntry {
+            label: edit.label.clone(),
+            ranges: edit.changes.len(),
+            bytes: edit.changes.iter().map(|c| c.after.len()).sum(),
+        }
+    }
+}
+
+/// The undo and redo stacks of a session, the last element is the most recent edit.
+#[derive(Clone, Default)]
+pub struct History {
+    pub(crate) undo: Vec<Edit>,
+    pub(crate) redo: Vec<Edit>,
+}
+
+impl History {
+    pub fn push(&mut self, edit: Edit) {
+        if self.undo.len() == HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn undo_entries(&self) -> Vec<HistoryEntry> {
+        self.undo.iter().map(HistoryEntry::from).collect()
+    }
+
+    pub fn redo_entries(&self) -> Vec<HistoryEntry> {
+        self.redo.iter().map(HistoryEntry::from).collect()
+    }
+}
+
+impl Serialize for History {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("History", 2)?;
+        state.serialize_field("undo", &self.undo_entries())?;
+        state.serialize_field("redo", &self.redo_entries())?;
+        state.end()
+    }
+}
+
+/// Byte ranges that differ between `before` and `after`, both must have the same length.
+pub fn diff(before: &[u8], after: &[u8]) -> Vec<ByteChange> {
+    let mut changes: Vec<ByteChange> = Vec::new();
+    let mut i = 0;
+
+    while i < before.len() {
+        if before[i] == after[i] {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let mut end = i + 1;
+        //Extend the range while the next difference is close enough
+        let mut j = end;
+        while j < before.len() && j - end <= MERGE_GAP {
+            if before[j] != after[j] {
+                end = j + 1;
+            }
+            j += 1;
+        }
+
+        changes.push(ByteChange {
+            offset: start,
+            before: before[start..end].to_vec(),
+            after: after[start..end].to_vec(),
+        });
+        i = end;
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::Error, utils::test_utils::build_save_data};
+
+    #[test]
+    fn test_diff() {
+        let before = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let mut after = before;
+        after[1] = 1;
+        after[3] = 1;
+        after[19] = 2;
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                ByteChange {
+                    offset: 1,
+                    before: vec![0, 0, 0],
+                    after: vec![1, 0, 1],
+                },
+                ByteChange {
+                    offset: 19,
+                    before: vec![0],
+                    after: vec![2],
+                }
+            ]
+        );
+        assert!(diff(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut save = build_save_data("testsave0");
+        let original = save.file.bytes.clone();
+        let original_username = save.username.clone();
+
+        assert!(matches!(save.undo(), Err(Error::NothingToUndo)));
+        assert!(matches!(save.redo(), Err(Error::NothingToRedo)));
+
+        //Two edits
+        let checkpoint = save.checkpoint();
+        save.username
+            .set(&mut save.file, "Undone".to_string())
+            .unwrap();
+        assert!(save.commit(checkpoint, "set_username"));
+
+        let checkpoint = save.checkpoint();
+        save.inventory
+            .add_item(&mut save.file, 1000, 5, false)
+            .unwrap();
+        assert!(save.commit(checkpoint, "add_item"));
+        let edited = save.file.bytes.clone();
+        let edited_inventory = serde_json::to_value(&save.inventory).unwrap();
+
+        //Nothing changed, nothing recorded
+        let checkpoint = save.checkpoint();
+        assert!(!save.commit(checkpoint, "noop"));
+
+        let labels: Vec<String> = save
+            .history
+            .undo_entries()
+            .into_iter()
+            .map(|e| e.label)
+            .collect();
+        assert_eq!(labels, ["set_username", "add_item"]);
+
+        assert_eq!(save.undo().unwrap(), "add_item");
+        assert_eq!(save.undo().unwrap(), "set_username");
+        assert_eq!(save.file.bytes, original);
+        assert_eq!(save.username, original_username);
+        assert!(!save.history.can_undo());
+
+        assert_eq!(save.redo().unwrap(), "set_username");
+        assert_eq!(save.redo().unwrap(), "add_item");
+        assert_eq!(save.file.bytes, edited);
+        assert_eq!(save.username.string, "Undone");
+        assert_eq!(
+            serde_json::to_value(&save.inventory).unwrap(),
+            edited_inventory
+        );
+
+        //A new edit drops the redo stack
+        save.undo().unwrap();
+        let checkpoint = save.checkpoint();
+        save.file.set_playtime([1, 2, 3, 4]);
+        save.commit(checkpoint, "set_playtime");
+        assert!(!save.history.can_redo());
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut save = build_save_data("testsave1");
+        for i in 0..HISTORY_LIMIT + 5 {
+            let checkpoint = save.checkpoint();
+            save.file.set_playtime((i as u32 + 1).to_le_bytes());
+            save.commit(checkpoint, &format!("edit {}", i));
+        }
+        let entries = save.history.undo_entries();
+        assert_eq!(entries.len(), HISTORY_LIMIT);
+        assert_eq!(entries[0].label, "edit 5");
+    }
+}
@@ -0,0 +1,2732 @@
+//! Inventory and storage parsing and editing.
+use super::{
+    article::{scale_weapon_info, Article, ItemInfo, WeaponMods},
+    constants::*,
+    enums::{ArticleType, Error, Imprint, Location, SlotShape, TypeFamily, UpgradeType},
+    file::FileData,
+    resources,
+    slots::Slot,
+    upgrades::{self, Upgrade},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{self, json, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Articles and upgrades of the inventory or the storage, grouped by type.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Inventory {
+    pub articles: HashMap<ArticleType, Vec<Article>>,
+    pub upgrades: HashMap<UpgradeType, Vec<Upgrade>>,
+    //If the first slot of the inventory contains an article store its type
+    pub first_article: Option<ArticleType>,
+    //If it contains an upgrade store its type
+    pub first_upgrade: Option<UpgradeType>,
+}
+
+impl Inventory {
+    /// Parses the articles and upgrades between `inv` and, for the inventory, the key items in `key`.
+    /// Parsed upgrades and slots are removed from `all_upgrades` and `all_slots`.
+    pub fn build(
+        file_data: &FileData,
+        inv: (usize, usize),
+        key: (usize, usize),
+        all_upgrades: &mut HashMap<u32, (Upgrade, UpgradeType)>,
+        all_slots: &mut HashMap<u64, Vec<Slot>>,
+    ) -> Inventory {
+        let mut has_slots = false;
+        let mut articles = HashMap::new();
+        let mut upgrades = HashMap::new();
+        let mut first_upgrade = None;
+        let mut first_article = None;
+        let mut first = true;
+
+        let mut parse = |start: usize, end: usize| {
+            for (index, i) in (start..end).step_by(16).enumerate() {
+                let number = file_data.bytes[i];
+                let mut id = u32::from_le_bytes([
+                    file_data.bytes[i + 8],
+                    file_data.bytes[i + 9],
+                    file_data.bytes[i + 10],
+                    0,
+                ]);
+                let first_part = u32::from_le_bytes([
+                    file_data.bytes[i + 4],
+                    file_data.bytes[i + 5],
+                    file_data.bytes[i + 6],
+                    file_data.bytes[i + 7],
+                ]);
+                let second_part = u32::from_le_bytes([
+                    file_data.bytes[i + 8],
+                    file_data.bytes[i + 9],
+                    file_data.bytes[i + 10],
+                    file_data.bytes[i + 11],
+                ]);
+                let amount = u32::from_le_bytes([
+                    file_data.bytes[i + 12],
+                    file_data.bytes[i + 13],
+                    file_data.bytes[i + 14],
+                    file_data.bytes[i + 15],
+                ]);
+
+                let result = match (file_data.bytes[i + 7], file_data.bytes[i + 11]) {
+                    (0xB0, 0x40) => {
+                        has_slots = false;
+                        get_info_item(id)
+                    }
+                    (_, 0x10) => {
+                        has_slots = true;
+                        get_info_armor(id)
+                    }
+                    _ => {
+                        id = second_part;
+                        has_slots = true;
+                        get_info_weapon(id)
+                    }
+                };
+
+                if let Ok((info, article_type)) = result {
+                    let mut slots = None;
+                    if has_slots {
+                        let key = u64::from_le_bytes([
+                            file_data.bytes[i + 4],
+                            file_data.bytes[i + 5],
+                            file_data.bytes[i + 6],
+                            file_data.bytes[i + 7],
+                            file_data.bytes[i + 8],
+                            file_data.bytes[i + 9],
+                            file_data.bytes[i + 10],
+                            file_data.bytes[i + 11],
+                        ]);
+                        slots = all_slots.remove(&key);
+                        if slots.is_none() {
+                            continue;
+                        }
+                    }
+
+                    let mut article = Article {
+                        number,
+                        id,
+                        first_part,
+                        second_part,
+                        amount,
+                        info,
+                        article_type,
+                        type_family: article_type.into(),
+                        slots,
+                        index,
+                    };
+                    if first {
+                        first_article = Some(article_type);
+                        first = false;
+                    }
+                    let category = articles.entry(article_type).or_insert(Vec::new());
+                    article.index = category.len();
+                    category.push(article);
+                } else if let Some(mut upgrade) = all_upgrades.remove(&first_part) {
+                    if first {
+                        first_upgrade = Some(upgrade.1);
+                        first = false;
+                    }
+                    let category = upgrades.entry(upgrade.1).or_insert(Vec::new());
+                    upgrade.0.index = category.len();
+                    upgrade.0.number = number;
+                    category.push(upgrade.0);
+                };
+            }
+        };
+        parse(inv.0, inv.1); //parse the inventory
+        parse(key.0, key.1); //parse the key inventory
+        Inventory {
+            articles,
+            upgrades,
+            first_article,
+            first_upgrade,
+        }
+    }
+    ///Modifies the amount of an article
+    pub fn edit_item(
+        &mut self,
+        file_data: &mut FileData,
+        number: u8,
+        id: u32,
+        value: u32,
+        is_storage: bool,
+    ) -> Result<(), Error> {
+        let value_endian = u32::to_le_bytes(value);
+        let mut found = false;
+        for (k, v) in self.articles.iter_mut() {
+            let family: TypeFamily = k.to_owned().into();
+            if family == TypeFamily::Item {
+                if let Some(item) = v.iter_mut().find(|item| item.id == id) {
+                    if k == &ArticleType::Key {
+                        return Err(Error::KeyItem { id });
+                    }
+                    item.amount = value;
+                    found = true;
+                    break;
+                }
+            }
+        }
+
+        let opt = file_data.find_article_offset(number, id, TypeFamily::Item, is_storage);
+        if let Some(offset) = opt {
+            for (i, o) in (offset + 12..offset + 16).enumerate() {
+                file_data.bytes[o] = value_endian[i];
+            }
+        }
+
+        if opt.is_none() || !found {
+            return Err(Error::ArticleNotFound {
+                number,
+                id,
+                location: Location::from(is_storage),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds `quantity` of the item `id` to the first empty slot.
+    pub fn add_item(
+        &mut self,
+        file_data: &mut FileData,
+        id: u32,
+        quantity: u32,
+        is_storage: bool,
+    ) -> Result<&mut Inventory, Error> {
+        let (info, article_type) = get_info_item(id)?;
+
+        let new_item = Article {
+            number: 0,
+            id,
+            first_part: 0xB0000000 | (id & 0x00FFFFFF),
+            second_part: 0x40000000 | (id & 0x00FFFFFF),
+            info,
+            amount: quantity,
+            article_type,
+            type_family: article_type.into(),
+            slots: None,
+            index: 0,
+        };
+        self.put_article(file_data, new_item, is_storage)?;
+
+        Ok(self)
+    }
+
+    /// Adds a weapon with a new slots block. `id` is the base id of the weapon, the imprint and
+    /// upgrade level are added to it and the slots without a shape in `shapes` are closed.
+    /// Creating the block moves the rest of the save, use [`SaveData::add_weapon`] to keep
+    /// the position in sync.
+    ///
+    /// [`SaveData::add_weapon`]: crate::save::SaveData::add_weapon
+    pub fn add_weapon(
+        &mut self,
+        file_data: &mut FileData,
+        id: u32,
+        imprint: Option<Imprint>,
+        upgrade_level: u8,
+        shapes: &[SlotShape],
+        is_storage: bool,
+    ) -> Result<Article, Error> {
+        if upgrade_level > MAX_UPGRADE_LEVEL {
+            return Err(Error::InvalidUpgradeLevel {
+                level: upgrade_level,
+            });
+        }
+        if shapes.len() > 5 {
+            return Err(Error::InvalidSlotIndex { id, index: 5 });
+        }
+        //80000 is part of the id of a few weapons, not an imprint
+        let base_id = match id % 100000 {
+            80000 => id,
+            mods => id - mods,
+        };
+        let second_part = base_id
+            + match imprint {
+                None => 0,
+                Some(Imprint::Uncanny) => 10000,
+                Some(Imprint::Lost) => 20000,
+            }
+            + 100 * upgrade_level as u32;
+        let (info, article_type) = get_info_weapon(second_part)?;
+
+        //Reuse the durability of another copy of the weapon
+        let durability = pool_entries(file_data)
+            .into_iter()
+            .filter(|&(offset, length)| length == 60 && file_data.bytes[offset + 3] == 0x80)
+            .map(|(offset, _)| {
+                (
+                    read_u32(file_data, offset + 4),
+                    read_u32(file_data, offset + 8),
+                )
+            })
+            .find(|&(block_id, durability)| block_id / 100000 == base_id / 100000 && durability > 1)
+            .map_or(DEFAULT_DURABILITY, |(_, durability)| durability);
+
+        let mut block_shapes = [SlotShape::Closed; 5];
+        block_shapes[..shapes.len()].copy_from_slice(shapes);
+        let weapon = Article {
+            number: 0,
+            id: second_part,
+            first_part: 0x80800000,
+            second_part,
+            amount: 1,
+            info,
+            article_type,
+            type_family: TypeFamily::Weapon,
+            slots: None,
+            index: 0,
+        };
+        self.add_with_slots(file_data, weapon, durability, block_shapes, is_storage)
+    }
+
+    /// Adds an armor with a new slots block, armors have every slot closed.
+    /// Creating the block moves the rest of the save, use [`SaveData::add_armor`] to keep
+    /// the position in sync.
+    ///
+    /// [`SaveData::add_armor`]: crate::save::SaveData::add_armor
+    pub fn add_armor(
+        &mut self,
+        file_data: &mut FileData,
+        id: u32,
+        is_storage: bool,
+    ) -> Result<Article, Error> {
+        let (info, article_type) = get_info_armor(id)?;
+        let armor = Article {
+            number: 0,
+            id,
+            first_part: 0x90800000,
+            second_part: 0x10000000 | id,
+            amount: 1,
+            info,
+            article_type,
+            type_family: TypeFamily::Armor,
+            slots: None,
+            index: 0,
+        };
+        //Armors in the inventory always have a durability of 1
+        self.add_with_slots(file_data, armor, 1, [SlotShape::Closed; 5], is_storage)
+    }
+
+    //Writes the slots block and the inventory slot of a weapon or armor, the first part of
+    //the article only has its marker and gets the next free counter
+    fn add_with_slots(
+        &mut self,
+        file_data: &mut FileData,
+        mut article: Article,
+        durability: u32,
+        shapes: [SlotShape; 5],
+        is_storage: bool,
+    ) -> Result<Article, Error> {
+        //Check everything before touching the file
+        let location = Location::from(is_storage);
+        if file_data.find_inv_empty_slot(location).is_none() {
+            return Err(Error::InventoryFull { location });
+        }
+        let entries = pool_entries(file_data);
+        let counter = next_counter(file_data, &entries).ok_or(Error::SaveFull { needed: 60 })?;
+        article.first_part |= counter;
+
+        let mut block = Vec::with_capacity(60);
+        block.extend(article.first_part.to_le_bytes());
+        block.extend(article.second_part.to_le_bytes());
+        block.extend(durability.to_le_bytes());
+        block.extend([0, 0, 0, 0, 0x01, 0x00, 0x00, 0x00]);
+        for shape in shapes {
+            let shape: [u8; 4] = shape.into();
+            block.extend(shape);
+            block.extend([0, 0, 0, 0]);
+        }
+        insert_slots_block(file_data, &block)?;
+
+        article.slots = Some(
+            shapes
+                .iter()
+                .enumerate()
+                .map(|(i, shape)| Slot::build(*shape, None, i))
+                .collect(),
+        );
+        Ok(self.put_article(file_data, article, is_storage)?.clone())
+    }
+
+    //Writes an article that is in no inventory to the first empty slot and stores it at the
+    //end of its type
+    fn put_article(
+        &mut self,
+        file_data: &mut FileData,
+        mut article: Article,
+        is_storage: bool,
+    ) -> Result<&mut Article, Error> {
+        //The inventories already span every slot the game allows
+        let location = Location::from(is_storage);
+        let empty_slot_index = file_data
+            .find_inv_empty_slot(location)
+            .ok_or(Error::InventoryFull { location })?;
+
+        file_data.bytes[empty_slot_index..empty_slot_index + 4]
+            .copy_from_slice(&article.first_part.to_le_bytes());
+        file_data.bytes[empty_slot_index + 4..empty_slot_index + 8]
+            .copy_from_slice(&article.second_part.to_le_bytes());
+        file_data.bytes[empty_slot_index + 8..empty_slot_index + 12]
+            .copy_from_slice(&article.amount.to_le_bytes());
+        update_counters(file_data, is_storage, 1);
+        //The slot keeps its number, it comes before the first part
+        article.number = file_data.bytes[empty_slot_index - 4];
+
+        let vec = self.articles.entry(article.article_type).or_default();
+        article.index = vec.len();
+        let (article_type, index) = (article.article_type, article.index);
+        vec.push(article);
+        self.sync_first_number(file_data, is_storage);
+        Ok(&mut self.articles.get_mut(&article_type).unwrap()[index])
+    }
+
+    //Clears the slot of an article and removes it from the model, the slots block is left as is
+    fn take_article(
+        &mut self,
+        file_data: &mut FileData,
+        article_type: ArticleType,
+        article_index: usize,
+        is_storage: bool,
+    ) -> Result<Article, Error> {
+        let articles_of_type = self
+            .articles
+            .get_mut(&article_type)
+            .ok_or(Error::NoArticlesOfType { article_type })?;
+        let article = articles_of_type
+            .get(article_index)
+            .ok_or(Error::InvalidArticleIndex {
+                article_type,
+                index: article_index,
+            })?;
+        let offset = file_data
+            .find_article_offset(article.number, article.id, article.type_family, is_storage)
+            .ok_or(Error::ArticleNotFound {
+                number: article.number,
+                id: article.id,
+                location: Location::from(is_storage),
+            })?;
+
+        //Clear the slot, the number before the first part is kept
+        let empty_slot = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+        file_data.bytes[offset + 4..offset + 16].copy_from_slice(&empty_slot);
+        update_counters(file_data, is_storage, -1);
+
+        for article in articles_of_type.iter_mut().skip(article_index + 1) {
+            article.index -= 1;
+        }
+        let article = articles_of_type.remove(article_index);
+        self.sync_first_number(file_data, is_storage);
+        Ok(article)
+    }
+
+    //The number of the first storage slot is also the first storage counter,
+    //whatever is in that slot gets the new value
+    fn sync_first_number(&mut self, file_data: &FileData, is_storage: bool) {
+        if !is_storage {
+            return;
+        }
+        let start = file_data.offsets.storage.0;
+        let number = file_data.bytes[start];
+        let first_part = read_u32(file_data, start + 4);
+        let second_part = read_u32(file_data, start + 8);
+        if let Some(article) = self
+            .articles
+            .values_mut()
+            .flatten()
+            .find(|a| a.first_part == first_part && a.second_part == second_part)
+        {
+            article.number = number;
+        } else if let Some(upgrade) = self
+            .upgrades
+            .values_mut()
+            .flatten()
+            .find(|u| u.id == first_part && u.source == second_part)
+        {
+            upgrade.number = number;
+        }
+    }
+
+    /// Creates a gem or rune that wasn't in the save and puts it in the first empty slot.
+    /// The record goes after the last upgrade and the first unused entry after it is freed,
+    /// so the blocks in between move 40B and the rest of the save 32B.
+    pub fn create_upgrade(
+        &mut self,
+        file_data: &mut FileData,
+        upgrade_type: UpgradeType,
+        shape: &str,
+        effects: [u32; 6],
+        source: u32,
+        is_storage: bool,
+    ) -> Result<Upgrade, Error> {
+        //Check everything before touching the file
+        let location = Location::from(is_storage);
+        if file_data.find_inv_empty_slot(location).is_none() {
+            return Err(Error::InventoryFull { location });
+        }
+        let shape = upgrades::shape_byte(shape, upgrade_type)?;
+        let upgrades_json: Value = serde_json::from_str(resources::UPGRADES)?;
+        for effect in effects {
+            upgrades::effect_info(&upgrades_json, upgrade_type, effect)?;
+        }
+        let entries = pool_entries(file_data);
+        let counter = next_counter(file_data, &entries).ok_or(Error::SaveFull { needed: 40 })?;
+
+        let id = 0xC0800000 | counter;
+        let mut record = Vec::with_capacity(40);
+        record.extend(id.to_le_bytes());
+        record.extend(source.to_le_bytes());
+        record.extend((u8::from(upgrade_type) as u32).to_le_bytes());
+        record.extend((shape as u32).to_le_bytes());
+        for effect in effects {
+            record.extend(effect.to_le_bytes());
+        }
+        insert_upgrade_record(file_data, &record)?;
+
+        let mut upgrade = match upgrades::parse_upgrades(file_data)?.remove(&id) {
+            Some((upgrade, _)) => upgrade,
+            None => return Err(Error::UpgradeNotFound { id }),
+        };
+        let empty_slot_index = file_data
+            .find_inv_empty_slot(location)
+            .ok_or(Error::InventoryFull { location })?;
+        upgrade.number = file_data.bytes[empty_slot_index - 4];
+        self.add_upgrade(file_data, upgrade, is_storage)?;
+        Ok(self.upgrades[&upgrade_type].last().unwrap().clone())
+    }
+
+    ///This method asumes that upgrade exists in file_data and it's not in the inventory
+    pub fn add_upgrade(
+        &mut self,
+        file_data: &mut FileData,
+        mut upgrade: Upgrade,
+        is_storage: bool,
+    ) -> Result<(), Error> {
+        //The inventories already span every slot the game allows
+        let location = Location::from(is_storage);
+        let empty_slot_index = file_data
+            .find_inv_empty_slot(location)
+            .ok_or(Error::InventoryFull { location })?;
+        let uname = file_data.offsets.username;
+        let (first_counter_index, second_counter_index) = {
+            if !is_storage {
+                (
+                    uname + USERNAME_TO_FIRST_INVENTORY_COUNTER,
+                    uname + USERNAME_TO_SECOND_INVENTORY_COUNTER,
+                )
+            } else {
+                (
+                    uname + USERNAME_TO_FIRST_STORAGE_COUNTER,
+                    uname + USERNAME_TO_SECOND_STORAGE_COUNTER,
+                )
+            }
+        };
+
+        let endian_id = u32::to_le_bytes(upgrade.id);
+        let endian_source = u32::to_le_bytes(upgrade.source);
+        let endian_quantity = [0x01, 0x00, 0x00, 0x00];
+
+        file_data.bytes[empty_slot_index..empty_slot_index + 4].copy_from_slice(&endian_id);
+        for i in 4..8 {
+            file_data.bytes[empty_slot_index + i] = endian_source[i % 4];
+        }
+        for i in 8..12 {
+            file_data.bytes[empty_slot_index + i] = endian_quantity[i % 4];
+        }
+
+        let new_counter_value = u32::from_le_bytes([
+            file_data.bytes[first_counter_index],
+            file_data.bytes[first_counter_index + 1],
+            file_data.bytes[first_counter_index + 2],
+            file_data.bytes[first_counter_index + 3],
+        ]) + 1;
+        let new_counter_value_bytes: [u8; 4] = new_counter_value.to_le_bytes();
+        file_data.bytes[first_counter_index..first_counter_index + 4]
+            .copy_from_slice(&new_counter_value_bytes);
+
+        let new_counter_value = u32::from_le_bytes([
+            file_data.bytes[second_counter_index],
+            file_data.bytes[second_counter_index + 1],
+            file_data.bytes[second_counter_index + 2],
+            file_data.bytes[second_counter_index + 3],
+        ]) + 1;
+        let new_counter_value_bytes: [u8; 4] = new_counter_value.to_le_bytes();
+        file_data.bytes[second_counter_index..second_counter_index + 4]
+            .copy_from_slice(&new_counter_value_bytes);
+
+        //Find the first item of the storage to increase it's index
+        let mut found = false;
+        if is_storage {
+            if let Some(article_type) = self.first_article {
+                if let Some(ref mut articles_of_type) = self.articles.get_mut(&article_type) {
+                    if let Some(first) = articles_of_type.first_mut() {
+                        first.number += 1;
+                        found = true;
+                    }
+                }
+            } else if let Some(upgrade_type) = self.first_upgrade {
+                if let Some(ref mut upgrades_of_type) = self.upgrades.get_mut(&upgrade_type) {
+                    if let Some(first) = upgrades_of_type.first_mut() {
+                        first.number += 1;
+                        found = true;
+                    }
+                }
+            }
+            if !found {
+                upgrade.number = file_data.bytes[first_counter_index];
+            }
+        } else {
+            //The slot keeps its number, it comes before the first part
+            upgrade.number = file_data.bytes[empty_slot_index - 4];
+        }
+
+        let vec = self.upgrades.entry(upgrade.upgrade_type).or_default();
+        upgrade.index = vec.len();
+        vec.push(upgrade);
+        Ok(())
+    }
+
+    ///This method asumes that the upgrade it's not in the inventory already
+    pub fn unequip_gem(
+        &mut self,
+        file_data: &mut FileData,
+        article_type: ArticleType,
+        article_index: usize,
+        slot_index: usize,
+        is_storage: bool,
+    ) -> Result<(), Error> {
+        //Check before touching the slot, the gem needs somewhere to go
+        let location = Location::from(is_storage);
+        if file_data.find_inv_empty_slot(location).is_none() {
+            return Err(Error::InventoryFull { location });
+        }
+        if let Some(articles_of_type) = self.articles.get_mut(&article_type) {
+            if let Some(article) = articles_of_type.get_mut(article_index) {
+                if let Some(ref mut slots) = &mut article.slots {
+                    if let Some(slot) = slots.get_mut(slot_index) {
+                        if let Some(ref mut gem) = &mut slot.gem {
+                            //Remove the gem in file_data
+                            let first_part = article.first_part.to_le_bytes();
+                            let second_part = article.second_part.to_le_bytes();
+                            let mut found = false;
+                            for i in
+                                file_data.offsets.equipped_gems.0..file_data.offsets.equipped_gems.1
+                            {
+                                if (file_data.bytes[i..i + 4] == first_part)
+                                    && (file_data.bytes[i + 4..i + 8] == second_part)
+                                {
+                                    found = true;
+                                    //24 is the index for the first gem id
+                                    let slot_index = i + 24 + 8 * slot_index;
+                                    for j in slot_index..slot_index + 4 {
+                                        file_data.bytes[j] = 0;
+                                    }
+                                    break;
+                                }
+                            }
+                            if !found {
+                                return Err(Error::SlotsNotFound {
+                                    first_part: article.first_part,
+                                    second_part: article.second_part,
+                                });
+                            }
+
+                            //Remove the gem
+                            let gem = gem.to_owned();
+                            slot.gem = None;
+
+                            self.add_upgrade(file_data, gem, is_storage)
+                        } else {
+                            Err(Error::SlotEmpty {
+                                id: article.id,
+                                index: slot_index,
+                            })
+                        }
+                    } else {
+                        Err(Error::InvalidSlotIndex {
+                            id: article.id,
+                            index: slot_index,
+                        })
+                    }
+                } else {
+                    Err(Error::NoSlots { id: article.id })
+                }
+            } else {
+                Err(Error::InvalidArticleIndex {
+                    article_type,
+                    index: article_index,
+                })
+            }
+        } else {
+            Err(Error::NoArticlesOfType { article_type })
+        }
+    }
+
+    pub fn remove_upgrade(
+        &mut self,
+        file_data: &mut FileData,
+        upgrade_type: UpgradeType,
+        upgrade_index: usize,
+        is_storage: bool,
+    ) -> Result<Upgrade, Error> {
+        if let Some(upgrades_of_type) = self.upgrades.get_mut(&upgrade_type) {
+            if upgrade_index < upgrades_of_type.len() {
+                //Remove the upgrade from the inventory
+                let first_part = upgrades_of_type[upgrade_index].id.to_le_bytes();
+                let second_part = upgrades_of_type[upgrade_index].source.to_le_bytes();
+                let mut found = false;
+                let (start, end) = match is_storage {
+                    true => file_data.offsets.storage,
+                    false => file_data.offsets.inventory,
+                };
+                //Leave the first 4 bytes
+                let empty_slot = [0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0];
+                for i in (start..end).step_by(16) {
+                    if (file_data.bytes[i + 4..i + 8] == first_part)
+                        && (file_data.bytes[i + 8..i + 12] == second_part)
+                    {
+                        found = true;
+                        for j in i + 4..i + 16 {
+                            file_data.bytes[j] = empty_slot[j - i - 4];
+                        }
+                        break;
+                    }
+                }
+                if !found {
+                    return Err(Error::UpgradeNotFound {
+                        id: upgrades_of_type[upgrade_index].id,
+                    });
+                }
+                //Update the index of the upgrades after the one to be removed
+                for upgrade in upgrades_of_type.iter_mut().skip(upgrade_index + 1) {
+                    upgrade.index -= 1;
+                }
+                Ok(upgrades_of_type.remove(upgrade_index))
+            } else {
+                Err(Error::InvalidUpgradeIndex {
+                    upgrade_type,
+                    index: upgrade_index,
+                })
+            }
+        } else {
+            Err(Error::NoUpgradesOfType { upgrade_type })
+        }
+    }
+
+    /// Destroys a gem or rune of this inventory. Every slot of the inventory and every weapon
+    /// slot with it are cleared and its record is removed from the upgrades table, which
+    /// moves the rest of the save. [`SaveData::destroy_upgrade`] also clears the other inventory.
+    ///
+    /// [`SaveData::destroy_upgrade`]: crate::save::SaveData::destroy_upgrade
+    pub fn destroy_upgrade(
+        &mut self,
+        file_data: &mut FileData,
+        upgrade_type: UpgradeType,
+        upgrade_index: usize,
+        is_storage: bool,
+    ) -> Result<Upgrade, Error> {
+        let id = self
+            .upgrades
+            .get(&upgrade_type)
+            .ok_or(Error::NoUpgradesOfType { upgrade_type })?
+            .get(upgrade_index)
+            .ok_or(Error::InvalidUpgradeIndex {
+                upgrade_type,
+                index: upgrade_index,
+            })?
+            .id;
+        if file_data.find_upgrade_offset(id).is_none() {
+            return Err(Error::UpgradeNotFound { id });
+        }
+        let upgrade = self.clear_upgrade(file_data, id, is_storage)?;
+        remove_upgrade_records(file_data, &[id])?;
+        upgrade.ok_or(Error::UpgradeNotFound { id })
+    }
+
+    /// Removes every copy of an upgrade from the slots of the inventory and the gem slots
+    /// of its articles, the record is kept. Returns the first copy found.
+    pub fn clear_upgrade(
+        &mut self,
+        file_data: &mut FileData,
+        id: u32,
+        is_storage: bool,
+    ) -> Result<Option<Upgrade>, Error> {
+        let mut found = None;
+        while let Some((upgrade_type, index)) = self
+            .upgrades
+            .iter()
+            .find_map(|(t, v)| v.iter().position(|u| u.id == id).map(|i| (*t, i)))
+        {
+            let upgrade = self.remove_upgrade(file_data, upgrade_type, index, is_storage)?;
+            update_counters(file_data, is_storage, -1);
+            found.get_or_insert(upgrade);
+        }
+        for article in self.articles.values_mut().flatten() {
+            let slots = match article.slots.as_mut() {
+                Some(slots) => slots,
+                None => continue,
+            };
+            for slot in slots.iter_mut() {
+                if slot.gem.as_ref().map(|gem| gem.id) != Some(id) {
+                    continue;
+                }
+                let block = find_slots_block(file_data, article.first_part, article.second_part)
+                    .ok_or(Error::SlotsNotFound {
+                        first_part: article.first_part,
+                        second_part: article.second_part,
+                    })?;
+                //24 is the index for the first gem id
+                let gem_offset = block + 24 + 8 * slot.index;
+                file_data.bytes[gem_offset..gem_offset + 4].copy_from_slice(&[0, 0, 0, 0]);
+                if let Some(gem) = slot.gem.take() {
+                    found.get_or_insert(gem);
+                }
+            }
+        }
+        self.sync_first_number(file_data, is_storage);
+        Ok(found)
+    }
+
+    /// Removes an article, clearing its slot and the block with its gem slots.
+    /// The gems equipped in it are moved to the same inventory, the removed article is returned.
+    /// Freeing the block moves the rest of the save, use [`SaveData::remove_article`] to keep
+    /// the position in sync.
+    ///
+    /// [`SaveData::remove_article`]: crate::save::SaveData::remove_article
+    pub fn remove_article(
+        &mut self,
+        file_data: &mut FileData,
+        article_type: ArticleType,
+        article_index: usize,
+        is_storage: bool,
+    ) -> Result<Article, Error> {
+        let article = self
+            .articles
+            .get(&article_type)
+            .ok_or(Error::NoArticlesOfType { article_type })?
+            .get(article_index)
+            .ok_or(Error::InvalidArticleIndex {
+                article_type,
+                index: article_index,
+            })?;
+        if article_type == ArticleType::Key {
+            return Err(Error::KeyItem { id: article.id });
+        }
+
+        //Check everything before touching the file
+        let location = Location::from(is_storage);
+        let gems: Vec<Upgrade> = article
+            .slots
+            .iter()
+            .flatten()
+            .filter_map(|slot| slot.gem.clone())
+            .collect();
+        let block = match article.slots {
+            Some(_) => Some(
+                find_slots_block(file_data, article.first_part, article.second_part).ok_or(
+                    Error::SlotsNotFound {
+                        first_part: article.first_part,
+                        second_part: article.second_part,
+                    },
+                )?,
+            ),
+            None => None,
+        };
+        //The slot of the article is freed, so one gem less needs an empty slot
+        if gems.len() > file_data.count_inv_empty_slots(location) + 1 {
+            return Err(Error::InventoryFull { location });
+        }
+
+        let mut article = self.take_article(file_data, article_type, article_index, is_storage)?;
+
+        //The block becomes an empty entry, everything after it moves back
+        if let Some(block) = block {
+            file_data.resize(block, 60, &EMPTY_ENTRY)?;
+        }
+        if let Some(slots) = &mut article.slots {
+            for slot in slots {
+                slot.gem = None;
+            }
+        }
+
+        for gem in gems {
+            self.add_upgrade(file_data, gem, is_storage)?;
+        }
+        Ok(article)
+    }
+
+    /// Moves `quantity` of an article to `other`, the inventory at the other location.
+    /// Items are split or merged into a stack of the same item, weapons and armors are moved
+    /// whole with their gems. The article as it ends up in `other` is returned.
+    pub fn move_article(
+        &mut self,
+        other: &mut Inventory,
+        file_data: &mut FileData,
+        article_type: ArticleType,
+        article_index: usize,
+        quantity: u32,
+        is_storage: bool,
+    ) -> Result<Article, Error> {
+        let article = self
+            .articles
+            .get(&article_type)
+            .ok_or(Error::NoArticlesOfType { article_type })?
+            .get(article_index)
+            .ok_or(Error::InvalidArticleIndex {
+                article_type,
+                index: article_index,
+            })?;
+        //The storage can't hold key items
+        if article_type == ArticleType::Key {
+            return Err(Error::KeyItem { id: article.id });
+        }
+        if quantity == 0 || quantity > article.amount {
+            return Err(Error::InvalidQuantity {
+                id: article.id,
+                quantity,
+                available: article.amount,
+            });
+        }
+
+        //Check everything before touching the file
+        let destination = Location::from(!is_storage);
+        let stack = match article.type_family {
+            TypeFamily::Item => other
+                .articles
+                .get(&article_type)
+                .and_then(|articles| articles.iter().find(|a| a.id == article.id))
+                .map(|stack| {
+                    let room = stack.max_amount(destination).saturating_sub(stack.amount);
+                    if quantity > room {
+                        return Err(Error::StackFull {
+                            id: stack.id,
+                            quantity,
+                            room,
+                            location: destination,
+                        });
+                    }
+                    file_data
+                        .find_article_offset(stack.number, stack.id, stack.type_family, !is_storage)
+                        .map(|offset| (stack.index, offset))
+                        .ok_or(Error::ArticleNotFound {
+                            number: stack.number,
+                            id: stack.id,
+                            location: destination,
+                        })
+                })
+                .transpose()?,
+            _ => None,
+        };
+        if stack.is_none() && file_data.find_inv_empty_slot(destination).is_none() {
+            return Err(Error::InventoryFull {
+                location: destination,
+            });
+        }
+        let source = file_data
+            .find_article_offset(article.number, article.id, article.type_family, is_storage)
+            .ok_or(Error::ArticleNotFound {
+                number: article.number,
+                id: article.id,
+                location: Location::from(is_storage),
+            })?;
+
+        let mut moved = if quantity == article.amount {
+            self.take_article(file_data, article_type, article_index, is_storage)?
+        } else {
+            let article = self
+                .articles
+                .get_mut(&article_type)
+                .and_then(|articles| articles.get_mut(article_index))
+                .ok_or(Error::InvalidArticleIndex {
+                    article_type,
+                    index: article_index,
+                })?;
+            article.amount -= quantity;
+            file_data.bytes[source + 12..source + 16]
+                .copy_from_slice(&article.amount.to_le_bytes());
+            article.clone()
+        };
+        moved.amount = quantity;
+
+        match stack {
+            Some((index, offset)) => {
+                let stack = other
+                    .articles
+                    .get_mut(&article_type)
+                    .and_then(|articles| articles.get_mut(index))
+                    .ok_or(Error::InvalidArticleIndex {
+                        article_type,
+                        index,
+                    })?;
+                stack.amount += quantity;
+                file_data.bytes[offset + 12..offset + 16]
+                    .copy_from_slice(&stack.amount.to_le_bytes());
+                Ok(stack.clone())
+            }
+            None => Ok(other.put_article(file_data, moved, !is_storage)?.clone()),
+        }
+    }
+
+    pub fn equip_gem(
+        &mut self,
+        file_data: &mut FileData,
+        upgrade_index: usize,
+        article_type: ArticleType,
+        article_index: usize,
+        slot_index: usize,
+        is_storage: bool,
+    ) -> Result<(), Error> {
+        if let Some(articles_of_type) = self.articles.get_mut(&article_type) {
+            if let Some(article) = articles_of_type.get_mut(article_index) {
+                if let Some(ref mut slots) = &mut article.slots {
+                    if let Some(slot) = slots.get_mut(slot_index) {
+                        if slot.gem.is_some() {
+                            return Err(Error::SlotOccupied {
+                                id: article.id,
+                                index: slot_index,
+                            });
+                        }
+                        let slot_raw_pointer = slot as *mut Slot;
+
+                        //Remove the gem in file_data
+                        let first_part = article.first_part.to_le_bytes();
+                        let second_part = article.second_part.to_le_bytes();
+                        let mut found = false;
+                        for i in
+                            file_data.offsets.equipped_gems.0..file_data.offsets.equipped_gems.1
+                        {
+                            if (file_data.bytes[i..i + 4] == first_part)
+                                && (file_data.bytes[i + 4..i + 8] == second_part)
+                            {
+                                let id_bytes;
+                                //Try to get the gem to be equipped
+                                let result = self.remove_upgrade(
+                                    file_data,
+                                    UpgradeType::Gem,
+                                    upgrade_index,
+                                    is_storage,
+                                );
+
+                                match result {
+                                    Ok(gem) => {
+                                        id_bytes = gem.id.to_le_bytes();
+                                        unsafe {
+                                            (*slot_raw_pointer).gem = Some(gem);
+                                        }
+                                    }
+                                    Err(error) => return Err(error),
+                                };
+
+                                //Equip the gem in the slot
+                                found = true;
+                                //24 is the index for the first gem id
+                                let slot_index = i + 24 + 8 * slot_index;
+                                file_data.bytes[slot_index..slot_index + 4]
+                                    .copy_from_slice(&id_bytes);
+                                break;
+                            }
+                        }
+                        if !found {
+                            return Err(Error::SlotsNotFound {
+                                first_part: u32::from_le_bytes(first_part),
+                                second_part: u32::from_le_bytes(second_part),
+                            });
+                        }
+
+                        Ok(())
+                    } else {
+                        Err(Error::InvalidSlotIndex {
+                            id: article.id,
+                            index: slot_index,
+                        })
+                    }
+                } else {
+                    Err(Error::NoSlots { id: article.id })
+                }
+            } else {
+                Err(Error::InvalidArticleIndex {
+                    article_type,
+                    index: article_index,
+                })
+            }
+        } else {
+            Err(Error::NoArticlesOfType { article_type })
+        }
+    }
+
+    pub fn change_weapon_level(
+        &mut self,
+        file_data: &mut FileData,
+        article_type: ArticleType,
+        article_index: usize,
+        _slot_index: usize,
+        _is_storage: bool,
+        level: u8,
+    ) -> Result<Article, Error> {
+        if let Some(articles_of_type) = self.articles.get_mut(&article_type) {
+            if let Some(article) = articles_of_type.get_mut(article_index) {
+                article.set_imprint_and_upgrade(file_data, None, Some(level))
+            } else {
+                Err(Error::InvalidArticleIndex {
+                    article_type,
+                    index: article_index,
+                })
+            }
+        } else {
+            Err(Error::NoArticlesOfType { article_type })
+        }
+    }
+}
+
+//Offset and length of the entries before the username: 8B when unused,
+//40B for an upgrade and 60B for a slots block
+fn pool_entries(file_data: &FileData) -> Vec<(usize, usize)> {
+    let bytes = &file_data.bytes;
+    let end = file_data.offsets.username - BLOCKS_END_TO_USERNAME;
+    let mut entries = Vec::new();
+    let mut offset = START_TO_UPGRADE;
+    while offset + 8 <= end {
+        let length = if bytes[offset..offset + 8] == EMPTY_ENTRY {
+            8
+        } else if bytes[offset + 3] == 0xC0 {
+            40
+        } else {
+            60
+        };
+        entries.push((offset, length));
+        offset += length;
+    }
+    entries
+}
+
+//Next value of the counter in the low 23 bits of the first part of every upgrade and slots block
+fn next_counter(file_data: &FileData, entries: &[(usize, usize)]) -> Option<u32> {
+    let counter = entries
+        .iter()
+        .filter(|&&(_, length)| length != 8)
+        .map(|&(offset, _)| read_u32(file_data, offset) & 0x7FFFFF)
+        .max()
+        .map_or(0, |max| max + 1);
+    match counter > 0x7FFFFF {
+        true => None,
+        false => Some(counter),
+    }
+}
+
+fn read_u32(file_data: &FileData, offset: usize) -> u32 {
+    let bytes = &file_data.bytes[offset..offset + 4];
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+//Offset of the block with the gem slots of an article
+fn find_slots_block(file_data: &FileData, first_part: u32, second_part: u32) -> Option<usize> {
+    let (start, end) = file_data.offsets.equipped_gems;
+    let first_part = first_part.to_le_bytes();
+    let second_part = second_part.to_le_bytes();
+    (start..end.saturating_sub(59)).find(|&i| {
+        file_data.bytes[i..i + 4] == first_part && file_data.bytes[i + 4..i + 8] == second_part
+    })
+}
+
+//Adds delta to both article counters of the inventory or the storage
+fn update_counters(file_data: &mut FileData, is_storage: bool, delta: i64) {
+    let uname = file_data.offsets.username;
+    let counters = match is_storage {
+        false => [
+            uname + USERNAME_TO_FIRST_INVENTORY_COUNTER,
+            uname + USERNAME_TO_SECOND_INVENTORY_COUNTER,
+        ],
+        true => [
+            uname + USERNAME_TO_FIRST_STORAGE_COUNTER,
+            uname + USERNAME_TO_SECOND_STORAGE_COUNTER,
+        ],
+    };
+    for counter in counters {
+        let bytes = &mut file_data.bytes[counter..counter + 4];
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64;
+        let value = (value + delta).max(0) as u32;
+        bytes.copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Removes the records of the upgrades table that no inventory, storage or gem slot
+/// refers to, returns their ids. The rest of the save moves 32B per record.
+pub fn collect_orphaned_upgrades(file_data: &mut FileData) -> Result<Vec<u32>, Error> {
+    let mut referenced = HashSet::new();
+    let offsets = &file_data.offsets;
+    for (start, end) in [offsets.inventory, offsets.storage, offsets.key_inventory] {
+        for i in (start..end.saturating_sub(14)).step_by(16) {
+            referenced.insert(read_u32(file_data, i + 4));
+        }
+    }
+    for (offset, length) in pool_entries(file_data) {
+        if length == 60 {
+            //24 is the index for the first gem id
+            referenced.extend((0..5).map(|i| read_u32(file_data, offset + 24 + 8 * i)));
+        }
+    }
+    let (start, end) = file_data.offsets.upgrades;
+    let orphans: Vec<u32> = (start..end)
+        .step_by(40)
+        .map(|offset| read_u32(file_data, offset))
+        .filter(|id| !referenced.contains(id))
+        .collect();
+    remove_upgrade_records(file_data, &orphans)?;
+    Ok(orphans)
+}
+
+//Puts a 40B record after the last upgrade and frees the first unused entry after it,
+//the blocks in between move 40B and the rest of the save 32B
+pub(crate) fn insert_upgrade_record(file_data: &mut FileData, record: &[u8]) -> Result<(), Error> {
+    let start = file_data.offsets.upgrades.1 + 1;
+    let empty = pool_entries(file_data)
+        .iter()
+        .find(|&&(offset, length)| offset >= start && length == 8)
+        .map(|&(offset, _)| offset)
+        .ok_or(Error::SaveFull { needed: 40 })?;
+
+    let mut new_bytes = Vec::with_capacity(40 + empty - start);
+    new_bytes.extend_from_slice(record);
+    new_bytes.extend_from_slice(&file_data.bytes[start..empty]);
+    file_data.resize(start, empty + 8 - start, &new_bytes)?;
+    //resize only moves what comes after the freed entry
+    let moved = |i: usize| if i >= start && i < empty { i + 40 } else { i };
+    let (gems_start, gems_end) = file_data.offsets.equipped_gems;
+    file_data.offsets.equipped_gems = (moved(gems_start), moved(gems_end));
+    Ok(())
+}
+
+//Writes a 60B slots block after the last one, or in any unused entry past the upgrades.
+//Returns its offset
+pub(crate) fn insert_slots_block(file_data: &mut FileData, block: &[u8]) -> Result<usize, Error> {
+    let entries = pool_entries(file_data);
+    let after_upgrades = entries
+        .iter()
+        .rposition(|&(_, length)| length == 40)
+        .map_or(0, |i| i + 1);
+    let after_blocks = entries
+        .iter()
+        .rposition(|&(_, length)| length == 60)
+        .map_or(after_upgrades, |i| i + 1);
+    let entry = entries
+        .iter()
+        .skip(after_blocks)
+        .chain(entries.iter().skip(after_upgrades))
+        .find(|&&(_, length)| length == 8)
+        .map(|&(offset, _)| offset)
+        .ok_or(Error::SaveFull { needed: 60 })?;
+
+    file_data.resize(entry, 8, block)?;
+    let (start, end) = file_data.offsets.equipped_gems;
+    file_data.offsets.equipped_gems = match start {
+        0 => (entry, entry + 59),
+        _ => (start.min(entry), end.max(entry + 59)),
+    };
+    Ok(entry)
+}
+
+//Offsets of the slots blocks, by the first part of the article they belong to
+pub(crate) fn slots_blocks(file_data: &FileData) -> Vec<(u32, usize)> {
+    pool_entries(file_data)
+        .into_iter()
+        .filter(|&(_, length)| length == 60)
+        .map(|(offset, _)| (read_u32(file_data, offset), offset))
+        .collect()
+}
+
+//Takes records out of the upgrades table, which has to stay contiguous to be found. An unused
+//entry for each one goes after the last upgrade so the entries before the username keep their count
+pub(crate) fn remove_upgrade_records(file_data: &mut FileData, ids: &[u32]) -> Result<(), Error> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let (start, end) = file_data.offsets.upgrades;
+    let mut new_bytes = Vec::with_capacity(end + 1 - start);
+    for offset in (start..end).step_by(40) {
+        if !ids.contains(&read_u32(file_data, offset)) {
+            new_bytes.extend_from_slice(&file_data.bytes[offset..offset + 40]);
+        }
+    }
+    let removed = (end + 1 - start - new_bytes.len()) / 40;
+    for _ in 0..removed {
+        new_bytes.extend(EMPTY_ENTRY);
+    }
+    file_data.resize(start, end + 1 - start, &new_bytes)
+}
+
+/// Looks up an item in the embedded items list.
+pub fn get_info_item(id: u32) -> Result<(ItemInfo, ArticleType), Error> {
+    let items: Value = serde_json::from_str(resources::ITEMS)?;
+    let items = items.as_object().unwrap();
+
+    for (category, category_items) in items {
+        if let Some(found) = category_items
+            .as_object()
+            .unwrap()
+            .keys()
+            .find(|x| x.parse::<u32>() == Ok(id))
+        {
+            let mut info: ItemInfo = serde_json::from_value(category_items[found].clone())?;
+            if category == "chalice" {
+                info.extra_info = Some(json!({
+                    "depth": &category_items[found]["depth"],
+                    "area": &category_items[found]["area"],
+                }));
+            }
+            return Ok((info, ArticleType::try_from(category.as_str())?));
+        }
+    }
+    Err(Error::ArticleInfoNotFound {
+        id,
+        family: TypeFamily::Item,
+    })
+}
+
+/// Looks up an armor in the embedded armors list.
+pub fn get_info_armor(id: u32) -> Result<(ItemInfo, ArticleType), Error> {
+    let armors: Value = serde_json::from_str(resources::ARMORS)?;
+    let armors = armors.as_object().unwrap();
+
+    if let Some(found) = armors.keys().find(|x| x.parse::<u32>() == Ok(id)) {
+        let mut info: ItemInfo = serde_json::from_value(armors[found].clone())?;
+        info.extra_info = Some(json!({
+            "physicalDefense": &armors[found]["physicalDefense"],
+            "elementalDefense": &armors[found]["elementalDefense"],
+            "resistance": &armors[found]["resistance"],
+            "beasthood": &armors[found]["beasthood"]
+        }));
+        return Ok((info, ArticleType::Armor));
+    }
+    Err(Error::ArticleInfoNotFound {
+        id,
+        family: TypeFamily::Armor,
+    })
+}
+
+/// Id of a weapon without its imprint and upgrade level, the key of the embedded weapons list.
+pub fn base_weapon_id(id: u32) -> u32 {
+    //Special case, the Bloody variants are listed on their own
+    if id == 12080000 || id == 6180000 {
+        return id;
+    }
+    (id / 100000) * 100000
+}
+
+/// Looks up a weapon in the embedded weapons list, `id` may include the imprint and upgrade level.
+pub fn get_info_weapon(id: u32) -> Result<(ItemInfo, ArticleType), Error> {
+    let weapons: Value = serde_json::from_str(resources::WEAPONS)?;
+    let weapons = weapons.as_object().unwrap();
+
+    let weapon_mods = WeaponMods::try_from(id)?;
+    let id = base_weapon_id(id);
+    for (category, category_weapons) in weapons {
+        if let Some(found) = category_weapons
+            .as_object()
+            .unwrap()
+            .keys()
+            .find(|x| x.parse::<u32>() == Ok(id))
+        {
+            let mut info: ItemInfo = serde_json::from_value(category_weapons[found].clone())?;
+            let mut extra_info = json!({
+                "_base_damage": &category_weapons[found]["damage"],
+                "damage": &category_weapons[found]["damage"],
+                "upgrade_level": weapon_mods.upgrade_level,
+                "imprint": weapon_mods.imprint,
+            });
+            if weapon_mods.upgrade_level > 0 {
+                scale_weapon_info(&mut extra_info);
+            }
+            info.extra_info = Some(extra_info);
+            return Ok((info, ArticleType::try_from(category.as_str())?));
+        }
+    }
+    Err(Error::ArticleInfoNotFound {
+        id,
+        family: TypeFamily::Weapon,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        save::SaveData,
+        slots::parse_equipped_gems,
+        upgrades::parse_upgrades,
+        utils::test_utils::{build_save_data, check_bytes},
+    };
+
+    #[test]
+    fn inventory_edit_item() {
+        let mut save = build_save_data("testsave0");
+        assert!(check_bytes(
+            &save.file,
+            0x89cc,
+            &[0x48, 0x80, 0xCF, 0xA8, 0x64, 0, 0, 0xB0, 0x64, 0, 0, 0x40, 0x01, 0, 0, 0]
+        ));
+        //Try to edit a key item (Iron Door Key)
+        let result = save
+            .inventory
+            .edit_item(&mut save.file, 0x6b, 4009, 0xAABBCCDD, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "The key item 4009 cannot be edited.");
+        }
+        //Try wrong index
+        let result = save
+            .inventory
+            .edit_item(&mut save.file, 0xAA, 0xAAAAAAAA, 0xAABBCCDD, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "The article 2863311530 (number 0xaa) was not found in the inventory."
+            );
+        }
+
+        save.inventory
+            .edit_item(&mut save.file, 0x48, 0x64, 0xAABBCCDD, false)
+            .unwrap();
+        assert!(check_bytes(
+            &save.file,
+            0x89cc,
+            &[0x48, 0x80, 0xCF, 0xA8, 0x64, 0, 0, 0xB0, 0x64, 0, 0, 0x40, 0xDD, 0xCC, 0xBB, 0xAA]
+        ));
+        assert_eq!(
+            save.inventory
+                .articles
+                .get(&ArticleType::Consumable)
+                .unwrap()[0]
+                .amount,
+            0xAABBCCDD
+        );
+    }
+
+    #[test]
+    fn test_parse_key_inventory() {
+        let save = build_save_data("testsave0");
+        let keys = save.inventory.articles.get(&ArticleType::Key).unwrap();
+        assert_eq!(keys.len(), 7);
+
+        //Item N0
+        assert_eq!(keys[0].number, 107);
+        assert_eq!(keys[0].id, u32::from_le_bytes([0xa9, 0x0f, 0x00, 0x00]));
+        assert_eq!(
+            keys[0].first_part,
+            u32::from_le_bytes([0xa9, 0x0f, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[0].second_part,
+            u32::from_le_bytes([0xa9, 0x0f, 0x00, 0x40])
+        );
+        assert_eq!(keys[0].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[0].article_type, ArticleType::Key);
+
+        //Item N1
+        assert_eq!(keys[1].number, 6);
+        assert_eq!(keys[1].id, u32::from_le_bytes([0x12, 0x10, 0x00, 0x00]));
+        assert_eq!(
+            keys[1].first_part,
+            u32::from_le_bytes([0x12, 0x10, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[1].second_part,
+            u32::from_le_bytes([0x12, 0x10, 0x00, 0x40])
+        );
+        assert_eq!(keys[1].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[1].article_type, ArticleType::Key);
+
+        //Item N2
+        assert_eq!(keys[2].number, 0);
+        assert_eq!(keys[2].id, u32::from_le_bytes([0xd8, 0x10, 0x00, 0x00]));
+        assert_eq!(
+            keys[2].first_part,
+            u32::from_le_bytes([0xd8, 0x10, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[2].second_part,
+            u32::from_le_bytes([0xd8, 0x10, 0x00, 0x40])
+        );
+        assert_eq!(keys[2].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[2].article_type, ArticleType::Key);
+
+        //Item N3
+        assert_eq!(keys[3].number, 1);
+        assert_eq!(keys[3].id, u32::from_le_bytes([0x0e, 0x10, 0x00, 0x00]));
+        assert_eq!(
+            keys[3].first_part,
+            u32::from_le_bytes([0x0e, 0x10, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[3].second_part,
+            u32::from_le_bytes([0x0e, 0x10, 0x00, 0x40])
+        );
+        assert_eq!(keys[3].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[3].article_type, ArticleType::Key);
+
+        //Item N4
+        assert_eq!(keys[4].number, 2);
+        assert_eq!(keys[4].id, u32::from_le_bytes([0xa0, 0x0f, 0x00, 0x00]));
+        assert_eq!(
+            keys[4].first_part,
+            u32::from_le_bytes([0xa0, 0x0f, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[4].second_part,
+            u32::from_le_bytes([0xa0, 0x0f, 0x00, 0x40])
+        );
+        assert_eq!(keys[4].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[4].article_type, ArticleType::Key);
+
+        //Item N5
+        assert_eq!(keys[5].number, 3);
+        assert_eq!(keys[5].id, u32::from_le_bytes([0x07, 0x10, 0x00, 0x00]));
+        assert_eq!(
+            keys[5].first_part,
+            u32::from_le_bytes([0x07, 0x10, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[5].second_part,
+            u32::from_le_bytes([0x07, 0x10, 0x00, 0x40])
+        );
+        assert_eq!(keys[5].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[5].article_type, ArticleType::Key);
+
+        //Item N6
+        assert_eq!(keys[6].number, 4);
+        assert_eq!(keys[6].id, u32::from_le_bytes([0xab, 0x0f, 0x00, 0x00]));
+        assert_eq!(
+            keys[6].first_part,
+            u32::from_le_bytes([0xab, 0x0f, 0x00, 0xb0])
+        );
+        assert_eq!(
+            keys[6].second_part,
+            u32::from_le_bytes([0xab, 0x0f, 0x00, 0x40])
+        );
+        assert_eq!(keys[6].amount, u32::from_le_bytes([0x01, 0x00, 0x00, 0x00]));
+        assert_eq!(keys[6].article_type, ArticleType::Key);
+    }
+
+    #[test]
+    fn inventory_add_item() {
+        let mut save = build_save_data("testsave0");
+        assert_eq!(
+            save.inventory
+                .articles
+                .get(&ArticleType::Consumable)
+                .unwrap()
+                .len(),
+            17
+        );
+        assert!(check_bytes(
+            &save.file,
+            0x8ccc,
+            &[
+                0x78, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+                0x00, 0x00
+            ]
+        ));
+        //Try to add an invalid item
+        let result = save.inventory.add_item(&mut save.file, 0x00, 0x00, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "Failed to find info for the item 0.");
+        }
+
+        //Add to the storage
+        assert!(save
+            .inventory
+            .add_item(
+                &mut save.file,
+                u32::from_le_bytes([0x60, 0x04, 0x00, 0x00]),
+                32,
+                true
+            )
+            .is_ok());
+
+        //Add to the inventory
+        save.inventory
+            .add_item(
+                &mut save.file,
+                u32::from_le_bytes([0x60, 0x04, 0x00, 0x00]),
+                32,
+                false,
+            )
+            .unwrap();
+        assert_eq!(
+            save.inventory
+                .articles
+                .get(&ArticleType::Consumable)
+                .unwrap()
+                .len(),
+            19
+        );
+        assert!(check_bytes(
+            &save.file,
+            0x8ccc,
+            &[
+                0x78, 0xff, 0xff, 0xff, 0x60, 0x04, 0x00, 0xb0, 0x60, 0x04, 0x00, 0x40, 0x20, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+                0x00, 0x00, 0x00, 0x00
+            ]
+        ));
+
+        let mut upgrades = parse_upgrades(&save.file).unwrap();
+        let mut slots = parse_equipped_gems(&mut save.file, &mut upgrades);
+        let inventory = Inventory::build(
+            &save.file,
+            save.file.offsets.inventory,
+            save.file.offsets.key_inventory,
+            &mut upgrades,
+            &mut slots,
+        );
+        let consumables = inventory.articles.get(&ArticleType::Consumable).unwrap();
+        let new_item = consumables.last().unwrap();
+        assert_eq!(new_item.number, 120);
+        assert_eq!(new_item.id, u32::from_le_bytes([0x60, 0x04, 0x00, 0x00]));
+        assert_eq!(
+            new_item.first_part,
+            u32::from_le_bytes([0x60, 0x04, 0x00, 0xb0])
+        );
+        assert_eq!(
+            new_item.second_part,
+            u32::from_le_bytes([0x60, 0x04, 0x00, 0x40])
+        );
+        assert_eq!(
+            new_item.amount,
+            u32::from_le_bytes([0x20, 0x00, 0x00, 0x00])
+        );
+        assert_eq!(new_item.article_type, ArticleType::Consumable);
+
+        //Add to a save without items in its storage
+        let mut save = build_save_data("testsave7");
+        save.storage
+            .add_item(
+                &mut save.file,
+                u32::from_le_bytes([0x60, 0x04, 0x00, 0x00]),
+                32,
+                true,
+            )
+            .unwrap();
+        let consumables = save.storage.articles.get(&ArticleType::Consumable).unwrap();
+        let new_item = consumables.last().unwrap();
+        assert_eq!(new_item.number, 1);
+        assert_eq!(new_item.id, u32::from_le_bytes([0x60, 0x04, 0x00, 0x00]));
+        assert_eq!(
+            new_item.first_part,
+            u32::from_le_bytes([0x60, 0x04, 0x00, 0xb0])
+        );
+        assert_eq!(
+            new_item.second_part,
+            u32::from_le_bytes([0x60, 0x04, 0x00, 0x40])
+        );
+        assert_eq!(
+            new_item.amount,
+            u32::from_le_bytes([0x20, 0x00, 0x00, 0x00])
+        );
+        assert_eq!(new_item.article_type, ArticleType::Consumable);
+    }
+
+    #[test]
+    fn test_equipped_gems() {
+        //This tests if Inventory::build allocates gems correctly
+        //(To the player inventory, storage, and weapon slots)
+        let save = build_save_data("testsave9");
+
+        //Inventory:
+        let gems = save.inventory.upgrades.get(&UpgradeType::Gem).unwrap();
+        let runes = save.inventory.upgrades.get(&UpgradeType::Rune).unwrap();
+        assert_eq!(runes[0].id, u32::from_le_bytes([0x72, 0x00, 0x80, 0xC0]));
+        assert_eq!(
+            runes[0].source,
+            u32::from_le_bytes([0x4a, 0x0d, 0x03, 0x80])
+        );
+
+        //This rune is equipped
+        //assert_eq!(runes[1].id, u32::from_le_bytes([0x76, 0x00, 0x80, 0xC0]));
+        //assert_eq!(runes[1].source, u32::from_le_bytes([0x40, 0x0d, 0x03, 0x80]));
+
+        //This rune is equipped
+        //assert_eq!(runes[2].id, u32::from_le_bytes([0x77, 0x00, 0x80, 0xC0]));
+        //assert_eq!(runes[2].source, u32::from_le_bytes([0xbf, 0x92, 0x01, 0x80]));
+
+        assert_eq!(gems[0].id, u32::from_le_bytes([0x73, 0x00, 0x80, 0xC0]));
+        assert_eq!(gems[0].source, u32::from_le_bytes([0xf0, 0x49, 0x02, 0x80]));
+
+        assert_eq!(gems[1].id, u32::from_le_bytes([0x70, 0x00, 0x80, 0xC0]));
+        assert_eq!(gems[1].source, u32::from_le_bytes([0xf2, 0x49, 0x02, 0x80]));
+
+        //Storage:
+        let gems = save.storage.upgrades.get(&UpgradeType::Gem).unwrap();
+
+        assert_eq!(gems[0].id, u32::from_le_bytes([0x71, 0x00, 0x80, 0xC0]));
+        assert_eq!(gems[0].source, u32::from_le_bytes([0x48, 0xe8, 0x01, 0x80]));
+
+        assert_eq!(gems[1].id, u32::from_le_bytes([0x75, 0x00, 0x80, 0xC0]));
+        assert_eq!(gems[1].source, u32::from_le_bytes([0x1a, 0xf0, 0x01, 0x80]));
+
+        //Equipped gems
+        let weapons = save
+            .inventory
+            .articles
+            .get(&ArticleType::RightHand)
+            .unwrap();
+        assert_eq!(weapons[0].id, 5000300);
+        let slots = weapons[0].slots.clone().unwrap();
+
+        //Slot 1
+        assert_eq!(slots[0].shape, SlotShape::Radial);
+        let gem = slots[0].gem.clone().unwrap();
+        assert_eq!(gem.id, u32::from_le_bytes([0x74, 0x00, 0x80, 0xc0]));
+        assert_eq!(gem.shape, String::from("Radial"));
+        let info = gem.info.clone();
+        assert_eq!(info.name, String::from("Tempering Blood Gemstone (2)"));
+        assert_eq!(info.effect, String::from("Physical ATK UP +7.3%"));
+        assert_eq!(info.rating, 7);
+        assert_eq!(info.level, 2);
+        assert_eq!(info.note, String::from(""));
+
+        //Slot 2
+        assert_eq!(slots[1].shape, SlotShape::Radial);
+        let gem = slots[1].gem.clone().unwrap();
+        assert_eq!(gem.id, u32::from_le_bytes([0x6f, 0x00, 0x80, 0xc0]));
+        assert_eq!(gem.shape, String::from("Radial"));
+        let info = gem.info.clone();
+        assert_eq!(info.name, String::from("Tempering Blood Gemstone (3)"));
+        assert_eq!(info.effect, String::from("Physical ATK UP +9.5%"));
+        assert_eq!(info.rating, 9);
+        assert_eq!(info.level, 3);
+        assert_eq!(info.note, String::from(""));
+
+        //Slot 3
+        assert_eq!(slots[2].shape, SlotShape::Closed);
+        assert!(slots[2].gem.is_none());
+
+        //Slot 4
+        assert_eq!(slots[3].shape, SlotShape::Closed);
+        assert!(slots[3].gem.is_none());
+
+        //Slot 5
+        assert_eq!(slots[4].shape, SlotShape::Closed);
+        assert!(slots[4].gem.is_none());
+    }
+
+    #[test]
+    fn inventory_add_upgrade() {
+        let mut save = build_save_data("testsave0");
+        let runes = save.inventory.upgrades.get(&UpgradeType::Rune).unwrap();
+        let rune = runes[0].clone();
+        assert_eq!(runes.len(), 1);
+        assert!(check_bytes(
+            &save.file,
+            0x8ccc,
+            &[
+                0x78, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+                0x00, 0x00
+            ]
+        ));
+
+        //Add to the inventory
+        save.inventory
+            .add_upgrade(&mut save.file, rune.clone(), false)
+            .unwrap();
+        let runes = save.inventory.upgrades.get(&UpgradeType::Rune).unwrap();
+        let mut rune2 = runes[1].clone();
+        rune2.index = 0;
+        //The upgrade takes the number of the slot it was added to
+        assert_eq!(rune2.number, 0x78);
+        rune2.number = rune.number;
+        assert_eq!(rune, rune2);
+        assert_eq!(runes.len(), 2);
+        assert!(check_bytes(
+            &save.file,
+            0x8ccc,
+            &[
+                0x78, 0xff, 0xff, 0xff, 0x42, 0x00, 0x80, 0xc0, 0xbf, 0x92, 0x01, 0x80, 0x01, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+                0x00, 0x00, 0x00, 0x00
+            ]
+        ));
+
+        //Add to a save without items in its storage
+        let mut save = build_save_data("testsave7");
+        save.storage
+            .add_upgrade(&mut save.file, rune, true)
+            .unwrap();
+        let runes = save.storage.upgrades.get(&UpgradeType::Rune).unwrap();
+        let new_rune = runes.last().unwrap();
+        assert_eq!(new_rune.number, 1);
+        assert_eq!(new_rune.id, u32::from_le_bytes([0x42, 0x00, 0x80, 0xc0]));
+    }
+
+    #[test]
+    fn inventory_unequip_gem() {
+        let mut save = build_save_data("testsave9");
+
+        //Test error cases
+        let result =
+            save.inventory
+                .unequip_gem(&mut save.file, ArticleType::Chalice, 500, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There are no articles of type Chalice.");
+        }
+
+        let result =
+            save.inventory
+                .unequip_gem(&mut save.file, ArticleType::RightHand, 500, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "There is no RightHand article at index 500."
+            );
+        }
+
+        let result =
+            save.inventory
+                .unequip_gem(&mut save.file, ArticleType::Consumable, 0, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "The article 100 has no slots.");
+        }
+
+        let result =
+            save.inventory
+                .unequip_gem(&mut save.file, ArticleType::RightHand, 0, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "The article 5000300 has no slot 500.");
+        }
+
+        let result =
+            save.inventory
+                .unequip_gem(&mut save.file, ArticleType::RightHand, 0, 4, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "The slot 4 of the article 5000300 does not have a gem."
+            );
+        }
+
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        let backup = hunter_axe.first_part;
+        hunter_axe.first_part = 0;
+        let result =
+            save.inventory
+                .unequip_gem(&mut save.file, ArticleType::RightHand, 0, 0, false);
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        hunter_axe.first_part = backup;
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "Failed to find the slots of the article 0x0 0x4c4c6c."
+            );
+        }
+
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        let gem = hunter_axe.slots.as_ref().unwrap()[0].clone().gem.unwrap();
+        //Slots of the hunter axe
+        assert!(check_bytes(
+            &save.file,
+            0x1570,
+            &[
+                0xd0, 0x01, 0x80, 0x80, 0x6c, 0x4c, 0x4c, 0x00, 0xfa, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x74, 0x00, 0x80, 0xc0,
+                0x01, 0x00, 0x00, 0x00, 0x6f, 0x00, 0x80, 0xc0, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+                0x00, 0x00, 0x00, 0x00, 0xd1, 0x01, 0x80, 0x80, 0x00, 0x12, 0x7a, 0x00, 0xfa, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0xd2, 0x01, 0x80, 0x90, 0x40, 0x19,
+                0x01, 0x10,
+            ]
+        ));
+        //Empty slot of the inventory
+        assert!(check_bytes(
+            &save.file,
+            0x9158,
+            &[
+                0x68, 0x80, 0x93, 0xb9, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+                0x00, 0x00
+            ]
+        ));
+
+        save.inventory
+            .unequip_gem(&mut save.file, ArticleType::RightHand, 0, 0, false)
+            .unwrap();
+        let mut upgrades = parse_upgrades(&save.file).unwrap();
+        let mut slots = parse_equipped_gems(&mut save.file, &mut upgrades);
+        let mut inventory = Inventory::build(
+            &save.file,
+            save.file.offsets.inventory,
+            save.file.offsets.key_inventory,
+            &mut upgrades,
+            &mut slots,
+        );
+        let new_gem = inventory
+            .upgrades
+            .get_mut(&UpgradeType::Gem)
+            .unwrap()
+            .get_mut(1)
+            .unwrap();
+        new_gem.number = gem.number;
+        new_gem.index = gem.index;
+        assert_eq!(*new_gem, gem);
+
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        assert!(hunter_axe.slots.as_ref().unwrap()[0].clone().gem.is_none());
+        assert!(check_bytes(
+            &save.file,
+            0x1570,
+            &[
+                0xd0, 0x01, 0x80, 0x80, 0x6c, 0x4c, 0x4c, 0x00, 0xfa, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x01, 0x00, 0x00, 0x00, 0x6f, 0x00, 0x80, 0xc0, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+                0x00, 0x00, 0x00, 0x00, 0xd1, 0x01, 0x80, 0x80, 0x00, 0x12, 0x7a, 0x00, 0xfa, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0xd2, 0x01, 0x80, 0x90, 0x40, 0x19,
+                0x01, 0x10,
+            ]
+        ));
+        //The slot now has the gem
+        assert!(check_bytes(
+            &save.file,
+            0x9158,
+            &[
+                0x68, 0x80, 0x93, 0xb9, 0x74, 0x00, 0x80, 0xc0, 0x62, 0xe4, 0x01, 0x80, 0x01, 0x00,
+                0x00, 0x00
+            ]
+        ));
+    }
+
+    #[test]
+    fn inventory_remove_upgrade() {
+        let mut save = build_save_data("testsave9");
+        //Test error cases
+        let result = save
+            .storage
+            .remove_upgrade(&mut save.file, UpgradeType::Rune, 0, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There are no upgrades of type Rune.");
+        }
+
+        let result = save
+            .inventory
+            .remove_upgrade(&mut save.file, UpgradeType::Rune, 999, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There is no Rune at index 999.");
+        }
+
+        let gem = save
+            .inventory
+            .upgrades
+            .get_mut(&UpgradeType::Gem)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        let backup = gem.id;
+        gem.id = 0;
+        let result = save
+            .inventory
+            .remove_upgrade(&mut save.file, UpgradeType::Gem, 0, false);
+        let gem = save
+            .inventory
+            .upgrades
+            .get_mut(&UpgradeType::Gem)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        gem.id = backup;
+        let gem = gem.clone();
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "Failed to find the upgrade 0x0.");
+        }
+
+        //The inventory has two gems
+        let gems = save.inventory.upgrades.get(&UpgradeType::Gem).unwrap();
+        assert_eq!(gems.len(), 2);
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[
+                0x51, 0x40, 0x89, 0x13, 0x73, 0x00, 0x80, 0xc0, 0xf0, 0x49, 0x02, 0x80, 0x01, 0x00,
+                0x00, 0x00
+            ]
+        ));
+
+        //Run the method
+        let removed_gem = save
+            .inventory
+            .remove_upgrade(&mut save.file, UpgradeType::Gem, 0, false)
+            .unwrap();
+
+        //Now the slot is empty and there is only one gem
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[0x51, 0x40, 0x89, 0x13, 0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0]
+        ));
+        let gems = save.inventory.upgrades.get(&UpgradeType::Gem).unwrap();
+        assert_eq!(gems.len(), 1);
+        assert_eq!(removed_gem, gem);
+
+        //Rebuild the inventory to check the changes to file_data are valid
+        let mut upgrades = parse_upgrades(&save.file).unwrap();
+        let mut slots = parse_equipped_gems(&mut save.file, &mut upgrades);
+        let inventory = Inventory::build(
+            &save.file,
+            save.file.offsets.inventory,
+            save.file.offsets.key_inventory,
+            &mut upgrades,
+            &mut slots,
+        );
+        let gems = inventory.upgrades.get(&UpgradeType::Gem).unwrap();
+        assert_eq!(gems.len(), 1);
+    }
+
+    #[test]
+    fn inventory_equip_gem() {
+        let mut save = build_save_data("testsave9");
+
+        //Test error cases
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::Chalice, 500, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There are no articles of type Chalice.");
+        }
+
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::RightHand, 500, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "There is no RightHand article at index 500."
+            );
+        }
+
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::Consumable, 0, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "The article 100 has no slots.");
+        }
+
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::RightHand, 0, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "The article 5000300 has no slot 500.");
+        }
+
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::RightHand, 0, 0, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "The slot 0 of the article 5000300 already has a gem."
+            );
+        }
+
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::RightHand, 0, 4, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There is no Gem at index 500.");
+        }
+
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        let backup = hunter_axe.first_part;
+        hunter_axe.first_part = 0;
+        let result =
+            save.inventory
+                .equip_gem(&mut save.file, 500, ArticleType::RightHand, 0, 4, false);
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .unwrap()
+            .get_mut(0)
+            .unwrap();
+        hunter_axe.first_part = backup;
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "Failed to find the slots of the article 0x0 0x4c4c6c."
+            );
+        }
+
+        //Get the gem to be equipped
+        let gem = save.inventory.upgrades.get(&UpgradeType::Gem).unwrap()[0].clone();
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get(&ArticleType::RightHand)
+            .unwrap()
+            .first()
+            .unwrap();
+        let slots = hunter_axe.slots.as_ref().unwrap();
+        //The slot n4 is empty
+        assert!(slots[4].gem.is_none());
+        //The inventory has two gems
+        assert_eq!(
+            save.inventory
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            2
+        );
+        //Slots of the hunter axe
+        assert!(check_bytes(
+            &save.file,
+            0x1570,
+            &[
+                0xd0, 0x01, 0x80, 0x80, 0x6c, 0x4c, 0x4c, 0x00, 0xfa, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x74, 0x00, 0x80, 0xc0,
+                0x01, 0x00, 0x00, 0x00, 0x6f, 0x00, 0x80, 0xc0, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+                0x00, 0x00, 0x00, 0x00,
+            ]
+        ));
+        //Slot of the inventory with the gem
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[
+                0x51, 0x40, 0x89, 0x13, 0x73, 0x00, 0x80, 0xc0, 0xf0, 0x49, 0x02, 0x80, 0x01, 0x00,
+                0x00, 0x00
+            ]
+        ));
+
+        //Run the function
+        save.inventory
+            .equip_gem(&mut save.file, 0, ArticleType::RightHand, 0, 4, false)
+            .unwrap();
+
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get(&ArticleType::RightHand)
+            .unwrap()
+            .first()
+            .unwrap();
+        let slots = hunter_axe.slots.as_ref().unwrap();
+        //Now the slot n4 contains the gem
+        assert_eq!(*slots[4].gem.as_ref().unwrap(), gem);
+        //And the inventory doesn't have that gem anymore
+        assert_eq!(
+            save.inventory
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        //Test again rebuilding the inventory
+        let mut upgrades = parse_upgrades(&save.file).unwrap();
+        let mut slots = parse_equipped_gems(&mut save.file, &mut upgrades);
+        let mut inventory = Inventory::build(
+            &save.file,
+            save.file.offsets.inventory,
+            save.file.offsets.key_inventory,
+            &mut upgrades,
+            &mut slots,
+        );
+        assert_eq!(
+            inventory.upgrades.get_mut(&UpgradeType::Gem).unwrap().len(),
+            1
+        );
+        let hunter_axe = save
+            .inventory
+            .articles
+            .get(&ArticleType::RightHand)
+            .unwrap()
+            .first()
+            .unwrap();
+        let slots = hunter_axe.slots.as_ref().unwrap();
+        assert_eq!(*slots[4].gem.as_ref().unwrap(), gem);
+
+        //Check the game is in the slots of the weapon
+        assert!(check_bytes(
+            &save.file,
+            0x1570,
+            &[
+                0xd0, 0x01, 0x80, 0x80, 0x6c, 0x4c, 0x4c, 0x00, 0xfa, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x74, 0x00, 0x80, 0xc0,
+                0x01, 0x00, 0x00, 0x00, 0x6f, 0x00, 0x80, 0xc0, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+                0x73, 0x00, 0x80, 0xc0,
+            ]
+        ));
+
+        //Now the slot in which the gem was is empty
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[0x51, 0x40, 0x89, 0x13, 0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0]
+        ));
+    }
+
+    #[test]
+    fn inventory_remove_article() {
+        let mut save = build_save_data("testsave9");
+
+        //Test error cases
+        let result =
+            save.inventory
+                .remove_article(&mut save.file, ArticleType::RightHand, 500, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "There is no RightHand article at index 500."
+            );
+        }
+        let result = save
+            .inventory
+            .remove_article(&mut save.file, ArticleType::Key, 0, false);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert!(error.to_string().contains("cannot be edited"));
+        }
+
+        let uname = save.file.offsets.username;
+        let counter = |save: &SaveData, offset: usize| {
+            let i = save.file.offsets.username + offset;
+            u32::from_le_bytes([
+                save.file.bytes[i],
+                save.file.bytes[i + 1],
+                save.file.bytes[i + 2],
+                save.file.bytes[i + 3],
+            ])
+        };
+        let first_counter = counter(&save, USERNAME_TO_FIRST_INVENTORY_COUNTER);
+        let second_counter = counter(&save, USERNAME_TO_SECOND_INVENTORY_COUNTER);
+        let weapons = save.inventory.articles[&ArticleType::RightHand].len();
+        let gems = save.inventory.upgrades[&UpgradeType::Gem].len();
+
+        //Hunter Axe +3 with two gems
+        let number = save.inventory.articles[&ArticleType::RightHand][0].number;
+        let first_gem = save.inventory.articles[&ArticleType::RightHand][0]
+            .slots
+            .as_ref()
+            .unwrap()[0]
+            .gem
+            .clone()
+            .unwrap();
+        let offset = save
+            .file
+            .find_article_offset(number, 5000300, TypeFamily::Weapon, false)
+            .unwrap();
+        let size = save.file.bytes.len();
+        let coordinates = save.position.coordinates.values(&save.file);
+        let axe = save
+            .remove_article(Location::Inventory, ArticleType::RightHand, 0)
+            .unwrap();
+        assert_eq!(axe.id, 5000300);
+        assert!(axe.slots.unwrap().iter().all(|s| s.gem.is_none()));
+        //The 60B block became an 8B empty entry, the rest of the save moved back
+        assert_eq!(save.file.bytes.len(), size);
+        assert_eq!(save.file.offsets.username, uname - 52);
+        assert!(check_bytes(&save.file, size - 52, &[0; 52]));
+        assert!(check_bytes(
+            &save.file,
+            0x1570,
+            &[0, 0, 0, 0, 255, 255, 255, 255]
+        ));
+        assert_eq!(save.position.coordinates.values(&save.file), coordinates);
+        //One article less and two gems more
+        assert_eq!(
+            counter(&save, USERNAME_TO_FIRST_INVENTORY_COUNTER),
+            first_counter + 1
+        );
+        assert_eq!(
+            counter(&save, USERNAME_TO_SECOND_INVENTORY_COUNTER),
+            second_counter + 1
+        );
+        //The gems are back in the inventory, the first one in the slot of the axe
+        assert_eq!(save.inventory.upgrades[&UpgradeType::Gem].len(), gems + 2);
+        let mut gem_slot = first_gem.id.to_le_bytes().to_vec();
+        gem_slot.extend(first_gem.source.to_le_bytes());
+        gem_slot.extend([0x01, 0x00, 0x00, 0x00]);
+        assert!(check_bytes(&save.file, offset - 52 + 4, &gem_slot));
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        let right_hand = &reparsed.inventory.articles[&ArticleType::RightHand];
+        assert_eq!(right_hand.len(), weapons - 1);
+        assert!(right_hand.iter().all(|w| w.id != 5000300));
+        assert_eq!(
+            reparsed.inventory.upgrades[&UpgradeType::Gem].len(),
+            gems + 2
+        );
+        assert_eq!(
+            save.inventory.articles[&ArticleType::RightHand]
+                .iter()
+                .map(|a| a.index)
+                .collect::<Vec<usize>>(),
+            (0..weapons - 1).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn inventory_add_weapon() {
+        let mut save = build_save_data("testsave9");
+
+        //Test error cases
+        let result = save.add_weapon(Location::Inventory, 5000000, None, 11, &[]);
+        assert_eq!(result.unwrap_err().kind(), "InvalidUpgradeLevel");
+        let result = save.add_weapon(
+            Location::Inventory,
+            5000000,
+            None,
+            0,
+            &[SlotShape::Radial; 6],
+        );
+        assert_eq!(result.unwrap_err().kind(), "InvalidSlotIndex");
+        let result = save.add_weapon(Location::Inventory, 123, None, 0, &[]);
+        assert_eq!(result.unwrap_err().kind(), "ArticleInfoNotFound");
+
+        let uname = save.file.offsets.username;
+        let (_, end) = save.file.offsets.equipped_gems;
+        let coordinates = save.position.coordinates.values(&save.file);
+        let weapons = save.inventory.articles[&ArticleType::RightHand].len();
+
+        //Hunter Axe +7 with a lost imprint
+        let axe = save
+            .add_weapon(
+                Location::Inventory,
+                5000000,
+                Some(Imprint::Lost),
+                7,
+                &[SlotShape::Radial, SlotShape::Droplet],
+            )
+            .unwrap();
+        assert_eq!(axe.id, 5020700);
+        assert_eq!(axe.second_part, 5020700);
+        //The counter after the highest one in testsave9
+        assert_eq!(axe.first_part, 0x808001EB);
+        assert_eq!(axe.index, weapons);
+        assert_eq!(axe.info.extra_info.as_ref().unwrap()["upgrade_level"], 7);
+
+        //The block replaced the empty entry after the last one
+        assert_eq!(save.file.offsets.username, uname + 52);
+        assert_eq!(save.file.offsets.equipped_gems.1, end + 60);
+        let mut block = vec![0xEB, 0x01, 0x80, 0x80, 0x1C, 0x9C, 0x4C, 0x00];
+        //The durability of the other Hunter Axe
+        block.extend([250, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0]);
+        block.extend([1, 0, 0, 0, 0, 0, 0, 0, 0x3F, 0, 0, 0, 0, 0, 0, 0]);
+        block.extend([0, 0, 0, 0x80, 0, 0, 0, 0].repeat(3));
+        assert!(check_bytes(&save.file, end + 1, &block));
+        assert_eq!(save.position.coordinates.values(&save.file), coordinates);
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        let found = reparsed.inventory.articles[&ArticleType::RightHand]
+            .iter()
+            .find(|a| a.first_part == axe.first_part)
+            .unwrap();
+        assert_eq!(found.id, axe.id);
+        assert_eq!(found.number, axe.number);
+        assert_eq!(found.slots, axe.slots);
+    }
+
+    #[test]
+    fn inventory_add_armor() {
+        let mut save = build_save_data("testsave0");
+
+        let result = save.add_armor(Location::Storage, 123);
+        assert_eq!(result.unwrap_err().kind(), "ArticleInfoNotFound");
+
+        //Grey Wolf Cap, the highest counter in testsave0 is 0x8D
+        let cap = save.add_armor(Location::Storage, 10000).unwrap();
+        assert_eq!(cap.first_part, 0x9080008E);
+        assert_eq!(cap.second_part, 0x10002710);
+        assert_eq!(cap.article_type, ArticleType::Armor);
+        assert!(cap
+            .slots
+            .as_ref()
+            .unwrap()
+            .iter()
+            .all(|s| s.shape == SlotShape::Closed && s.gem.is_none()));
+
+        let offset = save
+            .file
+            .find_article_offset(cap.number, cap.id, TypeFamily::Armor, true)
+            .unwrap();
+        assert!(check_bytes(
+            &save.file,
+            offset + 4,
+            &[0x8E, 0x00, 0x80, 0x90, 0x10, 0x27, 0x00, 0x10, 1, 0, 0, 0]
+        ));
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        assert!(reparsed.storage.articles[&ArticleType::Armor]
+            .iter()
+            .any(|a| a.first_part == cap.first_part && a.slots == cap.slots));
+    }
+
+    #[test]
+    fn inventory_add_to_full_save() {
+        //testsave1 has no zeros at its end to make room for a slots block
+        let mut save = build_save_data("testsave1");
+        let bytes = save.file.bytes.clone();
+        let articles = save.inventory.articles.clone();
+        let result = save.add_weapon(Location::Inventory, 5000000, None, 0, &[]);
+        assert_eq!(result.unwrap_err().kind(), "SaveFull");
+        let result = save.add_armor(Location::Storage, 10000);
+        assert_eq!(result.unwrap_err().kind(), "SaveFull");
+        assert!(save.file.bytes == bytes);
+        assert_eq!(save.inventory.articles, articles);
+    }
+
+    #[test]
+    fn inventory_create_upgrade_in_full_save() {
+        //testsave1 ends in the middle of a string, the new record would cut it
+        let mut save = build_save_data("testsave1");
+        let no_effect = 0xFFFFFFFF;
+        let bytes = save.file.bytes.clone();
+        let offsets = save.file.offsets.clone();
+        let upgrades = save.inventory.upgrades.clone();
+        let result = save.create_upgrade(
+            Location::Inventory,
+            UpgradeType::Rune,
+            "Oath",
+            [
+                1100000, no_effect, no_effect, no_effect, no_effect, no_effect,
+            ],
+            0x80000000,
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.kind(), "SaveFull");
+        assert_eq!(error.details(), serde_json::json!({ "needed": 32 }));
+        assert!(save.file.bytes == bytes);
+        assert_eq!(save.file.offsets, offsets);
+        assert_eq!(save.inventory.upgrades, upgrades);
+    }
+
+    #[test]
+    fn inventory_create_upgrade() {
+        let mut save = build_save_data("testsave0");
+        let no_effect = 0xFFFFFFFF;
+        let effects = [
+            1100000, no_effect, no_effect, no_effect, no_effect, no_effect,
+        ];
+
+        let result = save.create_upgrade(
+            Location::Inventory,
+            UpgradeType::Rune,
+            "Radial",
+            effects,
+            0x80000000,
+        );
+        assert_eq!(result.unwrap_err().kind(), "InvalidShapeName");
+        let result = save.create_upgrade(
+            Location::Inventory,
+            UpgradeType::Rune,
+            "Oath",
+            [1, no_effect, no_effect, no_effect, no_effect, no_effect],
+            0x80000000,
+        );
+        assert_eq!(result.unwrap_err().kind(), "EffectNotFound");
+
+        //testsave0 has 2 upgrades and its highest counter is 0x8D
+        let (start, end) = save.file.offsets.upgrades;
+        let (gems_start, gems_end) = save.file.offsets.equipped_gems;
+        let moon = save
+            .create_upgrade(
+                Location::Inventory,
+                UpgradeType::Rune,
+                "Oath",
+                effects,
+                0x80000000,
+            )
+            .unwrap();
+        assert_eq!(moon.id, 0xC080008E);
+        assert_eq!(moon.shape, "Oath");
+        assert_eq!(moon.info.name, "Moon");
+        assert_eq!(save.file.offsets.upgrades, (start, end + 40));
+        assert_eq!(
+            save.file.offsets.equipped_gems,
+            (gems_start + 40, gems_end + 32)
+        );
+        assert!(check_bytes(
+            &save.file,
+            end + 1,
+            &[
+                0x8E, 0x00, 0x80, 0xC0, 0x00, 0x00, 0x00, 0x80, 0x02, 0x00, 0x00, 0x00, 0x02, 0x00,
+                0x00, 0x00, 0xE0, 0xC8, 0x10, 0x00, 0xFF, 0xFF, 0xFF, 0xFF
+            ]
+        ));
+        assert_eq!(pool_entries(&save.file).len(), 4096);
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        let runes = &reparsed.inventory.upgrades[&UpgradeType::Rune];
+        assert_eq!(runes.last(), Some(&moon));
+        assert_eq!(
+            save.inventory.upgrades[&UpgradeType::Rune].last(),
+            Some(&moon)
+        );
+        assert_eq!(reparsed.position.coordinates, save.position.coordinates);
+    }
+
+    #[test]
+    fn inventory_destroy_upgrade() {
+        let mut save = build_save_data("testsave9");
+        let counter = |save: &SaveData| {
+            let i = save.file.offsets.username + USERNAME_TO_FIRST_INVENTORY_COUNTER;
+            u32::from_le_bytes([
+                save.file.bytes[i],
+                save.file.bytes[i + 1],
+                save.file.bytes[i + 2],
+                save.file.bytes[i + 3],
+            ])
+        };
+
+        let result = save.destroy_upgrade(0x1234);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Failed to find the upgrade 0x1234."
+        );
+        let result = save
+            .inventory
+            .destroy_upgrade(&mut save.file, UpgradeType::Rune, 999, false);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "There is no Rune at index 999."
+        );
+
+        //Corruption rune in the inventory
+        let (start, end) = save.file.offsets.upgrades;
+        let inventory_counter = counter(&save);
+        let rune = save
+            .inventory
+            .destroy_upgrade(&mut save.file, UpgradeType::Rune, 0, false)
+            .unwrap();
+        assert_eq!(rune.id, 0xC0800072);
+        assert_eq!(rune.info.name, "Corruption");
+        assert!(save.inventory.upgrades[&UpgradeType::Rune].is_empty());
+        assert_eq!(counter(&save), inventory_counter - 1);
+        assert_eq!(save.file.offsets.upgrades, (start, end - 40));
+        assert!(check_bytes(&save.file, end - 39, &EMPTY_ENTRY));
+        assert_eq!(save.file.find_upgrade_offset(rune.id), None);
+
+        //Gem equipped in a weapon of the inventory
+        let gem = save.destroy_upgrade(0xC0800074).unwrap();
+        assert_eq!(gem.info.name, "Tempering Blood Gemstone (2)");
+        assert_eq!(save.file.offsets.upgrades, (start, end - 80));
+        assert_eq!(counter(&save), inventory_counter - 1);
+        assert!(!save
+            .file
+            .bytes
+            .windows(4)
+            .any(|w| w == gem.id.to_le_bytes()));
+        assert_eq!(pool_entries(&save.file).len(), 4096);
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        let equipped = |save: &SaveData| {
+            save.inventory
+                .articles
+                .values()
+                .flatten()
+                .filter_map(|a| a.slots.as_ref())
+                .flatten()
+                .filter_map(|s| s.gem.as_ref().map(|g| g.id))
+                .collect::<Vec<u32>>()
+        };
+        assert!(!equipped(&save).contains(&gem.id));
+        assert_eq!(equipped(&reparsed).len(), equipped(&save).len());
+        assert!(!reparsed.inventory.upgrades.contains_key(&UpgradeType::Rune));
+        assert_eq!(reparsed.position.coordinates, save.position.coordinates);
+    }
+
+    #[test]
+    fn inventory_collect_orphaned_upgrades() {
+        //15 records of testsave6 are in no slot
+        let mut save = build_save_data("testsave6");
+        let (start, end) = save.file.offsets.upgrades;
+        let orphans = save.collect_orphaned_upgrades().unwrap();
+        assert_eq!(orphans.len(), 15);
+        assert!(orphans.contains(&0xC08100FE));
+        assert_eq!(save.file.offsets.upgrades, (start, end - 15 * 40));
+        assert_eq!(pool_entries(&save.file).len(), 4096);
+        assert!(save.collect_orphaned_upgrades().unwrap().is_empty());
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        for (inventory, reparsed) in [
+            (&save.inventory, &reparsed.inventory),
+            (&save.storage, &reparsed.storage),
+        ] {
+            assert_eq!(inventory.upgrades, reparsed.upgrades);
+        }
+        assert_eq!(reparsed.position.coordinates, save.position.coordinates);
+    }
+
+    #[test]
+    fn inventory_move_article() {
+        let mut save = build_save_data("testsave9");
+
+        //Test error cases
+        let result = save.move_article(Location::Inventory, ArticleType::Key, 0, 1);
+        assert_eq!(result.unwrap_err().kind(), "KeyItem");
+        let result = save.move_article(Location::Inventory, ArticleType::Consumable, 0, 0);
+        assert_eq!(result.unwrap_err().kind(), "InvalidQuantity");
+
+        let counter = |save: &SaveData, offset: usize| {
+            let i = save.file.offsets.username + offset;
+            u32::from_le_bytes([
+                save.file.bytes[i],
+                save.file.bytes[i + 1],
+                save.file.bytes[i + 2],
+                save.file.bytes[i + 3],
+            ])
+        };
+        let inventory_counter = counter(&save, USERNAME_TO_FIRST_INVENTORY_COUNTER);
+        let storage_counter = counter(&save, USERNAME_TO_FIRST_STORAGE_COUNTER);
+
+        //Part of the Blood Vials, the storage already has a stack of them
+        let find = |inventory: &Inventory, id: u32| {
+            inventory.articles[&ArticleType::Consumable]
+                .iter()
+                .find(|a| a.id == id)
+                .cloned()
+                .unwrap()
+        };
+        let vials = find(&save.inventory, 1000);
+        let stored_vials = find(&save.storage, 1000);
+        let stack = save
+            .move_article(Location::Inventory, ArticleType::Consumable, vials.index, 5)
+            .unwrap();
+        assert_eq!(stack.amount, stored_vials.amount + 5);
+        assert_eq!(find(&save.inventory, 1000).amount, vials.amount - 5);
+        let offset = save
+            .file
+            .find_article_offset(stack.number, 1000, TypeFamily::Item, true)
+            .unwrap();
+        assert!(check_bytes(
+            &save.file,
+            offset + 12,
+            &(stored_vials.amount + 5).to_le_bytes()
+        ));
+        assert_eq!(
+            counter(&save, USERNAME_TO_FIRST_INVENTORY_COUNTER),
+            inventory_counter
+        );
+        assert_eq!(
+            counter(&save, USERNAME_TO_FIRST_STORAGE_COUNTER),
+            storage_counter
+        );
+
+        //The Hunter Axe with its gems
+        let axe = save.inventory.articles[&ArticleType::RightHand][0].clone();
+        let moved = save
+            .move_article(Location::Inventory, ArticleType::RightHand, 0, 1)
+            .unwrap();
+        assert_eq!(moved.first_part, axe.first_part);
+        assert_eq!(moved.slots, axe.slots);
+        assert_eq!(
+            counter(&save, USERNAME_TO_FIRST_INVENTORY_COUNTER),
+            inventory_counter - 1
+        );
+        assert_eq!(
+            counter(&save, USERNAME_TO_FIRST_STORAGE_COUNTER),
+            storage_counter + 1
+        );
+        let first = save.storage.first_article.unwrap();
+        assert_eq!(
+            save.storage.articles[&first][0].number as u32,
+            storage_counter + 1
+        );
+
+        let reparsed = SaveData::from_file(save.file.clone()).unwrap();
+        assert!(reparsed.storage.articles[&ArticleType::RightHand].contains(&moved));
+        //The Blood Vials are the first article of the storage, their number follows the counter
+        assert_eq!(
+            find(&reparsed.storage, 1000),
+            Article {
+                number: stack.number + 1,
+                ..stack
+            }
+        );
+        assert_eq!(
+            reparsed.storage.articles[&first][0],
+            save.storage.articles[&first][0]
+        );
+        assert!(reparsed.inventory.articles[&ArticleType::RightHand]
+            .iter()
+            .all(|a| a.first_part != axe.first_part));
+
+        //A stack doesn't go past the most its location holds, nothing is written then
+        let vials = find(&save.inventory, 1000);
+        let stored_vials = find(&save.storage, 1000);
+        let room = vials.max_amount(Location::Inventory) - vials.amount;
+        let bytes = save.file.bytes.clone();
+        let result = save.move_article(
+            Location::Storage,
+            ArticleType::Consumable,
+            stored_vials.index,
+            room + 1,
+        );
+        assert_eq!(result.unwrap_err().kind(), "StackFull");
+        assert_eq!(save.file.bytes, bytes);
+        assert_eq!(find(&save.storage, 1000), stored_vials);
+        let stack = save
+            .move_article(
+                Location::Storage,
+                ArticleType::Consumable,
+                stored_vials.index,
+                room,
+            )
+            .unwrap();
+        assert_eq!(stack.amount, vials.max_amount(Location::Inventory));
+    }
+}
@@ -0,0 +1,469 @@
+//! Save-wide legitimacy report, everything in a save the game can't produce on its own.
+//!
+//! Gems and runes are checked by [`SaveData::validate_upgrades`], their violations are
+//! reported here along with the character, its articles and the boss flags.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{cmp::Reverse, collections::HashMap};
+
+use super::{
+    article::WeaponMods,
+    constants::{MAX_ATTRIBUTE, MAX_UPGRADE_LEVEL},
+    enums::{ArticleType, Check, Error, Location, Severity},
+    file::FileData,
+    inventory::Inventory,
+    resources,
+    save::SaveData,
+    stats::{self, Stat},
+};
+
+//Key items the game hands out once: the keys, the workshop tools, the hunter badges and the
+//items of the NPC quests. The messenger accessories aren't checked
+const UNIQUE_KEY_ITEMS: [u32; 41] = [
+    4000, 4003, 4006, 4009, 4010, 4011, 4012, 4013, 4014, 4015, 4017, 4018, 4019, 4020, 4021, 4102,
+    4103, 4104, 4105, 4110, 4111, 4112, 4113, 4114, 4115, 4116, 4117, 4118, 4119, 4120, 4300, 4304,
+    4305, 4308, 4310, 4311, 4312, 4330, 4340, 4341, 4342,
+];
+
+/// Something the game can't produce. `id` and `location` tell which article or upgrade
+/// it is about, they are empty for the stats and the bosses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: Check,
+    pub subject: String,
+    pub id: Option<u32>,
+    pub location: Option<Location>,
+    pub reason: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, check: Check, subject: &str, reason: String) -> Finding {
+        Finding {
+            severity,
+            check,
+            subject: subject.to_string(),
+            id: None,
+            location: None,
+            reason,
+        }
+    }
+
+    fn at(mut self, id: u32, location: Location) -> Finding {
+        self.id = Some(id);
+        self.location = Some(location);
+        self
+    }
+}
+
+/// Result of [`validate`], the most severe findings come first.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Report {
+    pub errors: usize,
+    pub warnings: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// True when nothing the game can't produce was found.
+    pub fn is_legit(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// Walks the whole save and reports what the game can't produce naturally: attributes above
+/// 99, below the origin or that don't add up to the level, Health and Stamina that don't
+/// follow Vitality and Endurance, weapons above +10 or with an imprint they don't have,
+/// duplicated unique key items, quantities over their caps, boss flags that are neither dead nor
+/// alive and illegal gems and runes.
+pub fn validate(save: &SaveData) -> Result<Report, Error> {
+    let mut findings = Vec::new();
+    check_stats(&save.file, &save.stats, &mut findings);
+    check_origin(save, &mut findings);
+
+    let weapons: Value = serde_json::from_str(resources::WEAPONS)?;
+    let mut key_items: HashMap<u32, u32> = HashMap::new();
+    for (location, inventory) in [
+        (Location::Inventory, &save.inventory),
+        (Location::Storage, &save.storage),
+    ] {
+        check_articles(inventory, location, &weapons, &mut findings)?;
+        for article in inventory
+            .articles
+            .get(&ArticleType::Key)
+            .into_iter()
+            .flatten()
+            .filter(|a| UNIQUE_KEY_ITEMS.contains(&a.id))
+        {
+            *key_items.entry(article.id).or_insert(0) += article.amount;
+        }
+    }
+    for (location, inventory) in [
+        (Location::Inventory, &save.inventory),
+        (Location::Storage, &save.storage),
+    ] {
+        for article in inventory
+            .articles
+            .get(&ArticleType::Key)
+            .into_iter()
+            .flatten()
+        {
+            let amount = key_items.remove(&article.id).unwrap_or(0);
+            if amount > 1 {
+                findings.push(
+                    Finding::new(
+                        Severity::Error,
+                        Check::DuplicateKeyItem,
+                        &article.info.item_name,
+                        format!("The save holds {} copies of a unique key item.", amount),
+                    )
+                    .at(article.id, location),
+                );
+            }
+        }
+    }
+
+    for boss in &save.bosses {
+        for (rel_offset, value) in boss.unexpected_flags() {
+            findings.push(Finding::new(
+                Severity::Warning,
+                Check::InvalidBossFlag,
+                boss.name(),
+                format!(
+                    "The bits of the flag at {} are {}, neither its dead nor its alive value.",
+                    rel_offset, value
+                ),
+            ));
+        }
+    }
+
+    for report in save.validate_upgrades()? {
+        for violation in report.violations {
+            findings.push(
+                Finding::new(
+                    Severity::Error,
+                    Check::Upgrade(violation.rule),
+                    &report.name,
+                    violation.reason,
+                )
+                .at(report.id, report.location),
+            );
+        }
+    }
+
+    //Stable, so the findings of the same severity keep the order of the save
+    findings.sort_by_key(|f| Reverse(f.severity));
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    Ok(Report {
+        errors,
+        warnings: findings.len() - errors,
+        findings,
+    })
+}
+
+fn check_stats(file: &FileData, stats: &[Stat], findings: &mut Vec<Finding>) {
+    let find = |name: &str| stats.iter().find(|s| s.name == name);
+
+    let mut attributes = Vec::with_capacity(stats::ATTRIBUTES.len());
+    for name in stats::ATTRIBUTES {
+        if let Some(attribute) = find(name) {
+            if attribute.value > MAX_ATTRIBUTE {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    Check::AttributeAboveMax,
+                    name,
+                    format!(
+                        "{} is {}, attributes stop at {}.",
+                        name, attribute.value, MAX_ATTRIBUTE
+                    ),
+                ));
+            }
+            attributes.push(attribute.value);
+        }
+    }
+    if let Some(level) = find("Level") {
+        let expected = stats::level(&attributes);
+        if level.value != expected {
+            findings.push(Finding::new(
+                Severity::Error,
+                Check::LevelMismatch,
+                "Level",
+                format!(
+                    "Level is {}, the attributes add up to level {}.",
+                    level.value, expected
+                ),
+            ));
+        }
+    }
+
+    let pools = [
+        (
+            "Health",
+            "Vitality",
+            stats::max_health as fn(u32) -> u32,
+            Check::HealthMismatch,
+        ),
+        (
+            "Stamina",
+            "Endurance",
+            stats::max_stamina,
+            Check::StaminaMismatch,
+        ),
+    ];
+    for (name, attribute_name, formula, check) in pools {
+        let (pool, attribute) = match (find(name), find(attribute_name)) {
+            (Some(pool), Some(attribute)) => (pool, attribute),
+            _ => continue,
+        };
+        if let [current, max, base] = pool.copies(file)[..] {
+            let expected = formula(attribute.value);
+            //The graphs may be off by a few points, the other checks are exact
+            if base != expected {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    check,
+                    name,
+                    format!(
+                        "The base max {} is {}, {} {} grants {}.",
+                        name, base, attribute_name, attribute.value, expected
+                    ),
+                ));
+            }
+            if max < base {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    check,
+                    name,
+                    format!("The max {} is {}, below its base {}.", name, max, base),
+                ));
+            }
+            if current > max {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    check,
+                    name,
+                    format!("{} is {}, above its max {}.", name, current, max),
+                ));
+            }
+        }
+    }
+}
+
+fn check_origin(save: &SaveData, findings: &mut Vec<Finding>) {
+    let origin = match &save.origin {
+        Some(origin) => origin,
+        None => {
+            if let Ok(stat) = save.stat("Origin") {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    Check::AttributeBelowOrigin,
+                    "Origin",
+                    format!("Origin is {}, there are only 9 origins.", stat.value),
+                ));
+            }
+            return;
+        }
+    };
+    for (name, min) in stats::ATTRIBUTES.iter().zip(origin.attributes.values()) {
+        if let Ok(attribute) = save.stat(name) {
+            if attribute.value < min {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    Check::AttributeBelowOrigin,
+                    name,
+                    format!(
+                        "{} is {}, {} starts with {}.",
+                        name, attribute.value, origin.origin, min
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+fn check_articles(
+    inventory: &Inventory,
+    location: Location,
+    weapons: &Value,
+    findings: &mut Vec<Finding>,
+) -> Result<(), Error> {
+    for article in inventory.articles.values().flatten() {
+        let name = &article.info.item_name;
+        match article.article_type {
+            ArticleType::RightHand | ArticleType::LeftHand => {
+                let weapon_mods = WeaponMods::try_from(article.second_part)?;
+                if weapon_mods.upgrade_level > MAX_UPGRADE_LEVEL {
+                    findings.push(
+                        Finding::new(
+                            Severity::Error,
+                            Check::UpgradeLevelAboveMax,
+                            name,
+                            format!(
+                                "{} is +{}, weapons stop at +{}.",
+                                name, weapon_mods.upgrade_level, MAX_UPGRADE_LEVEL
+                            ),
+                        )
+                        .at(article.id, location),
+                    );
+                }
+                if let Some(imprint) = weapon_mods.imprint {
+                    //The imprinted versions are listed with the upgrade level removed
+                    let key = (article.second_part - article.second_part % 10000).to_string();
+                    let listed = weapons
+                        .as_object()
+                        .into_iter()
+                        .flat_map(|categories| categories.values())
+                        .any(|category| category.get(&key).is_some());
+                    if !listed {
+                        findings.push(
+                            Finding::new(
+                                Severity::Error,
+                                Check::ImpossibleImprint,
+                                name,
+                                format!("{} has no {:?} version.", name, imprint),
+                            )
+                            .at(article.id, location),
+                        );
+                    }
+                }
+            }
+            ArticleType::Armor | ArticleType::Key => (),
+            _ => {
+                let cap = article.max_amount(location);
+                if article.amount > cap {
+                    findings.push(
+                        Finding::new(
+                            Severity::Error,
+                            Check::QuantityAboveCap,
+                            name,
+                            format!(
+                                "{} {} in the {}, it holds at most {}.",
+                                article.amount, name, location, cap
+                            ),
+                        )
+                        .at(article.id, location),
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{enums::UpgradeRule, utils::test_utils::build_save_data};
+
+    fn checks(report: &Report) -> Vec<Check> {
+        report.findings.iter().map(|f| f.check).collect()
+    }
+
+    #[test]
+    fn legitimacy_validate() {
+        //testsave8 comes straight from the game
+        let report = build_save_data("testsave8").validate().unwrap();
+        assert!(report.is_legit());
+        assert_eq!((report.errors, report.warnings), (0, 0));
+
+        //testsave4, attributes at 100 and a full storage of Vermin
+        let report = build_save_data("testsave4").validate().unwrap();
+        assert_eq!((report.errors, report.warnings), (15, 3));
+        let found = checks(&report);
+        assert_eq!(
+            found
+                .iter()
+                .filter(|c| **c == Check::AttributeAboveMax)
+                .count(),
+            6
+        );
+        assert!(found.contains(&Check::LevelMismatch));
+        assert!(found.contains(&Check::Upgrade(UpgradeRule::DuplicateEffect)));
+        let vermin = report
+            .findings
+            .iter()
+            .find(|f| f.check == Check::QuantityAboveCap)
+            .unwrap();
+        assert_eq!(vermin.subject, "Vermin");
+        assert_eq!(vermin.location, Some(Location::Storage));
+        let micolash = report
+            .findings
+            .iter()
+            .find(|f| f.check == Check::InvalidBossFlag)
+            .unwrap();
+        assert_eq!(micolash.severity, Severity::Warning);
+        assert_eq!(micolash.subject, "Micolash");
+        //Errors come first
+        assert!(report.findings[..15]
+            .iter()
+            .all(|f| f.severity == Severity::Error));
+    }
+
+    #[test]
+    fn legitimacy_item_caps() {
+        //testsave8 comes straight from the game, with 10 Molotov Cocktails and 25 Blood Vials
+        let mut save = build_save_data("testsave8");
+        let consumables = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::Consumable)
+            .unwrap();
+        let molotov = consumables.iter_mut().find(|a| a.id == 1200).unwrap();
+        molotov.amount = 11;
+        let report = save.validate().unwrap();
+        assert_eq!(checks(&report), vec![Check::QuantityAboveCap]);
+        assert_eq!(
+            report.findings[0].reason,
+            "11 Molotov Cocktail in the inventory, it holds at most 10."
+        );
+
+        //Only the unique key items are counted, a second messenger accessory isn't reported
+        let items: Value = serde_json::from_str(resources::ITEMS).unwrap();
+        for id in UNIQUE_KEY_ITEMS {
+            assert!(items["key"].get(id.to_string()).is_some(), "{}", id);
+        }
+        let keys = save.inventory.articles.get_mut(&ArticleType::Key).unwrap();
+        let ribbon = keys.iter().find(|a| a.id == 4903).unwrap().clone();
+        keys.push(ribbon);
+        assert_eq!(
+            checks(&save.validate().unwrap()),
+            vec![Check::QuantityAboveCap]
+        );
+    }
+
+    #[test]
+    fn legitimacy_validate_articles() {
+        //testsave2, its first left-hand weapon as a +12 Uncanny Hunter Pistol and a second copy of a key item
+        let mut save = build_save_data("testsave2");
+        assert!(save.validate().unwrap().is_legit());
+        let pistol = save
+            .inventory
+            .articles
+            .get_mut(&ArticleType::LeftHand)
+            .unwrap()
+            .first_mut()
+            .unwrap();
+        pistol.second_part = 14011200;
+        let keys = save.inventory.articles.get_mut(&ArticleType::Key).unwrap();
+        let key = keys[0].clone();
+        keys.push(key.clone());
+
+        let report = save.validate().unwrap();
+        assert_eq!(
+            checks(&report),
+            vec![
+                Check::UpgradeLevelAboveMax,
+                Check::ImpossibleImprint,
+                Check::DuplicateKeyItem
+            ]
+        );
+        assert_eq!(report.findings[2].id, Some(key.id));
+        assert_eq!(
+            report.findings[2].reason,
+            "The save holds 2 copies of a unique key item."
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Parser and editor for decrypted Bloodborne character saves.
+//!
+//! The crate has no dependency on the editor's UI, everything it needs to
+//! identify articles, gems, bosses and flags is embedded from the [`resources`] module.
+//!
+//! The usual entry point is [`save::SaveData::build`], which reads a save,
+//! locates its regions and parses the character. Every edit goes through a
+//! method that receives the underlying [`file::FileData`] so the raw bytes and
+//! the parsed model stay in sync, and [`save::SaveData::save`] writes the result.
+//!
+//! ```no_run
+//! use data_handling::save::SaveData;
+//!
+//! let mut save = SaveData::build("userdata0000").unwrap();
+//! let vitality = save.stats.iter_mut().find(|s| s.name == "Vitality").unwrap();
+//! vitality.edit(50, &mut save.file);
+//! save.save("userdata0000").unwrap();
+//! ```
+pub mod appearance;
+pub mod article;
+pub mod backup;
+pub mod bosses;
+pub mod constants;
+pub mod defense;
+pub mod enums;
+pub mod equipment;
+pub mod file;
+pub mod flags;
+pub mod history;
+pub mod inventory;
+pub mod legitimacy;
+pub mod offsets;
+pub mod patch;
+pub mod position;
+pub mod presets;
+pub mod resources;
+pub mod save;
+pub mod sheet;
+pub mod slots;
+pub mod stats;
+pub mod upgrades;
+pub mod username;
+pub mod utils;
@@ -1,3 +1,4 @@
+//! Location of every region of the save.
 use super::{constants::*, enums::Error};
 use serde::{Deserialize, Serialize};
 
@@ -14,15 +15,12 @@ pub struct Offsets {
 }
 
 impl Offsets {
-    //Searches the username and inventories offsets
-    pub fn build(bytes: &Vec<u8>) -> Result<Offsets, Error> {
-        let mut username_offset = 0;
+    ///Searches the username and inventories offsets
+    pub fn build(bytes: &[u8]) -> Result<Offsets, Error> {
         let mut inventory_offset = (0, 0);
         let mut upgrades_offset = (START_TO_UPGRADE, 0);
         let mut appearance_offset = (0, 0);
         let mut lced_offset = 0;
-        let inv_start_bytes = vec![0x40, 0xf0, 0xff, 0xff]; //Bytes the inventory starts with
-        let inv_start_bytes_len = inv_start_bytes.len();
         let appearance_start_bytes = [b'F', b'A', b'C', b'E'];
         let lced_bytes = [0x4C, 0x43, 0x45, 0x44];
 
@@ -40,7 +38,10 @@ impl Offsets {
 
         //Get the end offset for the upgrades
         for i in (upgrades_offset.0..(bytes.len())).step_by(40) {
-            let current = &bytes[(i + 8)..(i + 16)];
+            let current = match bytes.get((i + 8)..(i + 16)) {
+                Some(current) => current,
+                None => break,
+            };
 
             let is_match =
                 runes.iter().any(|&x| current == x) || gems.iter().any(|&x| current == x);
@@ -53,7 +54,7 @@ impl Offsets {
 
         let mut last_i: usize = 0;
         //Searches for the appearance_start_bytes
-        for i in 0xF000..(bytes.len() - 4) {
+        for i in 0xF000..bytes.len().saturating_sub(4) {
             //0xF000: In all the saves i found the save bytes after 0x10000
             if appearance_start_bytes == bytes[i..i + 4] {
                 appearance_offset.0 = i + 4;
@@ -63,17 +64,23 @@ impl Offsets {
             }
         }
         if appearance_offset.0 == 0 {
-            return Err(Error::CustomError("Failed to find the appearance."));
+            return Err(Error::OffsetNotFound {
+                region: "appearance",
+            });
+        }
+
+        if upgrades_offset.1 == 0 {
+            return Err(Error::OffsetNotFound { region: "upgrades" });
         }
 
         inventory_offset.0 = appearance_offset.0 - 4 - 34028;
-        username_offset = inventory_offset.0 - USERNAME_TO_INV_OFFSET;
+        let username_offset = inventory_offset.0 - USERNAME_TO_INV_OFFSET;
 
         //Find the end of the inventories
         inventory_offset.1 = username_offset + USERNAME_TO_INV_OFFSET + 1983 * 16; // source for the 1984 slots: https://www.bloodborne-wiki.com/2024/02/full-storage-glitch.html
 
         // Find lced offset
-        for i in last_i..(bytes.len() - 1) {
+        for i in last_i..bytes.len().saturating_sub(3) {
             if lced_bytes == bytes[i..i + 4] {
                 lced_offset = i;
                 break;
@@ -91,6 +98,24 @@ impl Offsets {
             username_offset + USERNAME_TO_KEY_INV_OFFSET + 2204,
         );
 
+        //Everything after this point is read at fixed distances, so the file has to reach them
+        let regions = [
+            ("appearance", appearance_offset.1 + 1),
+            ("inventory", inventory_offset.1 + 16),
+            ("key inventory", key_inventory_offset.1 + 16),
+            ("storage", storage_offset.1),
+            ("flags", username_offset + USERNAME_TO_ISZ_GLITCH + 2),
+        ];
+        for (region, end) in regions {
+            if end > bytes.len() {
+                return Err(Error::TruncatedFile {
+                    region,
+                    offset: end,
+                    size: bytes.len(),
+                });
+            }
+        }
+
         Ok(Offsets {
             username: username_offset,
             inventory: inventory_offset,
@@ -107,57 +132,50 @@ impl Offsets {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data_handling::file::FileData;
-    use std::path::PathBuf;
+    use crate::file::FileData;
 
     #[test]
     fn offsets_build() {
         //Test with invalid path
-        let file_data = FileData::build("invalid", PathBuf::from("resources"));
+        let file_data = FileData::build("invalid");
         assert!(file_data.is_err());
         if let Err(e) = file_data {
             assert!(e.to_string().contains("I/0 error:"));
         }
 
         //Test with empty save
-        let file_data = FileData::build("saves/emptysave", PathBuf::from("resources"));
+        let file_data = FileData::build("saves/emptysave");
         assert!(file_data.is_err());
         if let Err(e) = file_data {
-            assert_eq!(e.to_string(), "Save error: The selected file is empty.");
+            assert_eq!(e.to_string(), "The selected file is empty.");
         }
 
         //Test with a save that has no inventory
-        let file_data = FileData::build("saves/no_inv_save", PathBuf::from("resources"));
+        let file_data = FileData::build("saves/no_inv_save");
         assert!(file_data.is_err());
         if let Err(e) = file_data {
-            assert_eq!(
-                e.to_string(),
-                "Save error: Failed to find username in save data."
-            );
+            assert_eq!(e.to_string(), "Failed to find the appearance.");
         }
 
         //Test a save in which the inventory has no end
-        let file_data = FileData::build("saves/no_inv_end_save", PathBuf::from("resources"));
+        let file_data = FileData::build("saves/no_inv_end_save");
         assert!(file_data.is_err());
         if let Err(e) = file_data {
-            assert_eq!(
-                e.to_string(),
-                "Save error: Failed to find the end of the inventory."
-            );
+            assert_eq!(e.to_string(), "Failed to find the appearance.");
         }
 
         //Test a save with no appearance
-        let file_data = FileData::build("saves/noappearancesave0", PathBuf::from("resources"));
+        let file_data = FileData::build("saves/noappearancesave0");
         assert!(file_data.is_err());
         if let Err(e) = file_data {
-            assert_eq!(e.to_string(), "Save error: Failed to find the appearance.");
+            assert_eq!(e.to_string(), "Failed to find the appearance.");
         }
 
         //testsave0
-        let file_data = FileData::build("saves/testsave0", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave0").unwrap();
         assert_eq!(file_data.offsets.username, 0x8777);
-        assert_eq!(file_data.offsets.inventory, (0x894c, 0x8cd0));
-        assert_eq!(file_data.offsets.key_inventory, (0x10540, 0x105af));
+        assert_eq!(file_data.offsets.inventory, (0x894c, 0x1053c));
+        assert_eq!(file_data.offsets.key_inventory, (0x10540, 0x10ddc));
         assert_eq!(file_data.offsets.upgrades, (84, 163));
         assert_eq!(
             file_data.offsets.appearance,
@@ -165,10 +183,10 @@ mod tests {
         );
 
         //testsave1
-        let file_data = FileData::build("saves/testsave1", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave1").unwrap();
         assert_eq!(file_data.offsets.username, 0xa82b);
-        assert_eq!(file_data.offsets.inventory, (0xaa00, 0xb6a4));
-        assert_eq!(file_data.offsets.key_inventory, (0x125f4, 0x126e3));
+        assert_eq!(file_data.offsets.inventory, (0xaa00, 0x125f0));
+        assert_eq!(file_data.offsets.key_inventory, (0x125f4, 0x12e90));
         assert_eq!(file_data.offsets.upgrades, (84, 0x8c3));
         assert_eq!(
             file_data.offsets.appearance,
@@ -176,10 +194,10 @@ mod tests {
         );
 
         //testsave2
-        let file_data = FileData::build("saves/testsave2", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave2").unwrap();
         assert_eq!(file_data.offsets.username, 0xa86f);
-        assert_eq!(file_data.offsets.inventory, (0xaa44, 0xb638));
-        assert_eq!(file_data.offsets.key_inventory, (0x12638, 0x12797));
+        assert_eq!(file_data.offsets.inventory, (0xaa44, 0x12634));
+        assert_eq!(file_data.offsets.key_inventory, (0x12638, 0x12ed4));
         assert_eq!(file_data.offsets.upgrades, (84, 0x7d3));
         assert_eq!(
             file_data.offsets.appearance,
@@ -187,10 +205,10 @@ mod tests {
         );
 
         //testsave3
-        let file_data = FileData::build("saves/testsave3", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave3").unwrap();
         assert_eq!(file_data.offsets.username, 0xb473);
-        assert_eq!(file_data.offsets.inventory, (0xb648, 0xc8ac));
-        assert_eq!(file_data.offsets.key_inventory, (0x1323c, 0x133db));
+        assert_eq!(file_data.offsets.inventory, (0xb648, 0x13238));
+        assert_eq!(file_data.offsets.key_inventory, (0x1323c, 0x13ad8));
         assert_eq!(file_data.offsets.upgrades, (84, 0xf7b));
         assert_eq!(
             file_data.offsets.appearance,
@@ -198,10 +216,10 @@ mod tests {
         );
 
         //testsave4
-        let file_data = FileData::build("saves/testsave4", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave4").unwrap();
         assert_eq!(file_data.offsets.username, 0xc85f);
-        assert_eq!(file_data.offsets.inventory, (0xca34, 0xe778));
-        assert_eq!(file_data.offsets.key_inventory, (0x14628, 0x14857));
+        assert_eq!(file_data.offsets.inventory, (0xca34, 0x14624));
+        assert_eq!(file_data.offsets.key_inventory, (0x14628, 0x14ec4));
         assert_eq!(file_data.offsets.upgrades, (84, 163));
         assert_eq!(
             file_data.offsets.appearance,
@@ -209,10 +227,10 @@ mod tests {
         );
 
         //testsave8
-        let file_data = FileData::build("saves/testsave8", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave8").unwrap();
         assert_eq!(file_data.offsets.username, 0x19897);
-        assert_eq!(file_data.offsets.inventory, (0x19a6c, 0x210d0));
-        assert_eq!(file_data.offsets.key_inventory, (0x21660, 0x218ef));
+        assert_eq!(file_data.offsets.inventory, (0x19a6c, 0x2165c));
+        assert_eq!(file_data.offsets.key_inventory, (0x21660, 0x21efc));
         assert_eq!(file_data.offsets.upgrades, (84, 0x10ae3));
         assert_eq!(
             file_data.offsets.appearance,
@@ -0,0 +1,838 @@
+//! Differences between two saves, grouped by the region of the save they are in.
+//!
+//! Every change is stored relative to its region (or to the gem block of its article)
+//! instead of as an absolute offset, so a [`SaveDiff`] doubles as a patch that can be
+//! applied to another save whose regions are at different offsets.
+//!
+//! Upgrades and slots blocks that are only in one of the saves change the size of the entries
+//! before the username, they are stored whole as [`RecordChange`]s and inserted or removed
+//! through [`FileData::resize`] before the byte changes are applied.
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fmt, fs};
+
+use super::{
+    constants::{APPEARANCE_BYTES_AMOUNT, EMPTY_ENTRY, START_TO_UPGRADE, USERNAME_TO_AOB},
+    enums::Error,
+    file::FileData,
+    history::diff as diff_bytes,
+    inventory::{insert_slots_block, insert_upgrade_record, remove_upgrade_records, slots_blocks},
+    save::SaveData,
+};
+
+/// Version of the patch files written by [`SaveDiff::to_json`], older versions can still be read.
+pub const PATCH_VERSION: u32 = 2;
+
+//Size of an inventory slot, an upgrade and the slots block of a weapon or armor
+const SLOT_SIZE: usize = 16;
+const UPGRADE_SIZE: usize = 40;
+const GEM_BLOCK_SIZE: usize = 60;
+//Changes closer than this are stored as a single range
+const MERGE_GAP: usize = 8;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Region {
+    Header, //Loaded map and playtime
+    Upgrades,
+    EquippedGems,
+    Inventory,
+    KeyInventory,
+    Appearance,
+    Storage,
+    Flags,
+    Position,
+    Other, //Everything else, relative to the username
+}
+
+impl Region {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Region::Header => "header",
+            Region::Upgrades => "upgrades",
+            Region::EquippedGems => "equipped gems",
+            Region::Inventory => "inventory",
+            Region::KeyInventory => "key inventory",
+            Region::Appearance => "appearance",
+            Region::Storage => "storage",
+            Region::Flags => "flags",
+            Region::Position => "position",
+            Region::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StatChange {
+    pub name: String,
+    pub before: u32,
+    pub after: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RegionChange {
+    pub region: Region,
+    pub offset: isize, //Relative to the start of the region, or of the gem block
+    //First part of the article that owns the gem block, only for equipped gems
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<u32>,
+    pub before: Vec<u8>,
+    pub after: Vec<u8>,
+    pub description: String,
+}
+
+/// An upgrade or slots block that is only in one of the saves.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum RecordChange {
+    InsertUpgrade { record: Vec<u8> },
+    RemoveUpgrade { id: u32 },
+    InsertSlots { block: Vec<u8> },
+    RemoveSlots { first_part: u32 },
+}
+
+impl RecordChange {
+    //Inserts or removes the record, the entries before the username keep their count
+    fn apply(&self, file: &mut FileData, force: bool) -> Result<(), Error> {
+        let conflict = |region: Region| Error::PatchConflict {
+            region: region.name(),
+            offset: 0,
+        };
+        match self {
+            RecordChange::InsertUpgrade { record } => {
+                if record.len() != UPGRADE_SIZE || upgrade_ids(file).contains(&read_u32(record, 0))
+                {
+                    return Err(conflict(Region::Upgrades));
+                }
+                insert_upgrade_record(file, record)
+            }
+            RecordChange::RemoveUpgrade { id } => match upgrade_ids(file).contains(id) {
+                true => remove_upgrade_records(file, &[*id]),
+                false if force => Ok(()),
+                false => Err(conflict(Region::Upgrades)),
+            },
+            RecordChange::InsertSlots { block } => {
+                if block.len() != GEM_BLOCK_SIZE
+                    || slots_blocks(file)
+                        .iter()
+                        .any(|&(f, _)| f == read_u32(block, 0))
+                {
+                    return Err(conflict(Region::EquippedGems));
+                }
+                insert_slots_block(file, block).map(|_| ())
+            }
+            RecordChange::RemoveSlots { first_part } => {
+                match slots_blocks(file).iter().find(|&&(f, _)| f == *first_part) {
+                    Some(&(_, offset)) => file.resize(offset, GEM_BLOCK_SIZE, &EMPTY_ENTRY),
+                    None if force => Ok(()),
+                    None => Err(conflict(Region::EquippedGems)),
+                }
+            }
+        }
+    }
+}
+
+/// Changes needed to turn one save into another, also used as a portable patch.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SaveDiff {
+    pub version: u32,
+    pub stats: Vec<StatChange>,
+    //Applied before the changes, whose offsets are relative to the save with them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub records: Vec<RecordChange>,
+    pub changes: Vec<RegionChange>,
+}
+
+impl SaveDiff {
+    pub fn is_empty(&self) -> bool {
+        self.stats.is_empty() && self.records.is_empty() && self.changes.is_empty()
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<SaveDiff, Error> {
+        let patch: SaveDiff = serde_json::from_str(json)?;
+        if patch.version == 0 || patch.version > PATCH_VERSION {
+            return Err(Error::UnsupportedVersion {
+                format: "patch",
+                version: patch.version,
+                supported: PATCH_VERSION,
+            });
+        }
+        Ok(patch)
+    }
+
+    /// Applies the patch to `save` and parses it again.
+    ///
+    /// The records are inserted or removed first, on a copy of the save. Then every change is
+    /// checked against the bytes it replaces, if one doesn't match nothing is written and
+    /// [`Error::PatchConflict`] is returned. With `force` the check is skipped and the after
+    /// side is written anyway. An upgrade or slots block that is already in the save is a
+    /// conflict even with `force`.
+    pub fn apply(&self, save: &mut SaveData, force: bool) -> Result<(), Error> {
+        let resized;
+        let base = match self.records.is_empty() {
+            true => &*save,
+            false => {
+                let mut file = save.file.clone();
+                for record in &self.records {
+                    record.apply(&mut file, force)?;
+                }
+                resized = SaveData::from_file(file)?;
+                &resized
+            }
+        };
+        let patched = self.apply_changes(base, force)?;
+
+        let history = std::mem::take(&mut save.history);
+        *save = patched;
+        save.history = history;
+        Ok(())
+    }
+
+    //Writes the changes and the stats over `save`, which already has the records
+    fn apply_changes(&self, save: &SaveData, force: bool) -> Result<SaveData, Error> {
+        //Everything is checked against the current bytes before writing anything
+        let mut writes = Vec::with_capacity(self.changes.len());
+        for change in &self.changes {
+            let conflict = Error::PatchConflict {
+                region: change.region.name(),
+                offset: change.offset,
+            };
+            let start = match change.block {
+                Some(first_part) => find_gem_block(save, first_part).map(|b| b as isize),
+                None => Some(region_start(save, change.region)),
+            };
+            let offset = match start {
+                Some(start) if start + change.offset >= 0 => (start + change.offset) as usize,
+                _ => return Err(conflict),
+            };
+            let end = offset + change.after.len();
+            let bytes = &save.file.bytes;
+            if end > bytes.len() || (!force && bytes[offset..end] != change.before[..]) {
+                return Err(conflict);
+            }
+            writes.push((offset..end, &change.after));
+        }
+
+        let mut bytes = save.file.bytes.clone();
+        for (range, after) in writes {
+            bytes[range].copy_from_slice(after);
+        }
+        //The regions can move when the amount of upgrades changes
+        let mut file = FileData::from_bytes(bytes)?;
+
+        let mut stats = save.stats.clone();
+        for change in &self.stats {
+            match stats.iter_mut().find(|s| s.name == change.name) {
+                Some(stat) if force || stat.value == change.before => {
+                    stat.edit(change.after, &mut file)
+                }
+                _ => {
+                    return Err(Error::PatchConflict {
+                        region: "stats",
+                        offset: 0,
+                    })
+                }
+            }
+        }
+
+        SaveData::from_file(file)
+    }
+}
+
+/// Compares `before` with `after`, region by region.
+///
+/// The upgrades and slots blocks that are only in one of them are inserted or removed on a copy
+/// of `before` first, which fails if `before` has no room for them.
+pub fn diff(before: &SaveData, after: &SaveData) -> Result<SaveDiff, Error> {
+    let stats = after
+        .stats
+        .iter()
+        .filter_map(|a| {
+            let b = before.stats.iter().find(|b| b.name == a.name)?;
+            match a.value != b.value {
+                true => Some(StatChange {
+                    name: a.name.clone(),
+                    before: b.value,
+                    after: a.value,
+                }),
+                false => None,
+            }
+        })
+        .collect();
+
+    let records = diff_pool(&before.file, &after.file);
+    let resized;
+    let before = match records.is_empty() {
+        true => before,
+        false => {
+            let mut file = before.file.clone();
+            for record in &records {
+                record.apply(&mut file, false)?;
+            }
+            resized = SaveData::from_file(file)?;
+            &resized
+        }
+    };
+
+    let mut changes = Vec::new();
+    for region in [Region::Inventory, Region::KeyInventory, Region::Storage] {
+        diff_records(
+            before,
+            after,
+            region,
+            SLOT_SIZE,
+            describe_slot,
+            &mut changes,
+        );
+    }
+    diff_records(
+        before,
+        after,
+        Region::Upgrades,
+        UPGRADE_SIZE,
+        describe_upgrade,
+        &mut changes,
+    );
+    //When the entries before the username are laid out differently, a block can't be paired
+    //by its article and they are compared byte by byte instead
+    let same_layout = pool_layout(&before.file) == pool_layout(&after.file);
+    if same_layout {
+        diff_gem_blocks(before, after, &mut changes);
+    }
+    for region in [
+        Region::Header,
+        Region::Appearance,
+        Region::Flags,
+        Region::Position,
+    ] {
+        diff_region(before, after, region, &mut changes);
+    }
+    diff_other(before, after, same_layout, &mut changes);
+
+    Ok(SaveDiff {
+        version: PATCH_VERSION,
+        stats,
+        records,
+        changes,
+    })
+}
+
+//Slots blocks and their distance to the username
+fn pool_layout(file: &FileData) -> Vec<(u32, usize)> {
+    slots_blocks(file)
+        .into_iter()
+        .map(|(first_part, offset)| (first_part, file.offsets.username - offset))
+        .collect()
+}
+
+fn upgrade_ids(file: &FileData) -> Vec<u32> {
+    let (start, end) = file.offsets.upgrades;
+    (start..end)
+        .step_by(UPGRADE_SIZE)
+        .map(|offset| read_u32(&file.bytes, offset))
+        .collect()
+}
+
+//Records that are only in one of the saves. The removals go first so their entries are free
+//for the insertions, which keep the order they have in `after`
+fn diff_pool(before: &FileData, after: &FileData) -> Vec<RecordChange> {
+    let (old_ids, new_ids) = (upgrade_ids(before), upgrade_ids(after));
+    let (old_blocks, new_blocks) = (slots_blocks(before), slots_blocks(after));
+    let mut records: Vec<RecordChange> = old_ids
+        .iter()
+        .filter(|id| !new_ids.contains(id))
+        .map(|&id| RecordChange::RemoveUpgrade { id })
+        .collect();
+    records.extend(
+        old_blocks
+            .iter()
+            .filter(|(first_part, _)| new_blocks.iter().all(|(f, _)| f != first_part))
+            .map(|&(first_part, _)| RecordChange::RemoveSlots { first_part }),
+    );
+    let start = after.offsets.upgrades.0;
+    records.extend(
+        new_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| !old_ids.contains(id))
+            .map(|(i, _)| {
+                let offset = start + i * UPGRADE_SIZE;
+                RecordChange::InsertUpgrade {
+                    record: after.bytes[offset..offset + UPGRADE_SIZE].to_vec(),
+                }
+            }),
+    );
+    records.extend(
+        new_blocks
+            .iter()
+            .filter(|(first_part, _)| old_blocks.iter().all(|(f, _)| f != first_part))
+            .map(|&(_, offset)| RecordChange::InsertSlots {
+                block: after.bytes[offset..offset + GEM_BLOCK_SIZE].to_vec(),
+            }),
+    );
+    records
+}
+
+//Absolute offset the relative offsets of a region are added to
+fn region_start(save: &SaveData, region: Region) -> isize {
+    let offsets = &save.file.offsets;
+    (match region {
+        Region::Header => 0,
+        Region::Upgrades => offsets.upgrades.0,
+        Region::EquippedGems => offsets.equipped_gems.0,
+        Region::Inventory => offsets.inventory.0,
+        Region::KeyInventory => offsets.key_inventory.0,
+        Region::Appearance => offsets.appearance.0,
+        Region::Storage => offsets.storage.0,
+        Region::Flags => offsets.username + USERNAME_TO_AOB,
+        Region::Position => save.position.coordinates.values_offset(),
+        Region::Other => offsets.username,
+    }) as isize
+}
+
+fn region_len(save: &SaveData, region: Region) -> usize {
+    let offsets = &save.file.offsets;
+    match region {
+        Region::Header => START_TO_UPGRADE,
+        Region::Upgrades => offsets.upgrades.1 + 1 - offsets.upgrades.0,
+        Region::EquippedGems => match offsets.equipped_gems {
+            (0, 0) => 0,
+            (start, end) => end + 1 - start,
+        },
+        //The key inventory starts inside the last slot of the inventory
+        Region::Inventory => offsets.key_inventory.0 - offsets.inventory.0,
+        Region::KeyInventory => offsets.key_inventory.1 + SLOT_SIZE - offsets.key_inventory.0,
+        Region::Appearance => APPEARANCE_BYTES_AMOUNT,
+        Region::Storage => offsets.storage.1 - offsets.storage.0,
+        Region::Flags => offsets
+            .lced_offset
+            .saturating_sub(offsets.username + USERNAME_TO_AOB),
+        Region::Position => 12,
+        Region::Other => 0,
+    }
+}
+
+fn region_bytes(save: &SaveData, region: Region, len: usize) -> &[u8] {
+    let start = region_start(save, region) as usize;
+    let end = (start + len).min(save.file.bytes.len());
+    &save.file.bytes[start.min(end)..end]
+}
+
+//Regions made of fixed size records, every record that changed is stored whole
+fn diff_records(
+    before: &SaveData,
+    after: &SaveData,
+    region: Region,
+    size: usize,
+    describe: fn(&[u8]) -> String,
+    changes: &mut Vec<RegionChange>,
+) {
+    //The upgrades grow with the save, compare up to the longest of both
+    let len = region_len(before, region).max(region_len(after, region));
+    let old = region_bytes(before, region, len);
+    let new = region_bytes(after, region, len);
+
+    for (i, (b, a)) in old.chunks(size).zip(new.chunks(size)).enumerate() {
+        if b != a {
+            changes.push(RegionChange {
+                region,
+                offset: (i * size) as isize,
+                block: None,
+                before: b.to_vec(),
+                after: a.to_vec(),
+                description: format!("{} {}: {} -> {}", region, i, describe(b), describe(a)),
+            });
+        }
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn describe_slot(slot: &[u8]) -> String {
+    if slot.len() < SLOT_SIZE || slot[4..8] == [0, 0, 0, 0] {
+        return "empty".to_string();
+    }
+    format!("{:#010x} x{}", read_u32(slot, 8), read_u32(slot, 12))
+}
+
+fn describe_upgrade(upgrade: &[u8]) -> String {
+    if upgrade.len() < UPGRADE_SIZE {
+        return "none".to_string();
+    }
+    let effects: Vec<String> = (16..UPGRADE_SIZE)
+        .step_by(4)
+        .map(|i| read_u32(upgrade, i).to_string())
+        .collect();
+    format!("{:#010x} [{}]", read_u32(upgrade, 0), effects.join(", "))
+}
+
+fn find_gem_block(save: &SaveData, first_part: u32) -> Option<usize> {
+    let len = region_len(save, Region::EquippedGems);
+    if len < GEM_BLOCK_SIZE {
+        return None;
+    }
+    let start = save.file.offsets.equipped_gems.0;
+    let key = first_part.to_le_bytes();
+    (start..=start + len - GEM_BLOCK_SIZE).find(|&i| save.file.bytes[i..i + 4] == key)
+}
+
+fn gem_blocks(save: &SaveData) -> BTreeSet<u32> {
+    save.inventory
+        .articles
+        .values()
+        .chain(save.storage.articles.values())
+        .flatten()
+        .filter(|a| a.slots.is_some())
+        .map(|a| a.first_part)
+        .collect()
+}
+
+//The gem blocks are not at fixed distances, they are paired by the article they belong to.
+//Blocks of articles that are only in one of the saves are records
+fn diff_gem_blocks(before: &SaveData, after: &SaveData, changes: &mut Vec<RegionChange>) {
+    for first_part in gem_blocks(before).intersection(&gem_blocks(after)) {
+        let (b, a) = match (
+            find_gem_block(before, *first_part),
+            find_gem_block(after, *first_part),
+        ) {
+            (Some(b), Some(a)) => (b, a),
+            _ => continue,
+        };
+        let old = &before.file.bytes[b..b + GEM_BLOCK_SIZE];
+        let new = &after.file.bytes[a..a + GEM_BLOCK_SIZE];
+        for change in diff_bytes(old, new) {
+            changes.push(RegionChange {
+                region: Region::EquippedGems,
+                offset: change.offset as isize,
+                block: Some(*first_part),
+                description: format!("slots of {:#010x}", first_part),
+                before: change.before,
+                after: change.after,
+            });
+        }
+    }
+}
+
+fn diff_region(
+    before: &SaveData,
+    after: &SaveData,
+    region: Region,
+    changes: &mut Vec<RegionChange>,
+) {
+    let len = region_len(before, region).min(region_len(after, region));
+    let old = region_bytes(before, region, len);
+    let new = region_bytes(after, region, len);
+    let len = old.len().min(new.len());
+
+    for change in diff_bytes(&old[..len], &new[..len]) {
+        let description = match region {
+            Region::Position => format!(
+                "{:?} -> {:?}",
+                before.position.coordinates.values(&before.file),
+                after.position.coordinates.values(&after.file)
+            ),
+            _ => format!("{} {:#x}", region, change.offset),
+        };
+        changes.push(RegionChange {
+            region,
+            offset: change.offset as isize,
+            block: None,
+            before: change.before,
+            after: change.after,
+            description,
+        });
+    }
+}
+
+//Bytes covered by a region or a stat, they are left out of the "other" changes
+fn covered(save: &SaveData, gems: bool) -> Vec<bool> {
+    let mut covered = vec![false; save.file.bytes.len()];
+    let mut cover = |start: usize, len: usize| {
+        let end = (start + len).min(covered.len());
+        for c in &mut covered[start.min(end)..end] {
+            *c = true;
+        }
+    };
+
+    for region in [
+        Region::Header,
+        Region::Upgrades,
+        Region::EquippedGems,
+        Region::Inventory,
+        Region::KeyInventory,
+        Region::Appearance,
+        Region::Storage,
+        Region::Flags,
+        Region::Position,
+    ] {
+        if region == Region::EquippedGems && !gems {
+            continue;
+        }
+        cover(
+            region_start(save, region) as usize,
+            region_len(save, region),
+        );
+    }
+    for stat in &save.stats {
+        let start = save.file.offsets.username as isize + stat.rel_offset;
+        cover(start as usize, 4 * (stat.times - 1) + stat.length);
+    }
+    covered
+}
+
+fn diff_other(before: &SaveData, after: &SaveData, gems: bool, changes: &mut Vec<RegionChange>) {
+    let (old, new) = (&before.file.bytes, &after.file.bytes);
+    let (old_covered, new_covered) = (covered(before, gems), covered(after, gems));
+    let old_username = before.file.offsets.username as isize;
+    let new_username = after.file.offsets.username as isize;
+
+    //Offsets relative to the username that are inside both files
+    let first = -old_username.min(new_username);
+    let last = (old.len() as isize - old_username).min(new.len() as isize - new_username);
+
+    let mut ranges: Vec<(isize, isize)> = Vec::new();
+    for rel in first..last {
+        let (b, a) = ((old_username + rel) as usize, (new_username + rel) as usize);
+        if old_covered[b] || new_covered[a] || old[b] == new[a] {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(range) if rel - range.1 <= MERGE_GAP as isize => range.1 = rel + 1,
+            _ => ranges.push((rel, rel + 1)),
+        }
+    }
+
+    for (start, end) in ranges {
+        let b = (old_username + start) as usize..(old_username + end) as usize;
+        let a = (new_username + start) as usize..(new_username + end) as usize;
+        changes.push(RegionChange {
+            region: Region::Other,
+            offset: start,
+            block: None,
+            before: old[b].to_vec(),
+            after: new[a].to_vec(),
+            description: format!("username {:+}", start),
+        });
+    }
+}
+
+/// Writes the diff between the saves at `before` and `after` to `path`.
+pub fn export(before: &SaveData, after: &SaveData, path: &str) -> Result<SaveDiff, Error> {
+    let patch = diff(before, after)?;
+    fs::write(path, patch.to_json()?)?;
+    Ok(patch)
+}
+
+/// Reads the patch at `path` and applies it to `save`, see [`SaveDiff::apply`].
+pub fn import(save: &mut SaveData, path: &str, force: bool) -> Result<SaveDiff, Error> {
+    let patch = SaveDiff::from_json(&fs::read_to_string(path)?)?;
+    patch.apply(save, force)?;
+    Ok(patch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        enums::{ArticleType, Location, SlotShape, UpgradeType},
+        utils::test_utils::build_save_data,
+    };
+
+    #[test]
+    fn test_diff_same_save() {
+        let save = build_save_data("testsave0");
+        assert!(diff(&save, &save.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_regions() {
+        let before = build_save_data("testsave0");
+        let mut after = before.clone();
+        after.stats[5].edit(60, &mut after.file);
+        after.file.set_playtime([1, 2, 3, 4]);
+        after.file.set_flag(0x10, 0xAA);
+        after
+            .inventory
+            .add_item(&mut after.file, 1000, 3, false)
+            .unwrap();
+        after
+            .position
+            .teleport(&mut after.file, 1.0, 2.0, 3.0, [0x15, 0x01]);
+
+        let patch = diff(&before, &after).unwrap();
+        assert_eq!(
+            patch.stats,
+            vec![StatChange {
+                name: "Vitality".to_string(),
+                before: before.stats[5].value,
+                after: 60,
+            }]
+        );
+        let regions: Vec<Region> = patch.changes.iter().map(|c| c.region).collect();
+        assert!(regions.contains(&Region::Inventory));
+        assert!(regions.contains(&Region::Header));
+        assert!(regions.contains(&Region::Position));
+        let flag = patch
+            .changes
+            .iter()
+            .find(|c| c.region == Region::Flags)
+            .unwrap();
+        assert_eq!((flag.offset, flag.after.clone()), (0x10, vec![0xAA]));
+
+        //Applying the patch to the original save gives the edited one
+        let mut patched = build_save_data("testsave0");
+        patch.apply(&mut patched, false).unwrap();
+        assert_eq!(patched.file.bytes, after.file.bytes);
+        assert_eq!(patched.stats[5].value, 60);
+
+        //Once applied the before side doesn't match anymore
+        let result = patch.apply(&mut patched, false);
+        assert!(result.is_err());
+        assert!(patch.apply(&mut patched, true).is_ok());
+    }
+
+    #[test]
+    fn test_apply_to_other_save() {
+        //The regions of testsave3 are at different offsets than in testsave0
+        let before = build_save_data("testsave0");
+        let mut after = before.clone();
+        after.file.set_flag(0x20, 0x55);
+        after.stats[6].edit(40, &mut after.file);
+        let patch =
+            SaveDiff::from_json(&diff(&before, &after).unwrap().to_json().unwrap()).unwrap();
+
+        let mut other = build_save_data("testsave3");
+        let flag = other.file.get_flag(0x20);
+        let mut forced = patch.clone();
+        forced.changes[0].before = vec![flag];
+        forced.stats[0].before = other.stats[6].value;
+        forced.apply(&mut other, false).unwrap();
+        assert_eq!(other.file.get_flag(0x20), 0x55);
+        assert_eq!(other.stats[6].value, 40);
+    }
+
+    #[test]
+    fn test_diff_gem_blocks() {
+        let before = build_save_data("testsave9");
+        let mut after = before.clone();
+        let article = after
+            .inventory
+            .articles
+            .get_mut(&ArticleType::RightHand)
+            .and_then(|weapons| weapons.iter_mut().find(|w| w.slots.is_some()))
+            .unwrap();
+        let shape = article.slots.as_ref().unwrap()[1].shape;
+        let new_shape = match shape {
+            SlotShape::Radial => SlotShape::Circle,
+            _ => SlotShape::Radial,
+        };
+        article
+            .change_slot_shape(&mut after.file, 1, new_shape)
+            .unwrap();
+        let first_part = article.first_part;
+
+        let patch = diff(&before, &after).unwrap();
+        assert_eq!(patch.changes.len(), 1);
+        assert_eq!(patch.changes[0].region, Region::EquippedGems);
+        assert_eq!(patch.changes[0].block, Some(first_part));
+        assert_eq!(patch.changes[0].offset, 28);
+    }
+
+    #[test]
+    fn test_apply_growing_edit() {
+        let before = build_save_data("testsave0");
+        let mut after = before.clone();
+        let no_effect = 0xFFFFFFFF;
+        let moon = after
+            .create_upgrade(
+                Location::Inventory,
+                UpgradeType::Rune,
+                "Oath",
+                [
+                    1100000, no_effect, no_effect, no_effect, no_effect, no_effect,
+                ],
+                0x80000000,
+            )
+            .unwrap();
+
+        let patch =
+            SaveDiff::from_json(&diff(&before, &after).unwrap().to_json().unwrap()).unwrap();
+        assert!(matches!(
+            &patch.records[..],
+            [RecordChange::InsertUpgrade { record }] if read_u32(record, 0) == moon.id
+        ));
+        let mut patched = build_save_data("testsave0");
+        patch.apply(&mut patched, false).unwrap();
+        assert_eq!(patched.file.bytes, after.file.bytes);
+
+        //The upgrade is already in the save, even with force
+        let result = patch.apply(&mut patched, true);
+        assert_eq!(result.unwrap_err().kind(), "PatchConflict");
+
+        //And the way back removes it
+        let patch = diff(&after, &before).unwrap();
+        assert_eq!(
+            patch.records,
+            vec![RecordChange::RemoveUpgrade { id: moon.id }]
+        );
+        patch.apply(&mut patched, false).unwrap();
+        assert_eq!(patched.file.bytes, before.file.bytes);
+    }
+
+    #[test]
+    fn test_apply_slots_blocks() {
+        let before = build_save_data("testsave9");
+        let mut after = before.clone();
+        let axe = after
+            .add_weapon(Location::Inventory, 5000000, None, 7, &[SlotShape::Radial])
+            .unwrap();
+
+        let patch = diff(&before, &after).unwrap();
+        assert!(matches!(
+            &patch.records[..],
+            [RecordChange::InsertSlots { block }] if read_u32(block, 0) == axe.first_part
+        ));
+        let mut patched = build_save_data("testsave9");
+        patch.apply(&mut patched, false).unwrap();
+        assert_eq!(patched.file.bytes, after.file.bytes);
+
+        let patch = diff(&after, &before).unwrap();
+        assert_eq!(
+            patch.records,
+            vec![RecordChange::RemoveSlots {
+                first_part: axe.first_part
+            }]
+        );
+        patch.apply(&mut patched, false).unwrap();
+        assert_eq!(patched.file.bytes, before.file.bytes);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let save = build_save_data("testsave0");
+        let mut patch = diff(&save, &save).unwrap();
+        patch.version = PATCH_VERSION + 1;
+        let result = SaveDiff::from_json(&patch.to_json().unwrap());
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "The patch has version 3, only version 2 is supported."
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
+//! Character coordinates and the map it is loaded in.
 use super::enums::Error;
 use super::file::FileData;
 use serde::{Deserialize, Serialize};
@@ -18,13 +19,32 @@ pub struct Pos {
 }
 
 impl Pos {
+    /// Reads the loaded map and the coordinates of the character.
     pub fn new(file: &FileData) -> Result<Pos, Error> {
         let bytes = &file.bytes;
         Ok(Pos {
-            coordinates: Coordinates::new(file).unwrap(),
+            coordinates: Coordinates::new(file)?,
             loaded_map: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
         })
     }
+
+    ///[area, block] of the loaded map, see [`Pos::teleport`]
+    pub fn map_id(&self) -> [u8; 2] {
+        let le_map = self.loaded_map.to_le_bytes();
+        [le_map[3], le_map[2]]
+    }
+
+    ///map_id is [area, block], the same pair used by the lantern list in the frontend
+    pub fn teleport(&mut self, file: &mut FileData, x: f32, y: f32, z: f32, map_id: [u8; 2]) {
+        let le_map = [0x00, 0x00, map_id[1], map_id[0]];
+
+        for (i, j) in (0x04..0x08).enumerate() {
+            file.bytes[j] = le_map[i];
+        }
+        self.loaded_map = u32::from_le_bytes(le_map);
+
+        self.coordinates.edit(file, x, y, z);
+    }
 }
 
 impl Coordinates {
@@ -32,7 +52,8 @@ impl Coordinates {
         let bytes = &file.bytes;
         let lced_offset = file.offsets.lced_offset;
 
-        for i in lced_offset..(bytes.len() - 1) {
+        //The coordinates are the 12 bytes after the pattern
+        for i in lced_offset..bytes.len().saturating_sub(23) {
             if [
                 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
             ] == bytes[i..=i + 11]
@@ -53,10 +74,28 @@ impl Coordinates {
                 });
             }
         }
-        return Err(Error::CustomError("Coordinates could not be found"));
+        Err(Error::OffsetNotFound {
+            region: "coordinates",
+        })
+    }
+
+    ///Offset of the first byte of x, the coordinates are 3 f32 after it
+    pub fn values_offset(&self) -> usize {
+        self.offset + 12
+    }
+
+    ///Exact x, y and z, the strings are rounded to 3 decimals
+    pub fn values(&self, file: &FileData) -> [f32; 3] {
+        let mut values = [0.0; 3];
+        for (i, value) in values.iter_mut().enumerate() {
+            let start = self.values_offset() + 4 * i;
+            let bytes = &file.bytes[start..start + 4];
+            *value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        values
     }
 
-    pub fn edit(&self, file: &mut FileData, x: f32, y: f32, z: f32) {
+    pub fn edit(&mut self, file: &mut FileData, x: f32, y: f32, z: f32) {
         let bytes = &mut file.bytes;
         let coords = [
             f32::to_le_bytes(x),
@@ -67,5 +106,8 @@ impl Coordinates {
         for i in 0..3 {
             bytes[self.offset + 12 + 4 * i..=self.offset + 15 + 4 * i].copy_from_slice(&coords[i]);
         }
+        self.x = format!("{:.3}", x);
+        self.y = format!("{:.3}", y);
+        self.z = format!("{:.3}", z);
     }
 }
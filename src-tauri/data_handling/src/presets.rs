@@ -0,0 +1,114 @@
+//! Named attribute presets, such as "Arcane 50 / Bloodtinge SL120", stored as a JSON list.
+//!
+//! The editor keeps them in a [`PRESETS_FILE`] in its config folder so every save can use them.
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use super::{enums::Error, file::write_atomic, stats::Attributes};
+
+/// Name of the file the editor keeps the presets in.
+pub const PRESETS_FILE: &str = "presets.json";
+
+/// Attributes to give a character. With a `level` the character is respecced at that level,
+/// its attributes are raised towards these ones, see [`crate::stats::respec`]. Without one
+/// they are applied as they are.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub attributes: Attributes,
+    pub level: Option<u32>,
+}
+
+/// Every preset stored at `path`, none if the file doesn't exist yet.
+pub fn load(path: &Path) -> Result<Vec<Preset>, Error> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// The preset called `name`.
+pub fn find(path: &Path, name: &str) -> Result<Preset, Error> {
+    load(path)?
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| Error::PresetNotFound {
+            name: name.to_string(),
+        })
+}
+
+/// Stores `preset`, replacing the one with the same name. Returns every preset.
+pub fn save(path: &Path, preset: Preset) -> Result<Vec<Preset>, Error> {
+    let mut presets = load(path)?;
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(stored) => *stored = preset,
+        None => presets.push(preset),
+    }
+    write(path, &presets)?;
+    Ok(presets)
+}
+
+/// Removes the preset called `name`. Returns the remaining presets.
+pub fn delete(path: &Path, name: &str) -> Result<Vec<Preset>, Error> {
+    let mut presets = load(path)?;
+    let count = presets.len();
+    presets.retain(|p| p.name != name);
+    if presets.len() == count {
+        return Err(Error::PresetNotFound {
+            name: name.to_string(),
+        });
+    }
+    write(path, &presets)?;
+    Ok(presets)
+}
+
+fn write(path: &Path, presets: &[Preset]) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    //Written next to the file and renamed over it so a failed write keeps the old presets
+    write_atomic(
+        path,
+        serde_json::to_string_pretty(presets)?.as_bytes(),
+        |_| Ok(()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_save_and_delete() {
+        let dir = std::env::temp_dir().join("bbse_presets");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join(PRESETS_FILE);
+        assert!(load(&path).unwrap().is_empty());
+
+        let mut preset = Preset {
+            name: "Arcane 50 / Bloodtinge SL120".to_string(),
+            attributes: Attributes::from([20, 15, 0, 0, 40, 50]),
+            level: Some(120),
+        };
+        save(&path, preset.clone()).unwrap();
+        let quality = Preset {
+            name: "Quality".to_string(),
+            attributes: Attributes::from([50, 25, 50, 50, 9, 9]),
+            level: None,
+        };
+        assert_eq!(save(&path, quality.clone()).unwrap().len(), 2);
+
+        //Saving with the same name replaces it
+        preset.level = Some(100);
+        assert_eq!(save(&path, preset.clone()).unwrap().len(), 2);
+        assert_eq!(find(&path, &preset.name).unwrap(), preset);
+        assert!(!dir.join(format!("{}.tmp", PRESETS_FILE)).exists());
+
+        assert_eq!(delete(&path, &preset.name).unwrap(), vec![quality]);
+        let result = find(&path, &preset.name);
+        assert_eq!(result.unwrap_err().kind(), "PresetNotFound");
+        let result = delete(&path, &preset.name);
+        assert_eq!(result.unwrap_err().kind(), "PresetNotFound");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
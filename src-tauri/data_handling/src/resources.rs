@@ -0,0 +1,17 @@
+//! Game data the parser relies on, embedded in the binary at compile time so the
+//! crate doesn't need a resources folder next to the executable.
+
+/// Every consumable, material, key item and chalice, grouped by category.
+pub const ITEMS: &str = include_str!("../resources/items.json");
+/// Every weapon, grouped by hand.
+pub const WEAPONS: &str = include_str!("../resources/weapons.json");
+/// Every armor piece.
+pub const ARMORS: &str = include_str!("../resources/armors.json");
+/// Gem and rune effects.
+pub const UPGRADES: &str = include_str!("../resources/upgrades.json");
+/// Offsets of the character stats, relative to the username.
+pub const OFFSETS: &str = include_str!("../resources/offsets.json");
+/// Bosses and the flags that mark them as dead.
+pub const BOSSES: &str = include_str!("../resources/bosses.json");
+/// Named event flags, such as quests, shortcuts and dialogues, with their states.
+pub const FLAGS: &str = include_str!("../resources/flags.json");
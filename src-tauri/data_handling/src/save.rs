@@ -0,0 +1,1604 @@
+//! The fully parsed character, entry point of the crate.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    article::Article,
+    backup::Retention,
+    bosses::{self, Boss},
+    defense::{self, DefenseReport},
+    enums::{ArticleType, EquipSlot, Error, Imprint, Location, Origin, SlotShape, UpgradeType},
+    equipment::Equipment,
+    file::FileData,
+    flags::{self, FlagStatus},
+    history::{diff, Checkpoint, Edit, History, Snapshot},
+    inventory::{self, Inventory},
+    legitimacy::{self, Report},
+    position::Pos,
+    presets::Preset,
+    resources,
+    slots::{parse_equipped_gems, Slot},
+    stats::{self, Attributes, DerivedStats, OriginInfo, Stat},
+    upgrades::{parse_upgrades, Upgrade, UpgradeReport, Violation},
+    username::Username,
+};
+
+/// A parsed character. `file` holds the raw bytes, the rest is the model built from them.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SaveData {
+    #[serde(skip_serializing)]
+    pub file: FileData,
+    pub stats: Vec<Stat>,
+    //None when the Origin stat doesn't hold one of the nine origins
+    pub origin: Option<OriginInfo>,
+    pub inventory: Inventory,
+    pub storage: Inventory,
+    pub equipment: Equipment,
+    pub username: Username,
+    pub bosses: Vec<Boss>,
+    pub playtime: u32,
+    pub position: Pos,
+    #[serde(skip)]
+    pub history: History,
+}
+
+impl SaveData {
+    /// Reads and parses the save at `save_path`.
+    pub fn build(save_path: &str) -> Result<SaveData, Error> {
+        Self::build_with_retention(save_path, &Retention::default())
+    }
+
+    /// Same as [`SaveData::build`], keeping the backups `retention` allows.
+    pub fn build_with_retention(save_path: &str, retention: &Retention) -> Result<SaveData, Error> {
+        Self::from_file(FileData::build_with_retention(save_path, retention)?)
+    }
+
+    /// Parses the character of a [`FileData`] that was already read.
+    pub fn from_file(mut file: FileData) -> Result<SaveData, Error> {
+        let stats = stats::new(&file)?;
+        let origin = stats::read_origin(&stats);
+        let bosses = bosses::new(&file)?;
+        let mut upgrades = parse_upgrades(&file)?;
+        let mut slots = parse_equipped_gems(&mut file, &mut upgrades);
+        let inventory = Inventory::build(
+            &file,
+            file.offsets.inventory,
+            file.offsets.key_inventory,
+            &mut upgrades,
+            &mut slots,
+        );
+        let storage = Inventory::build(
+            &file,
+            file.offsets.storage,
+            (0, 0),
+            &mut upgrades,
+            &mut slots,
+        ); // Its not possible to store key items
+        let equipment = Equipment::new(&file);
+        let username = Username::build(&file);
+        let playtime = file.get_playtime();
+        let position = Pos::new(&file)?;
+
+        Ok(SaveData {
+            file,
+            stats,
+            origin,
+            inventory,
+            storage,
+            equipment,
+            username,
+            bosses,
+            playtime,
+            position,
+            history: History::default(),
+        })
+    }
+
+    /// Writes the save atomically to `path`. The written file is parsed again and
+    /// `path` is only replaced if it gives back the same stats, inventories and upgrades.
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        self.file.save_verified(path, |file| {
+            let written = SaveData::from_file(file)?;
+            verify_round_trip(self, &written)
+        })
+    }
+
+    /// Captures the current state, pass it to [`SaveData::commit`] once the edit is done.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            bytes: self.file.bytes.clone(),
+            snapshot: Snapshot::take(self),
+        }
+    }
+
+    /// Records everything that changed since `checkpoint` as one entry of the history.
+    /// Returns false if the bytes are the same and nothing was recorded.
+    pub fn commit(&mut self, checkpoint: Checkpoint, label: &str) -> bool {
+        let changes = diff(&checkpoint.bytes, &self.file.bytes);
+        if changes.is_empty() {
+            return false;
+        }
+
+        self.history.push(Edit {
+            label: label.to_string(),
+            changes,
+            snapshot: checkpoint.snapshot,
+        });
+        true
+    }
+
+    /// Reverts the last recorded edit and returns its label.
+    pub fn undo(&mut self) -> Result<String, Error> {
+        let mut edit = self.history.undo.pop().ok_or(Error::NothingToUndo)?;
+        edit.apply(&mut self.file.bytes, true);
+        let before = std::mem::replace(&mut edit.snapshot, Snapshot::take(self));
+        before.restore(self);
+
+        let label = edit.label.clone();
+        self.history.redo.push(edit);
+        Ok(label)
+    }
+
+    /// Applies again the last undone edit and returns its label.
+    pub fn redo(&mut self) -> Result<String, Error> {
+        let mut edit = self.history.redo.pop().ok_or(Error::NothingToRedo)?;
+        edit.apply(&mut self.file.bytes, false);
+        let after = std::mem::replace(&mut edit.snapshot, Snapshot::take(self));
+        after.restore(self);
+
+        let label = edit.label.clone();
+        self.history.undo.push(edit);
+        Ok(label)
+    }
+
+    pub fn get_slot_mut(
+        &mut self,
+        location: Location,
+        article_type: ArticleType,
+        article_index: usize,
+        slot_index: usize,
+    ) -> Option<&mut Slot> {
+        let articles = match location {
+            Location::Inventory => &mut self.inventory.articles,
+            Location::Storage => &mut self.storage.articles,
+        };
+
+        if let Some(articles_of_type) = articles.get_mut(&article_type) {
+            if let Some(article) = articles_of_type.get_mut(article_index) {
+                if let Some(ref mut slots) = &mut article.slots {
+                    return slots.get_mut(slot_index);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn get_article_mut(
+        &mut self,
+        location: Location,
+        article_type: ArticleType,
+        article_index: usize,
+    ) -> Option<&mut Article> {
+        let articles = match location {
+            Location::Inventory => &mut self.inventory.articles,
+            Location::Storage => &mut self.storage.articles,
+        };
+
+        if let Some(articles_of_type) = articles.get_mut(&article_type) {
+            return articles_of_type.get_mut(article_index);
+        }
+        None
+    }
+
+    pub fn get_equipped_upgrade_mut(
+        &mut self,
+        location: Location,
+        article_type: ArticleType,
+        article_index: usize,
+        slot_index: usize,
+    ) -> Option<&mut Upgrade> {
+        if let Some(slot) = self.get_slot_mut(location, article_type, article_index, slot_index) {
+            if let Some(ref mut gem) = &mut slot.gem {
+                return Some(gem);
+            }
+        }
+        None
+    }
+
+    pub fn get_upgrade_mut(
+        &mut self,
+        location: Location,
+        upgrade_type: UpgradeType,
+        upgrade_index: usize,
+    ) -> Option<&mut Upgrade> {
+        let upgrades = match location {
+            Location::Inventory => &mut self.inventory.upgrades,
+            Location::Storage => &mut self.storage.upgrades,
+        };
+
+        if let Some(upgrades_of_type) = upgrades.get_mut(&upgrade_type) {
+            return upgrades_of_type.get_mut(upgrade_index);
+        }
+        None
+    }
+
+    pub fn move_upgrade(
+        &mut self,
+        upgrade_type: UpgradeType,
+        upgrade_index: usize,
+        from: Location,
+    ) -> Result<(), Error> {
+        match from {
+            Location::Inventory => {
+                let upgrade = self.inventory.remove_upgrade(
+                    &mut self.file,
+                    upgrade_type,
+                    upgrade_index,
+                    false,
+                )?;
+                self.storage.add_upgrade(&mut self.file, upgrade, true)
+            }
+            Location::Storage => {
+                let upgrade = self.storage.remove_upgrade(
+                    &mut self.file,
+                    upgrade_type,
+                    upgrade_index,
+                    true,
+                )?;
+                self.inventory.add_upgrade(&mut self.file, upgrade, false)
+            }
+        }
+    }
+
+    /// Destroys a gem or rune wherever it is: the inventory, the storage, the gem slots of
+    /// their articles and the upgrades table. The position is read again because removing
+    /// the record moves it.
+    pub fn destroy_upgrade(&mut self, id: u32) -> Result<Upgrade, Error> {
+        let (parsed, _) = parse_upgrades(&self.file)?
+            .remove(&id)
+            .ok_or(Error::UpgradeNotFound { id })?;
+        let in_inventory = self.inventory.clear_upgrade(&mut self.file, id, false)?;
+        let in_storage = self.storage.clear_upgrade(&mut self.file, id, true)?;
+        inventory::remove_upgrade_records(&mut self.file, &[id])?;
+        self.position = Pos::new(&self.file)?;
+        Ok(in_inventory.or(in_storage).unwrap_or(parsed))
+    }
+
+    /// Validates every gem and rune of the inventory and the storage, equipped ones included,
+    /// see [`Upgrade::validate`]. Only the upgrades that break a rule are reported.
+    pub fn validate_upgrades(&self) -> Result<Vec<UpgradeReport>, Error> {
+        let upgrades_json: Value = serde_json::from_str(resources::UPGRADES)?;
+        let mut reports = Vec::new();
+        for (location, inventory) in [
+            (Location::Inventory, &self.inventory),
+            (Location::Storage, &self.storage),
+        ] {
+            let loose = inventory.upgrades.values().flatten().map(|u| (u, false));
+            let equipped = inventory
+                .articles
+                .values()
+                .flatten()
+                .filter_map(|a| a.slots.as_ref())
+                .flatten()
+                .filter_map(|s| s.gem.as_ref())
+                .map(|u| (u, true));
+            for (upgrade, equipped) in loose.chain(equipped) {
+                let (violations, _) = upgrade.check(&self.file, &upgrades_json)?;
+                if !violations.is_empty() {
+                    reports.push(UpgradeReport {
+                        id: upgrade.id,
+                        name: upgrade.info.name.clone(),
+                        location,
+                        equipped,
+                        violations,
+                    });
+                }
+            }
+        }
+        Ok(reports)
+    }
+
+    /// Fixes a gem or rune, see [`Upgrade::legitimize`]. Every copy of it in the model is updated.
+    pub fn legitimize_upgrade(&mut self, id: u32) -> Result<Vec<Violation>, Error> {
+        let mut copies: Vec<&mut Upgrade> = Vec::new();
+        for inventory in [&mut self.inventory, &mut self.storage] {
+            copies.extend(
+                inventory
+                    .upgrades
+                    .values_mut()
+                    .flatten()
+                    .filter(|u| u.id == id),
+            );
+            copies.extend(
+                inventory
+                    .articles
+                    .values_mut()
+                    .flatten()
+                    .filter_map(|a| a.slots.as_mut())
+                    .flatten()
+                    .filter_map(|s| s.gem.as_mut())
+                    .filter(|u| u.id == id),
+            );
+        }
+        let (first, others) = copies
+            .split_first_mut()
+            .ok_or(Error::UpgradeNotFound { id })?;
+        let violations = first.legitimize(&mut self.file)?;
+        for other in others {
+            other.shape = first.shape.clone();
+            other.effects = first.effects.clone();
+            other.info = first.info.clone();
+        }
+        Ok(violations)
+    }
+
+    /// Removes the upgrades nothing refers to, see [`inventory::collect_orphaned_upgrades`].
+    /// The position is read again because removing the records moves it.
+    pub fn collect_orphaned_upgrades(&mut self) -> Result<Vec<u32>, Error> {
+        let orphans = inventory::collect_orphaned_upgrades(&mut self.file)?;
+        self.position = Pos::new(&self.file)?;
+        Ok(orphans)
+    }
+
+    /// Reports everything in the save the game can't produce, see [`legitimacy::validate`].
+    pub fn validate(&self) -> Result<Report, Error> {
+        legitimacy::validate(self)
+    }
+
+    /// Defenses and resistances of the equipped armor, see [`defense::defense`].
+    pub fn defense(&self) -> Result<DefenseReport, Error> {
+        defense::defense(self)
+    }
+
+    /// Equips an article of the inventory, see [`Equipment::equip`].
+    pub fn equip(
+        &mut self,
+        slot: EquipSlot,
+        article_type: ArticleType,
+        index: usize,
+    ) -> Result<Equipment, Error> {
+        self.equipment
+            .equip(&mut self.file, &self.inventory, slot, article_type, index)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Leaves a slot empty, see [`Equipment::unequip`].
+    pub fn unequip(&mut self, slot: EquipSlot) -> Result<Equipment, Error> {
+        self.equipment.unequip(&mut self.file, slot)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Puts a consumable on the belt, see [`Equipment::assign_quick_item`].
+    pub fn assign_quick_item(&mut self, slot: usize, index: usize) -> Result<Equipment, Error> {
+        self.equipment
+            .assign_quick_item(&mut self.file, &self.inventory, slot, index)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Empties a belt slot, see [`Equipment::clear_quick_item`].
+    pub fn clear_quick_item(&mut self, slot: usize) -> Result<Equipment, Error> {
+        self.equipment.clear_quick_item(&mut self.file, slot)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Puts a consumable in an item shortcut, see [`Equipment::assign_shortcut`].
+    pub fn assign_shortcut(&mut self, slot: usize, index: usize) -> Result<Equipment, Error> {
+        self.equipment
+            .assign_shortcut(&mut self.file, &self.inventory, slot, index)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Empties an item shortcut, see [`Equipment::clear_shortcut`].
+    pub fn clear_shortcut(&mut self, slot: usize) -> Result<Equipment, Error> {
+        self.equipment.clear_shortcut(&mut self.file, slot)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Stocks up on Blood Vials and Quicksilver Bullets and fills the belt with tools, see
+    /// [`Equipment::ready_belt`].
+    pub fn ready_belt(&mut self) -> Result<Equipment, Error> {
+        self.equipment
+            .ready_belt(&mut self.file, &mut self.inventory)?;
+        Ok(self.equipment.clone())
+    }
+
+    /// Kills or revives the boss called `name`, changing only the bits of its flags.
+    pub fn set_boss_dead(&mut self, name: &str, dead: bool) -> Result<&Boss, Error> {
+        let boss = self
+            .bosses
+            .iter_mut()
+            .find(|b| b.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::BossNotFound {
+                name: name.to_string(),
+            })?;
+        boss.set_dead(&mut self.file, dead)?;
+        Ok(boss)
+    }
+
+    /// State of every named flag, see [`flags::statuses`].
+    pub fn flags(&self) -> Result<Vec<FlagStatus>, Error> {
+        flags::statuses(&self.file, &flags::list()?)
+    }
+
+    /// Puts a named flag in one of its states, see [`flags::set`].
+    pub fn set_named_flag(&mut self, name: &str, state: &str) -> Result<FlagStatus, Error> {
+        flags::set(&mut self.file, &flags::list()?, name, state)
+    }
+
+    /// Level, base max Health and Stamina and level up cost of the current attributes.
+    pub fn derived_stats(&self) -> Result<DerivedStats, Error> {
+        Ok(Attributes::from_stats(&self.stats)?.derived())
+    }
+
+    /// Writes the attributes along with the Level, Health and Stamina they lead to, so the stats
+    /// stay consistent. Only the base max Health and Stamina are replaced, see
+    /// [`Stat::set_base_max`]. Nothing is written if an attribute is outside of 1 to 99 or below
+    /// the origin.
+    pub fn set_attributes(&mut self, attributes: &Attributes) -> Result<DerivedStats, Error> {
+        attributes.check()?;
+        if let Some(origin) = &self.origin {
+            attributes.check_floor(&origin.attributes)?;
+        }
+        let derived = attributes.derived();
+        let values = stats::ATTRIBUTES.iter().copied().zip(attributes.values());
+        for (name, value) in values.chain([("Level", derived.level)]) {
+            let index = self.stat_index(name)?;
+            self.stats[index].edit(value, &mut self.file);
+        }
+        for (name, base) in [("Health", derived.health), ("Stamina", derived.stamina)] {
+            let index = self.stat_index(name)?;
+            self.stats[index].set_base_max(base, &mut self.file);
+        }
+        Ok(derived)
+    }
+
+    /// Edits the stat called `name`, in any case. Attributes can't go below the origin and
+    /// the Origin stat only takes the value of one of the nine origins.
+    pub fn edit_stat(&mut self, name: &str, value: u32) -> Result<Stat, Error> {
+        let origin = self.origin;
+        let index = self.stat_index(name)?;
+        let stat = &mut self.stats[index];
+        if let Some(index) = stats::ATTRIBUTES.iter().position(|a| *a == stat.name) {
+            if let Some(origin) = origin {
+                let min = origin.attributes.values()[index];
+                if value < min {
+                    return Err(Error::AttributeBelowOrigin {
+                        name: stat.name.clone(),
+                        value,
+                        min,
+                    });
+                }
+            }
+        }
+        let is_origin = stat.name == "Origin";
+        if is_origin {
+            Origin::try_from(value)?;
+        }
+        stat.edit(value, &mut self.file);
+        let stat = stat.clone();
+        if is_origin {
+            self.origin = stats::read_origin(&self.stats);
+        }
+        Ok(stat)
+    }
+
+    /// Changes the origin of the character. The attributes below the ones of the new origin
+    /// are raised to them, the Level, Health and Stamina follow.
+    pub fn change_origin(&mut self, origin: Origin) -> Result<DerivedStats, Error> {
+        let info = OriginInfo::from(origin);
+        let mut values = Attributes::from_stats(&self.stats)?.values();
+        for (value, min) in values.iter_mut().zip(info.attributes.values()) {
+            *value = (*value).max(min);
+        }
+        let attributes = Attributes::from(values);
+        attributes.check()?;
+
+        let index = self.stat_index("Origin")?;
+        self.stats[index].edit(origin.into(), &mut self.file);
+        self.origin = Some(info);
+        self.set_attributes(&attributes)
+    }
+
+    /// Starting attributes of the origin of the character.
+    pub fn origin_attributes(&self) -> Result<Attributes, Error> {
+        match &self.origin {
+            Some(origin) => Ok(origin.attributes),
+            None => Err(Error::InvalidOrigin {
+                value: self.stat("Origin")?.value,
+            }),
+        }
+    }
+
+    /// The stat called `name`, in any case.
+    pub fn stat(&self, name: &str) -> Result<&Stat, Error> {
+        Ok(&self.stats[self.stat_index(name)?])
+    }
+
+    fn stat_index(&self, name: &str) -> Result<usize, Error> {
+        self.stats
+            .iter()
+            .position(|s| s.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::StatNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Redistributes the attribute points at `level` with the attributes of the origin as the
+    /// floor, see [`stats::respec`]. Level, Health and Stamina follow.
+    pub fn respec(&mut self, goals: &Attributes, level: u32) -> Result<DerivedStats, Error> {
+        let attributes = stats::respec(&self.origin_attributes()?, goals, level)?;
+        self.set_attributes(&attributes)
+    }
+
+    /// Applies a preset, with a respec if it has a level. Nothing is written if its
+    /// attributes are lower than the ones of the origin.
+    pub fn apply_preset(&mut self, preset: &Preset) -> Result<DerivedStats, Error> {
+        match preset.level {
+            Some(level) => self.respec(&preset.attributes, level),
+            None => {
+                preset.attributes.check_floor(&self.origin_attributes()?)?;
+                self.set_attributes(&preset.attributes)
+            }
+        }
+    }
+
+    /// Moves `quantity` of an article from `from` to the other location,
+    /// see [`Inventory::move_article`]. An equipped article leaving the inventory whole is
+    /// unequipped first.
+    pub fn move_article(
+        &mut self,
+        from: Location,
+        article_type: ArticleType,
+        article_index: usize,
+        quantity: u32,
+    ) -> Result<Article, Error> {
+        let equipment = self.equipment.clone();
+        if from == Location::Inventory {
+            self.release(article_type, article_index, Some(quantity))?;
+        }
+        let result = match from {
+            Location::Inventory => self.inventory.move_article(
+                &mut self.storage,
+                &mut self.file,
+                article_type,
+                article_index,
+                quantity,
+                false,
+            ),
+            Location::Storage => self.storage.move_article(
+                &mut self.inventory,
+                &mut self.file,
+                article_type,
+                article_index,
+                quantity,
+                true,
+            ),
+        };
+        if result.is_err() {
+            self.restore_equipment(equipment);
+        }
+        result
+    }
+
+    //The equipment points at inventory entries, an article leaving the inventory is unequipped
+    //first. Moving part of a stack leaves the entry in place.
+    fn release(
+        &mut self,
+        article_type: ArticleType,
+        article_index: usize,
+        quantity: Option<u32>,
+    ) -> Result<(), Error> {
+        let article = match self
+            .inventory
+            .articles
+            .get(&article_type)
+            .and_then(|articles| articles.get(article_index))
+        {
+            Some(article) => article,
+            None => return Ok(()),
+        };
+        if quantity.map_or(false, |quantity| quantity != article.amount) {
+            return Ok(());
+        }
+        let equipment = self.equipment.clone();
+        let result = self.equipment.release(&mut self.file, article);
+        if result.is_err() {
+            self.restore_equipment(equipment);
+        }
+        result
+    }
+
+    //Puts the equipment back as it was before an edit that failed
+    fn restore_equipment(&mut self, equipment: Equipment) {
+        equipment.write(&mut self.file);
+        self.equipment = equipment;
+    }
+
+    /// Removes an article, see [`Inventory::remove_article`], unequipping it first. The position
+    /// is read again because freeing the slots block moves it.
+    pub fn remove_article(
+        &mut self,
+        location: Location,
+        article_type: ArticleType,
+        article_index: usize,
+    ) -> Result<Article, Error> {
+        let is_storage = location == Location::Storage;
+        let equipment = self.equipment.clone();
+        if !is_storage {
+            self.release(article_type, article_index, None)?;
+        }
+        let inventory = match location {
+            Location::Inventory => &mut self.inventory,
+            Location::Storage => &mut self.storage,
+        };
+        let result =
+            inventory.remove_article(&mut self.file, article_type, article_index, is_storage);
+        if result.is_err() {
+            self.restore_equipment(equipment);
+        }
+        self.position = Pos::new(&self.file)?;
+        result
+    }
+
+    /// Adds a weapon, see [`Inventory::add_weapon`]. The position is read again because
+    /// creating the slots block moves it.
+    pub fn add_weapon(
+        &mut self,
+        location: Location,
+        id: u32,
+        imprint: Option<Imprint>,
+        upgrade_level: u8,
+        shapes: &[SlotShape],
+    ) -> Result<Article, Error> {
+        let is_storage = location == Location::Storage;
+        let inventory = match location {
+            Location::Inventory => &mut self.inventory,
+            Location::Storage => &mut self.storage,
+        };
+        let result = inventory.add_weapon(
+            &mut self.file,
+            id,
+            imprint,
+            upgrade_level,
+            shapes,
+            is_storage,
+        );
+        self.position = Pos::new(&self.file)?;
+        result
+    }
+
+    /// Adds an armor, see [`Inventory::add_armor`]. The position is read again because
+    /// creating the slots block moves it.
+    pub fn add_armor(&mut self, location: Location, id: u32) -> Result<Article, Error> {
+        let is_storage = location == Location::Storage;
+        let inventory = match location {
+            Location::Inventory => &mut self.inventory,
+            Location::Storage => &mut self.storage,
+        };
+        let result = inventory.add_armor(&mut self.file, id, is_storage);
+        self.position = Pos::new(&self.file)?;
+        result
+    }
+
+    /// Creates a gem or rune, see [`Inventory::create_upgrade`]. The position is read again
+    /// because the new record moves it.
+    pub fn create_upgrade(
+        &mut self,
+        location: Location,
+        upgrade_type: UpgradeType,
+        shape: &str,
+        effects: [u32; 6],
+        source: u32,
+    ) -> Result<Upgrade, Error> {
+        let is_storage = location == Location::Storage;
+        let inventory = match location {
+            Location::Inventory => &mut self.inventory,
+            Location::Storage => &mut self.storage,
+        };
+        let result = inventory.create_upgrade(
+            &mut self.file,
+            upgrade_type,
+            shape,
+            effects,
+            source,
+            is_storage,
+        );
+        self.position = Pos::new(&self.file)?;
+        result
+    }
+}
+
+//Parts of the model compared after writing a save, sorted so the order of the vectors doesn't matter
+type ArticleSummary = (String, u8, u32, u32, u32, u32, Vec<([u8; 4], Option<u32>)>);
+type UpgradeSummary = (String, u8, u32, u32, String, Vec<u32>);
+
+fn summarize(inventory: &Inventory) -> (Vec<ArticleSummary>, Vec<UpgradeSummary>) {
+    let mut articles: Vec<ArticleSummary> = inventory
+        .articles
+        .values()
+        .flatten()
+        .map(|a| {
+            let slots = a
+                .slots
+                .iter()
+                .flatten()
+                .map(|s| (s.shape.into(), s.gem.as_ref().map(|g| g.id)))
+                .collect();
+            (
+                format!("{:?}", a.article_type),
+                a.number,
+                a.id,
+                a.first_part,
+                a.second_part,
+                a.amount,
+                slots,
+            )
+        })
+        .collect();
+    articles.sort();
+
+    let mut upgrades: Vec<UpgradeSummary> = inventory
+        .upgrades
+        .values()
+        .flatten()
+        .map(|u| {
+            (
+                format!("{:?}", u.upgrade_type),
+                u.number,
+                u.id,
+                u.source,
+                u.shape.clone(),
+                u.effects.iter().map(|e| e.0).collect(),
+            )
+        })
+        .collect();
+    upgrades.sort();
+
+    (articles, upgrades)
+}
+
+fn verify_round_trip(expected: &SaveData, written: &SaveData) -> Result<(), Error> {
+    let stats = |save: &SaveData| -> Vec<(String, u32)> {
+        save.stats
+            .iter()
+            .map(|s| (s.name.clone(), s.value))
+            .collect()
+    };
+    if stats(expected) != stats(written) {
+        return Err(Error::VerificationFailed { section: "stats" });
+    }
+
+    for (section, expected, written) in [
+        ("inventory", &expected.inventory, &written.inventory),
+        ("storage", &expected.storage, &written.storage),
+    ] {
+        let (expected_articles, expected_upgrades) = summarize(expected);
+        let (written_articles, written_upgrades) = summarize(written);
+        if expected_articles != written_articles {
+            return Err(Error::VerificationFailed { section });
+        }
+        if expected_upgrades != written_upgrades {
+            return Err(Error::VerificationFailed {
+                section: "upgrades",
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::{
+        constants::USERNAME_TO_ISZ_GLITCH,
+        enums::{Check, SlotShape},
+        utils::test_utils::{build_save_data, check_bytes},
+    };
+
+    #[test]
+    fn test_build() {
+        assert!(SaveData::build("saves/testsave0").is_ok());
+    }
+
+    #[test]
+    fn test_build_truncated() {
+        let save = SaveData::build("saves/testsave0").unwrap();
+        let bytes = save.file.bytes;
+        let offsets = save.file.offsets;
+        let path = std::env::temp_dir().join("bbse_truncated_save");
+        let path = path.to_str().unwrap();
+
+        let cut = |length: usize| -> Error {
+            std::fs::write(path, &bytes[..length]).unwrap();
+            match SaveData::build(path) {
+                Ok(_) => panic!("A save truncated to {:#x} bytes was loaded", length),
+                Err(error) => error,
+            }
+        };
+
+        //Too short to contain the appearance
+        let error = cut(0x10);
+        assert_eq!(error.to_string(), "Failed to find the appearance.");
+        let error = cut(offsets.appearance.0);
+        assert_eq!(error.to_string(), "Failed to find the appearance.");
+
+        let error = cut(offsets.storage.1 - 1);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "The save is truncated, the storage ends at {:#x} but the file is only {:#x} bytes long.",
+                offsets.storage.1,
+                offsets.storage.1 - 1
+            )
+        );
+        assert_eq!(error.kind(), "TruncatedFile");
+
+        //The flags region is checked up to the isz bytes, the bosses go further
+        let flags_end = offsets.username + USERNAME_TO_ISZ_GLITCH + 2;
+        let error = cut(flags_end + 1);
+        assert_eq!(error.kind(), "TruncatedFile");
+
+        //The coordinates are searched after the LCED bytes
+        let error = cut(offsets.lced_offset + 4);
+        assert_eq!(error.to_string(), "Failed to find the coordinates.");
+    }
+
+    #[test]
+    fn test_save_verified() {
+        let path = std::env::temp_dir().join("bbse_verified_save");
+        let path = path.to_str().unwrap();
+        let mut save = build_save_data("testsave9");
+
+        save.inventory
+            .unequip_gem(&mut save.file, ArticleType::RightHand, 0, 0, false)
+            .unwrap();
+        save.inventory
+            .equip_gem(&mut save.file, 0, ArticleType::RightHand, 0, 0, false)
+            .unwrap();
+        save.inventory
+            .add_item(&mut save.file, 1000, 3, false)
+            .unwrap();
+        save.storage
+            .add_item(&mut save.file, 1000, 3, true)
+            .unwrap();
+        save.stats[0].edit(1234, &mut save.file);
+        save.save(path).unwrap();
+
+        let written = SaveData::build(path).unwrap();
+        assert_eq!(written.file.bytes, save.file.bytes);
+        assert_eq!(written.stats, save.stats);
+
+        //A model that doesn't match its bytes is not written
+        let previous = std::fs::read(path).unwrap();
+        save.file.set_playtime([1, 2, 3, 4]);
+        save.stats[0].value = 1;
+        let result = save.save(path);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "The written save doesn't match the edited stats, the original file was left untouched."
+            );
+        }
+        assert_eq!(std::fs::read(path).unwrap(), previous);
+        assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+    }
+
+    #[test]
+    fn test_get_slot_mut() {
+        //Inventory
+        let mut save = SaveData::build("saves/testsave5").unwrap();
+        let articles = save.inventory.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article = articles_of_type.first().unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot1 = slots.first().unwrap();
+        let slot2 = save
+            .get_slot_mut(Location::Inventory, ArticleType::RightHand, 0, 0)
+            .unwrap();
+        assert_eq!(*slot1, *slot2);
+        assert_eq!(slot1.shape, SlotShape::Droplet);
+
+        slot2.shape = SlotShape::Triangle;
+
+        let articles = save.inventory.articles;
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article = articles_of_type.first().unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot1 = slots.first().unwrap();
+        assert_eq!(slot1.shape, SlotShape::Triangle);
+
+        //Storage
+        let mut save = SaveData::build("saves/testsave5").unwrap();
+        let articles = save.storage.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::Armor).unwrap();
+        let article = articles_of_type.first().unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot1 = slots.first().unwrap();
+        let slot2 = save
+            .get_slot_mut(Location::Storage, ArticleType::Armor, 0, 0)
+            .unwrap();
+        assert_eq!(*slot1, *slot2);
+        assert_eq!(slot1.shape, SlotShape::Closed);
+
+        slot2.shape = SlotShape::Waning;
+
+        let articles = save.storage.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::Armor).unwrap();
+        let article = articles_of_type.first().unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot1 = slots.first().unwrap();
+        assert_eq!(slot1.shape, SlotShape::Waning);
+
+        //Not found
+        assert!(save
+            .get_slot_mut(Location::Storage, ArticleType::Chalice, 0, 0)
+            .is_none());
+        assert!(save
+            .get_slot_mut(Location::Storage, ArticleType::Armor, usize::MAX, 0)
+            .is_none());
+        assert!(save
+            .get_slot_mut(Location::Storage, ArticleType::Armor, 0, usize::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_get_article_mut() {
+        //Inventory
+        let mut save = SaveData::build("saves/testsave5").unwrap();
+        let articles = save.inventory.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article1 = articles_of_type.first().unwrap();
+        let article2 = save
+            .get_article_mut(Location::Inventory, ArticleType::RightHand, 0)
+            .unwrap();
+        assert_eq!(*article1, *article2);
+        assert_eq!(article1.id, 28001000);
+
+        article2.id = 0;
+
+        let articles = save.inventory.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article1 = articles_of_type.first().unwrap();
+        assert_eq!(article1.id, 0);
+
+        //Storage
+        let mut save = SaveData::build("saves/testsave5").unwrap();
+        let articles = save.storage.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::Armor).unwrap();
+        let article1 = articles_of_type.first().unwrap();
+        let article2 = save
+            .get_article_mut(Location::Storage, ArticleType::Armor, 0)
+            .unwrap();
+        assert_eq!(*article1, *article2);
+        assert_eq!(article1.id, 351000);
+
+        article2.id = 0;
+
+        let articles = save.storage.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::Armor).unwrap();
+        let article1 = articles_of_type.first().unwrap();
+        assert_eq!(article1.id, 0);
+
+        //Not found
+        assert!(save
+            .get_article_mut(Location::Storage, ArticleType::Chalice, 0)
+            .is_none());
+        assert!(save
+            .get_article_mut(Location::Storage, ArticleType::Armor, usize::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_get_equipped_upgrade_mut() {
+        //Inventory
+        let mut save = SaveData::build("saves/testsave8").unwrap();
+        let articles = save.inventory.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article = articles_of_type.first().unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot = slots.first().unwrap();
+        let gem1 = slot.gem.as_ref().unwrap();
+        let gem2 = save
+            .get_equipped_upgrade_mut(Location::Inventory, ArticleType::RightHand, 0, 0)
+            .unwrap();
+        assert_eq!(*gem1, *gem2);
+        assert_eq!(gem1.id, 3229615259);
+
+        gem2.id = 0;
+
+        let articles = save.inventory.articles;
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article = articles_of_type.first().unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot = slots.first().unwrap();
+        let gem1 = slot.gem.as_ref().unwrap();
+        assert_eq!(gem1.id, 0);
+
+        //Storage
+        let mut save = SaveData::build("saves/testsave8").unwrap();
+        let articles = save.storage.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article = articles_of_type.get(17).unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot = slots.first().unwrap();
+        let gem1 = slot.gem.as_ref().unwrap();
+        let gem2 = save
+            .get_equipped_upgrade_mut(Location::Storage, ArticleType::RightHand, 17, 0)
+            .unwrap();
+        assert_eq!(*gem1, *gem2);
+        assert_eq!(gem1.id, 3229614569);
+
+        gem2.id = 0;
+
+        let articles = save.storage.articles.clone();
+        let articles_of_type = articles.get(&ArticleType::RightHand).unwrap();
+        let article = articles_of_type.get(17).unwrap();
+        let slots = &article.slots.as_ref().unwrap();
+        let slot = slots.first().unwrap();
+        let gem1 = slot.gem.as_ref().unwrap();
+        assert_eq!(gem1.id, 0);
+
+        //Not found
+        assert!(save
+            .get_equipped_upgrade_mut(Location::Storage, ArticleType::Chalice, 0, 0)
+            .is_none());
+        assert!(save
+            .get_equipped_upgrade_mut(Location::Storage, ArticleType::Armor, usize::MAX, 0)
+            .is_none());
+        assert!(save
+            .get_equipped_upgrade_mut(Location::Storage, ArticleType::Armor, 0, usize::MAX)
+            .is_none());
+    }
+
+    #[test]
+    fn test_get_upgrade_mut() {
+        //Inventory
+        let mut save = SaveData::build("saves/testsave5").unwrap();
+        let upgrades = save.inventory.upgrades.clone();
+        let upgrades_of_type = upgrades.get(&UpgradeType::Rune).unwrap();
+        let upgrade1 = upgrades_of_type.first().unwrap();
+        let upgrade2 = save
+            .get_upgrade_mut(Location::Inventory, UpgradeType::Rune, 0)
+            .unwrap();
+        assert_eq!(*upgrade1, *upgrade2);
+        assert_eq!(upgrade1.id, 3229614361);
+
+        upgrade2.id = 0;
+
+        let upgrades = save.inventory.upgrades.clone();
+        let upgrades_of_type = upgrades.get(&UpgradeType::Rune).unwrap();
+        let upgrade1 = upgrades_of_type.first().unwrap();
+        assert_eq!(upgrade1.id, 0);
+
+        //Storage
+        let mut save = SaveData::build("saves/testsave9").unwrap();
+        let upgrades = save.storage.upgrades.clone();
+        let upgrades_of_type = upgrades.get(&UpgradeType::Gem).unwrap();
+        let upgrade1 = upgrades_of_type.first().unwrap();
+        let upgrade2 = save
+            .get_upgrade_mut(Location::Storage, UpgradeType::Gem, 0)
+            .unwrap();
+        assert_eq!(*upgrade1, *upgrade2);
+        assert_eq!(upgrade1.id, 3229614193);
+
+        upgrade2.id = 0;
+
+        let upgrades = save.storage.upgrades.clone();
+        let upgrades_of_type = upgrades.get(&UpgradeType::Gem).unwrap();
+        let upgrade1 = upgrades_of_type.first().unwrap();
+        assert_eq!(upgrade1.id, 0);
+
+        //Not found
+        assert!(save
+            .get_upgrade_mut(Location::Storage, UpgradeType::Rune, 0)
+            .is_none());
+        assert!(save
+            .get_upgrade_mut(Location::Storage, UpgradeType::Gem, usize::MAX)
+            .is_none());
+    }
+
+    #[test]
+    #[ignore] //cargo test -- --include-ignored
+    fn test_save_data_get_muts_runtime() {
+        let mut save = SaveData::build("saves/testsave5").unwrap();
+
+        //Test get_slot_mut() runtime
+        let now = Instant::now();
+        save.get_slot_mut(Location::Inventory, ArticleType::RightHand, 0, 0)
+            .unwrap();
+        let elapsed = now.elapsed().as_micros();
+        assert!(elapsed < 10);
+
+        //Test get_article_mut() runtime
+        let now = Instant::now();
+        save.get_article_mut(Location::Inventory, ArticleType::RightHand, 0)
+            .unwrap();
+        let elapsed = now.elapsed().as_micros();
+        assert!(elapsed < 10);
+
+        //Test get_equipped_upgrade_mut() runtime
+        let now = Instant::now();
+        save.get_equipped_upgrade_mut(Location::Inventory, ArticleType::RightHand, 0, 3)
+            .unwrap();
+        let elapsed = now.elapsed().as_micros();
+        assert!(elapsed < 10);
+
+        //Test get_upgrade_mut() runtime
+        let now = Instant::now();
+        save.get_upgrade_mut(Location::Inventory, UpgradeType::Rune, 0)
+            .unwrap();
+        let elapsed = now.elapsed().as_micros();
+        assert!(elapsed < 10);
+    }
+
+    #[test]
+    fn test_move_upgrade() {
+        let mut save = build_save_data("testsave9");
+
+        //Test error cases
+        let result = save.move_upgrade(UpgradeType::Rune, 500, Location::Storage);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There are no upgrades of type Rune.");
+        }
+
+        let result = save.move_upgrade(UpgradeType::Gem, 500, Location::Storage);
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "There is no Gem at index 500.");
+        }
+
+        //The inventory has 2 gems
+        assert_eq!(
+            save.inventory
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            2
+        );
+        //The storage has 2 gems
+        assert_eq!(
+            save.storage
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            2
+        );
+        //Get the gem to be moved
+        let gem = save.inventory.upgrades.get_mut(&UpgradeType::Gem).unwrap()[0].clone();
+        //Slot of the inventory with the gem
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[
+                0x51, 0x40, 0x89, 0x13, 0x73, 0x00, 0x80, 0xc0, 0xf0, 0x49, 0x02, 0x80, 0x01, 0x00,
+                0x00, 0x00
+            ]
+        ));
+        //Last slot of the storage
+        assert!(check_bytes(
+            &save.file,
+            0x11524,
+            &[
+                0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+                0x00, 0x00
+            ]
+        ));
+
+        //Move the gem
+        save.move_upgrade(UpgradeType::Gem, 0, Location::Inventory)
+            .unwrap();
+
+        //The inventory now has 1 gem
+        assert_eq!(
+            save.inventory
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            1
+        );
+        //The storage now has 3 gems
+        assert_eq!(
+            save.storage
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            3
+        );
+        //Get the moved gem
+        let mut moved_gem = save
+            .storage
+            .upgrades
+            .get_mut(&UpgradeType::Gem)
+            .unwrap()
+            .last_mut()
+            .unwrap()
+            .clone();
+        assert_eq!(moved_gem.index, 2);
+        moved_gem.index = 0;
+        assert_eq!(gem, moved_gem);
+        //Now the inventory slot in which the gem was is empty
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[0x51, 0x40, 0x89, 0x13, 0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0]
+        ));
+        //And the last slot of the storage has the gem
+        assert!(check_bytes(
+            &save.file,
+            0x11524,
+            &[
+                0x69, 0x00, 0x00, 0x00, 0x73, 0x00, 0x80, 0xc0, 0xf0, 0x49, 0x02, 0x80, 0x01, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+                0x00, 0x00, 0x00, 0x00
+            ]
+        ));
+
+        //Try again but from the storage
+
+        //The inventory has 1 gems
+        assert_eq!(
+            save.inventory
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            1
+        );
+        //The storage has 3 gems
+        assert_eq!(
+            save.storage
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            3
+        );
+        //Get the gem to be moved
+        let gem = save.storage.upgrades.get_mut(&UpgradeType::Gem).unwrap()[0].clone();
+        //Slot of the storage with the gem
+        assert!(check_bytes(
+            &save.file,
+            0x11504,
+            &[
+                0x44, 0x40, 0x89, 0x13, 0x71, 0x00, 0x80, 0xc0, 0x48, 0xe8, 0x01, 0x80, 0x01, 0x00,
+                0x00, 0x00
+            ]
+        ));
+        //Empty slot of the inventory
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[0x51, 0x40, 0x89, 0x13, 0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0]
+        ));
+
+        //Move the gem
+        save.move_upgrade(UpgradeType::Gem, 0, Location::Storage)
+            .unwrap();
+
+        //The inventory now has 2 gem
+        assert_eq!(
+            save.inventory
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            2
+        );
+        //The storage now has 2 gems
+        assert_eq!(
+            save.storage
+                .upgrades
+                .get_mut(&UpgradeType::Gem)
+                .unwrap()
+                .len(),
+            2
+        );
+        //Get the moved gem
+        let mut moved_gem = save
+            .inventory
+            .upgrades
+            .get_mut(&UpgradeType::Gem)
+            .unwrap()
+            .last_mut()
+            .unwrap()
+            .clone();
+        assert_eq!(moved_gem.index, 1);
+        moved_gem.index = 0;
+        assert_eq!(moved_gem.number, 0x51);
+        moved_gem.number = gem.number;
+        assert_eq!(gem, moved_gem);
+        //Now the storage slot in which the gem was is empty
+        assert!(check_bytes(
+            &save.file,
+            0x11504,
+            &[0x44, 0x40, 0x89, 0x13, 0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 0]
+        ));
+        //The slot now has the gem
+        assert!(check_bytes(
+            &save.file,
+            0x8fe8,
+            &[
+                0x51, 0x40, 0x89, 0x13, 0x71, 0x00, 0x80, 0xc0, 0x48, 0xe8, 0x01, 0x80, 0x01, 0x00,
+                0x00, 0x00
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_set_attributes_keeps_bonuses() {
+        //testsave2 has 55 Health and 19 Stamina from runes on top of Vitality and Endurance 30
+        let mut save = build_save_data("testsave2");
+        let mut attributes = Attributes::from_stats(&save.stats).unwrap();
+        attributes.vitality = 40;
+        attributes.endurance = 35;
+        let derived = save.set_attributes(&attributes).unwrap();
+        let health = save.stat("Health").unwrap();
+        assert_eq!(
+            health.copies(&save.file),
+            vec![1155, derived.health + 55, derived.health]
+        );
+        assert_eq!(health.value, 1155);
+        let stamina = save.stat("Stamina").unwrap();
+        assert_eq!(
+            stamina.copies(&save.file),
+            vec![152, derived.stamina + 19, derived.stamina]
+        );
+
+        //The current value doesn't stay above the new max, 14 is the Vitality of its origin
+        attributes.vitality = 14;
+        let derived = save.set_attributes(&attributes).unwrap();
+        let health = save.stat("Health").unwrap();
+        assert_eq!(
+            health.copies(&save.file),
+            vec![derived.health + 55, derived.health + 55, derived.health]
+        );
+    }
+
+    #[test]
+    fn test_set_attributes() {
+        let mut save = build_save_data("testsave0");
+        let attributes = Attributes::from([40, 20, 25, 25, 9, 14]);
+        let derived = save.set_attributes(&attributes).unwrap();
+        assert_eq!(derived.level, 83);
+        assert_eq!(save.derived_stats().unwrap(), derived);
+
+        let value =
+            |save: &SaveData, name: &str| save.stats.iter().find(|s| s.name == name).unwrap().value;
+        assert_eq!(value(&save, "Vitality"), 40);
+        assert_eq!(value(&save, "Arcane"), 14);
+        assert_eq!(value(&save, "Level"), 83);
+        //Only the base max values are replaced
+        assert_eq!(
+            save.stat("Health").unwrap().copies(&save.file)[2],
+            derived.health
+        );
+        assert_eq!(
+            save.stat("Stamina").unwrap().copies(&save.file)[2],
+            derived.stamina
+        );
+        //The stats are consistent now
+        assert!(!save
+            .validate()
+            .unwrap()
+            .findings
+            .iter()
+            .any(|f| f.check == Check::LevelMismatch));
+
+        //Nothing is written when an attribute is out of range
+        let attributes = Attributes::from([40, 20, 25, 25, 9, 100]);
+        let result = save.set_attributes(&attributes);
+        assert_eq!(result.unwrap_err().kind(), "InvalidAttribute");
+        assert_eq!(value(&save, "Arcane"), 14);
+        assert_eq!(value(&save, "Level"), 83);
+    }
+
+    #[test]
+    fn test_apply_preset() {
+        //testsave2, a Lone Survivor
+        let mut save = build_save_data("testsave2");
+        assert_eq!(
+            save.origin_attributes().unwrap().values(),
+            [14, 11, 11, 10, 7, 7]
+        );
+        let mut preset = Preset {
+            name: "Arcane 50 / Bloodtinge SL120".to_string(),
+            attributes: Attributes::from([40, 25, 0, 0, 50, 50]),
+            level: Some(120),
+        };
+        let derived = save.apply_preset(&preset).unwrap();
+        assert_eq!(derived.level, 120);
+        //Not enough points for every goal, they get what they miss in proportion
+        let attributes = Attributes::from_stats(&save.stats).unwrap();
+        assert_eq!(attributes.values(), [37, 23, 11, 10, 45, 44]);
+        assert!(save.validate().unwrap().is_legit());
+
+        //Without a level the attributes can't go below the origin
+        preset.level = None;
+        let result = save.apply_preset(&preset);
+        assert_eq!(result.unwrap_err().kind(), "AttributeBelowOrigin");
+        assert_eq!(Attributes::from_stats(&save.stats).unwrap(), attributes);
+        preset.attributes = Attributes::from([40, 25, 11, 10, 50, 50]);
+        assert_eq!(save.apply_preset(&preset).unwrap().level, 136);
+    }
+
+    #[test]
+    fn test_origin() {
+        //testsave7, a fresh Milquetoast
+        let save = build_save_data("testsave7");
+        let origin = save.origin.unwrap();
+        assert_eq!(origin.origin, Origin::Milquetoast);
+        assert_eq!(origin.level, 10);
+        assert_eq!(
+            Attributes::from_stats(&save.stats).unwrap(),
+            origin.attributes
+        );
+
+        //testsave2, a Lone Survivor with Bloodtinge and Arcane at their starting 7
+        let mut save = build_save_data("testsave2");
+        assert_eq!(save.origin.unwrap().origin, Origin::LoneSurvivor);
+        let result = save.edit_stat("Vitality", 13);
+        assert_eq!(result.unwrap_err().kind(), "AttributeBelowOrigin");
+        assert_eq!(save.stat("Vitality").unwrap().value, 30);
+        assert_eq!(save.edit_stat("vitality", 14).unwrap().value, 14);
+        let result = save.edit_stat("Origin", 9);
+        assert_eq!(result.unwrap_err().kind(), "InvalidOrigin");
+        assert_eq!(save.edit_stat("Echoes", 0).unwrap().value, 0);
+
+        //Noble Scion starts with Bloodtinge 14 and Arcane 9, both are raised
+        let checkpoint = save.checkpoint();
+        let derived = save.change_origin(Origin::NobleScion).unwrap();
+        save.commit(checkpoint, "change_origin");
+        assert_eq!(save.origin.unwrap().origin, Origin::NobleScion);
+        assert_eq!(save.stat("Origin").unwrap().value, 6);
+        let attributes = Attributes::from_stats(&save.stats).unwrap();
+        assert_eq!(attributes.values(), [14, 30, 46, 30, 14, 9]);
+        assert_eq!(derived.level, 93);
+        assert!(save.validate().unwrap().is_legit());
+        let result = save.edit_stat("Bloodtinge", 13);
+        assert_eq!(result.unwrap_err().kind(), "AttributeBelowOrigin");
+
+        save.undo().unwrap();
+        assert_eq!(save.origin.unwrap().origin, Origin::LoneSurvivor);
+        assert_eq!(save.stat("Bloodtinge").unwrap().value, 7);
+
+        //Editing the Origin stat directly moves the floor too
+        save.edit_stat("Origin", 7).unwrap();
+        assert_eq!(save.origin.unwrap().origin, Origin::CruelFate);
+        assert_eq!(save.origin_attributes().unwrap().arcane, 14);
+        let report = save.validate().unwrap();
+        assert!(report
+            .findings
+            .iter()
+            .any(|f| f.check == Check::AttributeBelowOrigin && f.subject == "Arcane"));
+    }
+
+    #[test]
+    fn test_remove_equipped_article() {
+        let mut save = build_save_data("testsave2");
+        let weapon = save
+            .equipment
+            .article_in(EquipSlot::LeftHand1, &save.inventory)
+            .unwrap()
+            .clone();
+        let removed = save
+            .remove_article(Location::Inventory, weapon.article_type, weapon.index)
+            .unwrap();
+        assert_eq!(removed.first_part, weapon.first_part);
+        //The hand went back to the fists, the save agrees with the model
+        assert!(save
+            .equipment
+            .article_in(EquipSlot::LeftHand1, &save.inventory)
+            .is_none());
+        assert_ne!(
+            save.equipment.handle(EquipSlot::LeftHand1),
+            weapon.first_part
+        );
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        //Worn armor is swapped for the [CUT] one
+        let hat = save.inventory.articles[&ArticleType::Armor]
+            .iter()
+            .find(|a| a.info.item_name == "Hunter Hat")
+            .unwrap()
+            .clone();
+        save.equip(EquipSlot::Head, ArticleType::Armor, hat.index)
+            .unwrap();
+        save.remove_article(Location::Inventory, ArticleType::Armor, hat.index)
+            .unwrap();
+        let worn = save.equipment.article_in(EquipSlot::Head, &save.inventory);
+        assert_eq!(worn.unwrap().info.item_name, "[CUT] Head");
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        //A failed removal leaves the equipment as it was
+        let key = save.inventory.articles[&ArticleType::Key][0].clone();
+        let equipment = save.equipment.clone();
+        let result = save.remove_article(Location::Inventory, ArticleType::Key, key.index);
+        assert_eq!(result.unwrap_err().kind(), "KeyItem");
+        assert_eq!(save.equipment, equipment);
+        assert_eq!(Equipment::new(&save.file), equipment);
+    }
+
+    #[test]
+    fn test_move_equipped_article() {
+        let mut save = build_save_data("testsave9");
+        let consumables = &save.inventory.articles[&ArticleType::Consumable];
+        let antidote = consumables.iter().find(|a| a.id == 1100).unwrap().clone();
+        assert!(antidote.amount > 1);
+        assert!(save.equipment.is_equipped(&save.file, &antidote));
+        //The Antidotes of the storage are a full stack, they make room for the moved ones
+        let stored = save.storage.articles[&ArticleType::Consumable]
+            .iter()
+            .find(|a| a.id == 1100)
+            .unwrap()
+            .index;
+        save.remove_article(Location::Storage, ArticleType::Consumable, stored)
+            .unwrap();
+
+        //Part of the stack leaves the entry on the belt
+        save.move_article(
+            Location::Inventory,
+            ArticleType::Consumable,
+            antidote.index,
+            1,
+        )
+        .unwrap();
+        assert_eq!(save.equipment.quick_items[0], Some(0x2B));
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        //The rest of it empties the belt slot, the selection goes with it
+        save.move_article(
+            Location::Inventory,
+            ArticleType::Consumable,
+            antidote.index,
+            antidote.amount - 1,
+        )
+        .unwrap();
+        assert_eq!(save.equipment.quick_items[0], None);
+        assert_eq!(save.equipment.selected_quick_item, None);
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        //The bell leaves its shortcut
+        let bell = save.inventory.articles[&ArticleType::Consumable]
+            .iter()
+            .find(|a| a.id == 200)
+            .unwrap()
+            .clone();
+        save.move_article(
+            Location::Inventory,
+            ArticleType::Consumable,
+            bell.index,
+            bell.amount,
+        )
+        .unwrap();
+        assert_eq!(save.equipment.shortcuts[1], None);
+        assert_eq!(save.equipment.shortcut_handles[1], 0);
+        assert_eq!(Equipment::new(&save.file), save.equipment);
+
+        //The [CUT] chest worn has nothing to be swapped for, nothing moves
+        let chest = save
+            .equipment
+            .article_in(EquipSlot::Chest, &save.inventory)
+            .unwrap()
+            .clone();
+        assert_eq!(chest.info.item_name, "[CUT] Body");
+        let equipment = save.equipment.clone();
+        let result = save.move_article(Location::Inventory, ArticleType::Armor, chest.index, 1);
+        assert_eq!(result.unwrap_err().kind(), "NothingToUnequipWith");
+        assert_eq!(save.equipment, equipment);
+        assert_eq!(Equipment::new(&save.file), equipment);
+        assert!(save.inventory.articles[&ArticleType::Armor].contains(&chest));
+
+        assert_eq!(
+            Equipment::bullets(&save.inventory).unwrap().info.item_name,
+            "Quicksilver Bullets"
+        );
+    }
+}
@@ -0,0 +1,787 @@
+//! Human readable character sheets, a versioned JSON description of a character
+//! that can be exported from one save and applied to another.
+//!
+//! Articles and upgrades are listed in the order they appear in the save so two
+//! sheets of the same character can be compared with a plain text diff.
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs};
+
+use super::{
+    article::{Article, WeaponMods},
+    constants::NO_EFFECT,
+    enums::{ArticleType, Error, Imprint, SlotShape, TypeFamily, UpgradeType},
+    file::FileData,
+    inventory::Inventory,
+    position::Pos,
+    save::SaveData,
+    upgrades::Upgrade,
+};
+
+/// Version written by [`CharacterSheet::from_save`], sheets with another version are rejected.
+pub const SHEET_VERSION: u32 = 1;
+
+//Order of the articles and upgrades in a sheet
+const ARTICLE_TYPES: [ArticleType; 7] = [
+    ArticleType::Consumable,
+    ArticleType::Material,
+    ArticleType::Key,
+    ArticleType::Chalice,
+    ArticleType::RightHand,
+    ArticleType::LeftHand,
+    ArticleType::Armor,
+];
+const UPGRADE_TYPES: [UpgradeType; 2] = [UpgradeType::Gem, UpgradeType::Rune];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CharacterSheet {
+    pub version: u32,
+    pub username: String,
+    pub playtime: u32,
+    pub stats: BTreeMap<String, u32>,
+    pub position: SheetPosition,
+    pub inventory: SheetInventory,
+    pub storage: SheetInventory,
+    pub bosses: BTreeMap<String, bool>, //true if the boss is dead
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SheetPosition {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub map_id: [u8; 2], //[area, block]
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct SheetInventory {
+    pub articles: Vec<SheetArticle>,
+    pub upgrades: Vec<SheetUpgrade>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SheetArticle {
+    pub id: u32,
+    pub name: String,
+    pub article_type: ArticleType,
+    pub amount: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imprint: Option<Imprint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slots: Vec<SheetSlot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SheetSlot {
+    pub shape: SlotShape,
+    pub gem: Option<SheetUpgrade>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SheetUpgrade {
+    pub id: u32,
+    pub name: String,
+    pub upgrade_type: UpgradeType,
+    pub shape: String,
+    pub source: u32,
+    pub effects: Vec<(u32, String)>,
+}
+
+/// What [`CharacterSheet::apply`] changed and what it couldn't recreate in the target save.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ApplyReport {
+    pub applied: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl From<&Upgrade> for SheetUpgrade {
+    fn from(upgrade: &Upgrade) -> Self {
+        SheetUpgrade {
+            id: upgrade.id,
+            name: upgrade.info.name.clone(),
+            upgrade_type: upgrade.upgrade_type,
+            shape: upgrade.shape.clone(),
+            source: upgrade.source,
+            effects: upgrade.effects.clone(),
+        }
+    }
+}
+
+impl From<&Article> for SheetArticle {
+    fn from(article: &Article) -> Self {
+        let mods = match article.is_weapon() {
+            true => WeaponMods::try_from(article.second_part).ok(),
+            false => None,
+        };
+        SheetArticle {
+            id: article.id,
+            name: article.info.item_name.clone(),
+            article_type: article.article_type,
+            amount: article.amount,
+            upgrade_level: mods.as_ref().map(|m| m.upgrade_level),
+            imprint: mods.and_then(|m| m.imprint),
+            slots: article
+                .slots
+                .iter()
+                .flatten()
+                .map(|s| SheetSlot {
+                    shape: s.shape,
+                    gem: s.gem.as_ref().map(SheetUpgrade::from),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&Inventory> for SheetInventory {
+    fn from(inventory: &Inventory) -> Self {
+        let articles = ARTICLE_TYPES
+            .iter()
+            .filter_map(|t| inventory.articles.get(t))
+            .flatten()
+            .map(SheetArticle::from)
+            .collect();
+        let upgrades = UPGRADE_TYPES
+            .iter()
+            .filter_map(|t| inventory.upgrades.get(t))
+            .flatten()
+            .map(SheetUpgrade::from)
+            .collect();
+        SheetInventory { articles, upgrades }
+    }
+}
+
+impl CharacterSheet {
+    pub fn from_save(save: &SaveData) -> CharacterSheet {
+        let [x, y, z] = save.position.coordinates.values(&save.file);
+        CharacterSheet {
+            version: SHEET_VERSION,
+            username: save.username.string.clone(),
+            playtime: save.playtime,
+            stats: save
+                .stats
+                .iter()
+                .map(|s| (s.name.clone(), s.value))
+                .collect(),
+            position: SheetPosition {
+                x,
+                y,
+                z,
+                map_id: save.position.map_id(),
+            },
+            inventory: SheetInventory::from(&save.inventory),
+            storage: SheetInventory::from(&save.storage),
+            bosses: save
+                .bosses
+                .iter()
+                .map(|b| (b.name().to_string(), b.is_dead()))
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<CharacterSheet, Error> {
+        let sheet: CharacterSheet = serde_json::from_str(json)?;
+        sheet.check_version()?;
+        Ok(sheet)
+    }
+
+    fn check_version(&self) -> Result<(), Error> {
+        if self.version != SHEET_VERSION {
+            return Err(Error::UnsupportedVersion {
+                format: "character sheet",
+                version: self.version,
+                supported: SHEET_VERSION,
+            });
+        }
+        Ok(())
+    }
+
+    /// Recreates the character of the sheet in `save`.
+    ///
+    /// Existing articles are matched by id (weapons by their base id) and updated, upgrades by
+    /// their type, shape, source and effects. Missing articles, upgrades and the gems of their
+    /// slots are created, unless the save or the inventory has no room left for them, which
+    /// is reported as skipped.
+    ///
+    /// Applying a sheet is additive: articles and upgrades of `save` that aren't in the sheet
+    /// are kept. On error `save` is left as it was, nothing of the sheet is applied.
+    pub fn apply(&self, save: &mut SaveData) -> Result<ApplyReport, Error> {
+        self.check_version()?;
+        //Applied to a copy so an error halfway doesn't leave a half edited save
+        let mut applied = save.clone();
+        let report = self.apply_to(&mut applied)?;
+        *save = applied;
+        Ok(report)
+    }
+
+    fn apply_to(&self, save: &mut SaveData) -> Result<ApplyReport, Error> {
+        let mut report = ApplyReport::default();
+
+        if save.username.string != self.username {
+            save.username.set(&mut save.file, self.username.clone())?;
+            report.applied.push(format!("username: {}", self.username));
+        }
+        if save.playtime != self.playtime {
+            save.file.set_playtime(self.playtime.to_le_bytes());
+            save.playtime = self.playtime;
+            report.applied.push(format!("playtime: {}", self.playtime));
+        }
+
+        for (name, value) in &self.stats {
+            match save.stats.iter_mut().find(|s| &s.name == name) {
+                Some(stat) if stat.value != *value => {
+                    stat.edit(*value, &mut save.file);
+                    report.applied.push(format!("{}: {}", name, value));
+                }
+                Some(_) => {}
+                None => report.skipped.push(format!("unknown stat {}", name)),
+            }
+        }
+
+        let position = &self.position;
+        let current = save.position.coordinates.values(&save.file);
+        if current != [position.x, position.y, position.z]
+            || save.position.map_id() != position.map_id
+        {
+            save.position.teleport(
+                &mut save.file,
+                position.x,
+                position.y,
+                position.z,
+                position.map_id,
+            );
+            report.applied.push("position".to_string());
+        }
+
+        for (name, dead) in &self.bosses {
+            match save.bosses.iter_mut().find(|b| b.name() == name) {
+                Some(boss) if boss.is_dead() != *dead => {
+                    boss.set_dead(&mut save.file, *dead)?;
+                    let state = if *dead { "dead" } else { "alive" };
+                    report.applied.push(format!("{}: {}", name, state));
+                }
+                Some(_) => {}
+                None => report.skipped.push(format!("unknown boss {}", name)),
+            }
+        }
+
+        apply_inventory(
+            &self.inventory,
+            &mut save.inventory,
+            &mut save.file,
+            false,
+            &mut report,
+        )?;
+        apply_inventory(
+            &self.storage,
+            &mut save.storage,
+            &mut save.file,
+            true,
+            &mut report,
+        )?;
+        //New slots blocks and upgrades move the rest of the save
+        save.position = Pos::new(&save.file)?;
+
+        Ok(report)
+    }
+}
+
+//Weapons are matched without their upgrade level and imprint
+fn same_article(sheet: &SheetArticle, article: &Article) -> bool {
+    match article.type_family {
+        TypeFamily::Weapon => sheet.id / 100000 == article.id / 100000,
+        TypeFamily::Armor | TypeFamily::Item => sheet.id == article.id,
+    }
+}
+
+fn apply_inventory(
+    sheet: &SheetInventory,
+    inventory: &mut Inventory,
+    file: &mut FileData,
+    is_storage: bool,
+    report: &mut ApplyReport,
+) -> Result<(), Error> {
+    let location = if is_storage { "storage" } else { "inventory" };
+    //Articles of the save already paired with one of the sheet
+    let mut used: Vec<(ArticleType, usize)> = Vec::new();
+
+    for sheet_article in &sheet.articles {
+        let article_type = sheet_article.article_type;
+        let found = inventory.articles.get(&article_type).and_then(|articles| {
+            articles.iter().enumerate().position(|(i, a)| {
+                !used.contains(&(article_type, i)) && same_article(sheet_article, a)
+            })
+        });
+        let label = format!("{} {}", location, sheet_article.name);
+
+        let index = match found {
+            Some(index) => index,
+            None if article_type == ArticleType::Key => {
+                report.skipped.push(format!("{}: not in this save", label));
+                continue;
+            }
+            None => {
+                let added = match TypeFamily::from(article_type) {
+                    TypeFamily::Item => inventory
+                        .add_item(file, sheet_article.id, sheet_article.amount, is_storage)
+                        .map(|inventory| inventory.articles[&article_type].len() - 1),
+                    TypeFamily::Weapon => {
+                        let shapes: Vec<SlotShape> =
+                            sheet_article.slots.iter().map(|s| s.shape).collect();
+                        inventory
+                            .add_weapon(
+                                file,
+                                sheet_article.id,
+                                sheet_article.imprint,
+                                sheet_article.upgrade_level.unwrap_or(0),
+                                &shapes,
+                                is_storage,
+                            )
+                            .map(|article| article.index)
+                    }
+                    TypeFamily::Armor => inventory
+                        .add_armor(file, sheet_article.id, is_storage)
+                        .map(|article| article.index),
+                };
+                match out_of_room(added)? {
+                    Ok(index) => {
+                        report
+                            .applied
+                            .push(format!("{}: added {}", label, sheet_article.amount));
+                        used.push((article_type, index));
+                        fill_slots(
+                            sheet_article,
+                            inventory,
+                            file,
+                            index,
+                            is_storage,
+                            &label,
+                            report,
+                        )?;
+                    }
+                    Err(error) => report.skipped.push(format!("{}: {}", label, error)),
+                }
+                continue;
+            }
+        };
+        used.push((article_type, index));
+
+        let article = &mut inventory.articles.get_mut(&article_type).unwrap()[index];
+        let (number, id, amount) = (article.number, article.id, article.amount);
+        match article.type_family {
+            TypeFamily::Item if amount != sheet_article.amount => {
+                if article_type == ArticleType::Key {
+                    report
+                        .skipped
+                        .push(format!("{}: key items can't be edited", label));
+                } else {
+                    inventory.edit_item(file, number, id, sheet_article.amount, is_storage)?;
+                    report
+                        .applied
+                        .push(format!("{}: {}", label, sheet_article.amount));
+                }
+            }
+            TypeFamily::Item => {}
+            TypeFamily::Weapon | TypeFamily::Armor => {
+                apply_article(sheet_article, article, file, is_storage, &label, report)?;
+                fill_slots(
+                    sheet_article,
+                    inventory,
+                    file,
+                    index,
+                    is_storage,
+                    &label,
+                    report,
+                )?;
+            }
+        }
+    }
+
+    //Upgrade ids are local to each save, they are matched by what they are
+    let mut used: Vec<(UpgradeType, usize)> = Vec::new();
+    for sheet_upgrade in &sheet.upgrades {
+        let upgrade_type = sheet_upgrade.upgrade_type;
+        let label = format!("{} {}", location, sheet_upgrade.name);
+        let found = inventory.upgrades.get(&upgrade_type).and_then(|upgrades| {
+            upgrades.iter().enumerate().position(|(i, u)| {
+                !used.contains(&(upgrade_type, i)) && same_upgrade(sheet_upgrade, u)
+            })
+        });
+        match found {
+            Some(index) => used.push((upgrade_type, index)),
+            None => {
+                match out_of_room(create_upgrade(sheet_upgrade, inventory, file, is_storage))? {
+                    Ok(upgrade) => {
+                        used.push((upgrade_type, upgrade.index));
+                        report.applied.push(format!("{}: created", label));
+                    }
+                    Err(error) => report.skipped.push(format!("{}: {}", label, error)),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+//A full save or inventory is reported instead of stopping the whole sheet
+fn out_of_room<T>(result: Result<T, Error>) -> Result<Result<T, Error>, Error> {
+    match result {
+        Err(error @ Error::SaveFull { .. }) | Err(error @ Error::InventoryFull { .. }) => {
+            Ok(Err(error))
+        }
+        Err(error) => Err(error),
+        Ok(value) => Ok(Ok(value)),
+    }
+}
+
+fn same_upgrade(sheet: &SheetUpgrade, upgrade: &Upgrade) -> bool {
+    let effects = |effects: &[(u32, String)]| -> Vec<u32> {
+        effects
+            .iter()
+            .map(|e| e.0)
+            .filter(|e| *e != NO_EFFECT)
+            .collect()
+    };
+    sheet.upgrade_type == upgrade.upgrade_type
+        && sheet.shape == upgrade.shape
+        && sheet.source == upgrade.source
+        && effects(&sheet.effects) == effects(&upgrade.effects)
+}
+
+fn create_upgrade(
+    sheet: &SheetUpgrade,
+    inventory: &mut Inventory,
+    file: &mut FileData,
+    is_storage: bool,
+) -> Result<Upgrade, Error> {
+    let mut effects = [NO_EFFECT; 6];
+    for (effect, sheet_effect) in effects.iter_mut().zip(&sheet.effects) {
+        *effect = sheet_effect.0;
+    }
+    inventory.create_upgrade(
+        file,
+        sheet.upgrade_type,
+        &sheet.shape,
+        effects,
+        sheet.source,
+        is_storage,
+    )
+}
+
+//Creates the gems of the sheet that the slots of the article lack and equips them
+fn fill_slots(
+    sheet: &SheetArticle,
+    inventory: &mut Inventory,
+    file: &mut FileData,
+    index: usize,
+    is_storage: bool,
+    label: &str,
+    report: &mut ApplyReport,
+) -> Result<(), Error> {
+    let article_type = sheet.article_type;
+    for (slot_index, sheet_slot) in sheet.slots.iter().enumerate() {
+        let sheet_gem = match &sheet_slot.gem {
+            Some(gem) => gem,
+            None => continue,
+        };
+        let empty = inventory.articles[&article_type][index]
+            .slots
+            .as_ref()
+            .and_then(|slots| slots.get(slot_index))
+            .map_or(false, |slot| slot.gem.is_none());
+        if !empty {
+            continue;
+        }
+        let slot_label = format!("{}: slot {} {}", label, slot_index + 1, sheet_gem.name);
+        match out_of_room(create_upgrade(sheet_gem, inventory, file, is_storage))? {
+            Ok(gem) => {
+                inventory.equip_gem(
+                    file,
+                    gem.index,
+                    article_type,
+                    index,
+                    slot_index,
+                    is_storage,
+                )?;
+                report.applied.push(slot_label);
+            }
+            Err(error) => report.skipped.push(format!("{}: {}", slot_label, error)),
+        }
+    }
+    Ok(())
+}
+
+//Upgrade level, imprint and slots of a weapon or armor, the gems missing from the slots are
+//left to fill_slots
+fn apply_article(
+    sheet: &SheetArticle,
+    article: &mut Article,
+    file: &mut FileData,
+    is_storage: bool,
+    label: &str,
+    report: &mut ApplyReport,
+) -> Result<(), Error> {
+    if article.is_weapon() {
+        let mods = WeaponMods::try_from(article.second_part)?;
+        let level = sheet.upgrade_level.unwrap_or(mods.upgrade_level);
+        if mods.upgrade_level != level || mods.imprint != sheet.imprint {
+            //The second part is only updated in the inventory
+            if is_storage {
+                report.skipped.push(format!(
+                    "{}: weapons in the storage can't be upgraded",
+                    label
+                ));
+            } else {
+                article.set_imprint_and_upgrade(file, Some(sheet.imprint), Some(level))?;
+                report.applied.push(format!("{}: +{}", label, level));
+            }
+        }
+    }
+
+    let slot_count = article.slots.as_ref().map_or(0, |s| s.len());
+    for (slot_index, sheet_slot) in sheet.slots.iter().enumerate().take(slot_count) {
+        let shape = article.slots.as_ref().unwrap()[slot_index].shape;
+        if shape != sheet_slot.shape {
+            article.change_slot_shape(file, slot_index, sheet_slot.shape)?;
+            report.applied.push(format!(
+                "{}: slot {} {:?}",
+                label,
+                slot_index + 1,
+                sheet_slot.shape
+            ));
+        }
+
+        let gem = &mut article.slots.as_mut().unwrap()[slot_index].gem;
+        if let (Some(gem), Some(sheet_gem)) = (gem, &sheet_slot.gem) {
+            if apply_upgrade(sheet_gem, gem, file)? {
+                report.applied.push(format!(
+                    "{}: slot {} {}",
+                    label,
+                    slot_index + 1,
+                    sheet_gem.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+//Returns true if the shape, the source or an effect changed
+fn apply_upgrade(
+    sheet: &SheetUpgrade,
+    upgrade: &mut Upgrade,
+    file: &mut FileData,
+) -> Result<bool, Error> {
+    let mut changed = false;
+    if upgrade.source != sheet.source {
+        upgrade.change_source(file, sheet.source)?;
+        changed = true;
+    }
+    if upgrade.shape != sheet.shape {
+        upgrade.change_shape(file, sheet.shape.clone())?;
+        changed = true;
+    }
+    for (index, effect) in sheet.effects.iter().enumerate() {
+        if upgrade.effects.get(index).map(|e| e.0) != Some(effect.0) {
+            upgrade.change_effect(file, effect.0, index)?;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// Writes the character sheet of `save` to `path`.
+pub fn export(save: &SaveData, path: &str) -> Result<(), Error> {
+    fs::write(path, CharacterSheet::from_save(save).to_json()?)?;
+    Ok(())
+}
+
+/// Reads the character sheet at `path` and applies it to `save`.
+pub fn import(save: &mut SaveData, path: &str) -> Result<ApplyReport, Error> {
+    CharacterSheet::from_json(&fs::read_to_string(path)?)?.apply(save)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::build_save_data;
+
+    #[test]
+    fn test_round_trip() {
+        let save = build_save_data("testsave0");
+        let sheet = CharacterSheet::from_save(&save);
+        assert_eq!(sheet.version, SHEET_VERSION);
+        assert_eq!(sheet.stats["Vitality"], save.stats[5].value);
+        assert_eq!(sheet.username, save.username.string);
+
+        let parsed = CharacterSheet::from_json(&sheet.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, sheet);
+
+        //Applying the sheet of a save to itself changes nothing
+        let mut target = build_save_data("testsave0");
+        let report = sheet.apply(&mut target).unwrap();
+        assert!(report.applied.is_empty());
+        assert!(report.skipped.is_empty());
+        assert_eq!(target.file.bytes, save.file.bytes);
+    }
+
+    #[test]
+    fn test_apply() {
+        let source = build_save_data("testsave0");
+        let mut sheet = CharacterSheet::from_save(&source);
+        sheet.username = "Sheet".to_string();
+        sheet.playtime = 1234;
+        *sheet.stats.get_mut("Strength").unwrap() = 40;
+        sheet.position.x = 10.5;
+        let blood_vial = sheet
+            .inventory
+            .articles
+            .iter_mut()
+            .find(|a| a.id == 1000)
+            .unwrap();
+        blood_vial.amount = 7;
+        let boss = sheet.bosses.keys().next().unwrap().clone();
+        let dead = !sheet.bosses[&boss];
+        sheet.bosses.insert(boss.clone(), dead);
+
+        let mut target = build_save_data("testsave0");
+        sheet.apply(&mut target).unwrap();
+        assert_eq!(CharacterSheet::from_save(&target), sheet);
+
+        //The edits are written to the bytes too
+        let reparsed = SaveData::from_file(target.file.clone()).unwrap();
+        assert_eq!(CharacterSheet::from_save(&reparsed), sheet);
+    }
+
+    #[test]
+    fn test_apply_to_other_save() {
+        let source = build_save_data("testsave3");
+        let sheet = CharacterSheet::from_save(&source);
+
+        //testsave2 ends in enough zeros for the new slots blocks and upgrades
+        let mut target = build_save_data("testsave2");
+        let original = CharacterSheet::from_save(&target);
+        let report = sheet.apply(&mut target).unwrap();
+        let applied = CharacterSheet::from_save(&target);
+        assert_eq!(applied.stats, sheet.stats);
+        assert_eq!(applied.username, sheet.username);
+        assert_eq!(applied.bosses, sheet.bosses);
+        //Only the key items can't be added
+        assert!(!report.applied.is_empty());
+        for skipped in &report.skipped {
+            assert!(skipped.ends_with("not in this save"), "{}", skipped);
+        }
+
+        //The bytes hold the same character as the model
+        let reparsed = SaveData::from_file(target.file.clone()).unwrap();
+        assert_eq!(CharacterSheet::from_save(&reparsed), applied);
+
+        for (sheet_inventory, applied_inventory, original_inventory) in [
+            (&sheet.inventory, &applied.inventory, &original.inventory),
+            (&sheet.storage, &applied.storage, &original.storage),
+        ] {
+            //Every article of the sheet is in the target as the sheet describes it
+            for article in &sheet_inventory.articles {
+                let same = |a: &&SheetArticle| {
+                    a.article_type == article.article_type
+                        && a.id == article.id
+                        && a.imprint == article.imprint
+                        && a.upgrade_level == article.upgrade_level
+                        && a.slots.len() == article.slots.len()
+                        && a.slots.iter().zip(&article.slots).all(|(s, t)| {
+                            s.shape == t.shape
+                                && s.gem.as_ref().map(without_id) == t.gem.as_ref().map(without_id)
+                        })
+                };
+                let found = applied_inventory.articles.iter().find(same);
+                match found {
+                    Some(found) if article.article_type != ArticleType::Key => {
+                        assert_eq!(found.amount, article.amount, "{}", article.name)
+                    }
+                    Some(_) => {}
+                    None => {
+                        assert_eq!(article.article_type, ArticleType::Key, "{}", article.name);
+                        assert!(report.skipped.iter().any(|s| s.contains(&article.name)));
+                    }
+                }
+            }
+
+            //Every loose upgrade of the sheet is there, and the upgrades of the target that
+            //match none of them are left alone
+            let count = |upgrades: &[SheetUpgrade], upgrade: &SheetUpgrade| {
+                upgrades
+                    .iter()
+                    .filter(|u| without_id(u) == without_id(upgrade))
+                    .count()
+            };
+            for upgrade in &sheet_inventory.upgrades {
+                let wanted = count(&sheet_inventory.upgrades, upgrade);
+                let kept = count(&original_inventory.upgrades, upgrade);
+                assert_eq!(
+                    count(&applied_inventory.upgrades, upgrade),
+                    wanted.max(kept),
+                    "{}",
+                    upgrade.name
+                );
+            }
+            for upgrade in &original_inventory.upgrades {
+                if count(&sheet_inventory.upgrades, upgrade) == 0 {
+                    assert!(
+                        applied_inventory.upgrades.contains(upgrade),
+                        "{}",
+                        upgrade.name
+                    );
+                }
+            }
+        }
+    }
+
+    //An upgrade as the sheet describes it, without the id that only means something in its save
+    fn without_id(upgrade: &SheetUpgrade) -> SheetUpgrade {
+        SheetUpgrade {
+            id: 0,
+            ..upgrade.clone()
+        }
+    }
+
+    #[test]
+    fn test_apply_error_keeps_save() {
+        let save = build_save_data("testsave0");
+        let mut sheet = CharacterSheet::from_save(&save);
+        sheet.username = "Sheet".to_string();
+        *sheet.stats.get_mut("Strength").unwrap() = 40;
+        //The stats are applied before the articles, the unknown article stops the sheet
+        let mut unknown = sheet.inventory.articles[0].clone();
+        unknown.id = 999999;
+        sheet.inventory.articles.push(unknown);
+
+        let mut target = build_save_data("testsave0");
+        assert!(sheet.apply(&mut target).is_err());
+        assert_eq!(target.file.bytes, save.file.bytes);
+        assert_eq!(
+            CharacterSheet::from_save(&target),
+            CharacterSheet::from_save(&save)
+        );
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let save = build_save_data("testsave0");
+        let mut sheet = CharacterSheet::from_save(&save);
+        sheet.version = SHEET_VERSION + 1;
+
+        let result = CharacterSheet::from_json(&sheet.to_json().unwrap());
+        assert!(result.is_err());
+        if let Err(error) = result {
+            assert_eq!(
+                error.to_string(),
+                "The character sheet has version 2, only version 1 is supported."
+            );
+        }
+    }
+}
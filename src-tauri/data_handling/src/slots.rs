@@ -1,3 +1,4 @@
+//! Gem slots of weapons and armors.
 use serde::{Deserialize, Serialize};
 
 use super::{
@@ -15,11 +16,12 @@ pub struct Slot {
 }
 
 impl Slot {
-    fn build(shape: SlotShape, gem: Option<Upgrade>, index: usize) -> Self {
+    pub fn build(shape: SlotShape, gem: Option<Upgrade>, index: usize) -> Self {
         Slot { shape, gem, index }
     }
 }
 
+/// Parses the gem slots of every weapon and armor, keyed by the first and second part of the article.
 pub fn parse_equipped_gems(
     file_data: &mut FileData,
     upgrades: &mut HashMap<u32, (Upgrade, UpgradeType)>,
@@ -53,8 +55,12 @@ pub fn parse_equipped_gems(
     //If there is, it is added to the HashMap
     //The offset must point to the fist bit of the block
     let mut get_slots = |offset: usize| -> bool {
+        if offset + 60 > file_data.bytes.len() {
+            return false;
+        }
+
         let id = u64::from_le_bytes([
-            file_data.bytes[offset + 0],
+            file_data.bytes[offset],
             file_data.bytes[offset + 1],
             file_data.bytes[offset + 2],
             file_data.bytes[offset + 3],
@@ -69,11 +75,11 @@ pub fn parse_equipped_gems(
         }
 
         let mut slots_vec = Vec::with_capacity(5);
-        for val in (offset + 20..offset + 60).into_iter().step_by(8) {
+        for val in (offset + 20..offset + 60).step_by(8) {
             let mut gem: Option<Upgrade> = None;
             //If the slot shape is valid
             if let Ok(shape) = SlotShape::try_from(&[
-                file_data.bytes[val + 0],
+                file_data.bytes[val],
                 file_data.bytes[val + 1],
                 file_data.bytes[val + 2],
                 file_data.bytes[val + 3],
@@ -132,16 +138,17 @@ pub fn parse_equipped_gems(
         }
 
         //Skip the garbage
-        while u64::from_le_bytes([
-            file_data.bytes[index + 0],
-            file_data.bytes[index + 1],
-            file_data.bytes[index + 2],
-            file_data.bytes[index + 3],
-            file_data.bytes[index + 4],
-            file_data.bytes[index + 5],
-            file_data.bytes[index + 6],
-            file_data.bytes[index + 7],
-        ]) == 0xFFFFFFFF00000000
+        while index + 8 <= file_data.bytes.len()
+            && u64::from_le_bytes([
+                file_data.bytes[index],
+                file_data.bytes[index + 1],
+                file_data.bytes[index + 2],
+                file_data.bytes[index + 3],
+                file_data.bytes[index + 4],
+                file_data.bytes[index + 5],
+                file_data.bytes[index + 6],
+                file_data.bytes[index + 7],
+            ]) == 0xFFFFFFFF00000000
         {
             index += 8;
         }
@@ -152,19 +159,18 @@ pub fn parse_equipped_gems(
         }
     }
 
-    return slots;
+    slots
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data_handling::upgrades::parse_upgrades;
-    use std::path::PathBuf;
+    use crate::upgrades::parse_upgrades;
 
     #[test]
     fn test_parse_equipped_gems() {
-        let mut file_data = FileData::build("saves/testsave9", PathBuf::from("resources")).unwrap();
-        let mut upgrades = parse_upgrades(&file_data);
+        let mut file_data = FileData::build("saves/testsave9").unwrap();
+        let mut upgrades = parse_upgrades(&file_data).unwrap();
         let slots = parse_equipped_gems(&mut file_data, &mut upgrades);
         assert_eq!(file_data.offsets.equipped_gems, (0x1bc, 0x1bc3));
         //Hunter Axe +3
@@ -0,0 +1,578 @@
+//! Character stats (attributes, level, echoes, insight...).
+use serde::{Deserialize, Serialize};
+
+use super::{
+    constants::MAX_ATTRIBUTE,
+    enums::{Error, Origin},
+    file::FileData,
+    resources,
+};
+
+/// Names of the attributes that add up to the level.
+pub const ATTRIBUTES: [&str; 6] = [
+    "Vitality",
+    "Endurance",
+    "Strength",
+    "Skill",
+    "Bloodtinge",
+    "Arcane",
+];
+
+//Every origin starts with attributes adding up to its level plus this
+const LEVEL_OFFSET: u32 = 50;
+
+//Stage points of the game's correction graphs: attribute, value and the exponent of the curve
+//up to the next point. A negative exponent bends the curve the other way, fast then slow
+const HEALTH_GRAPH: [(u32, f64, f64); 4] = [
+    (1, 520.0, 1.0),
+    (30, 1100.0, -1.2),
+    (50, 1500.0, 1.0),
+    (99, 1900.0, 1.0),
+];
+const STAMINA_GRAPH: [(u32, f64, f64); 4] = [
+    (1, 80.0, 1.0),
+    (10, 91.0, 1.2),
+    (40, 160.0, 1.0),
+    (99, 200.0, 1.0),
+];
+
+//Starting attributes of every origin, in the order of Origin
+const ORIGIN_ATTRIBUTES: [[u32; 6]; 9] = [
+    [11, 10, 12, 10, 9, 8],
+    [14, 11, 11, 10, 7, 7],
+    [9, 14, 9, 13, 6, 9],
+    [12, 11, 15, 9, 6, 7],
+    [9, 12, 9, 15, 7, 8],
+    [10, 10, 14, 13, 7, 6],
+    [7, 8, 9, 13, 14, 9],
+    [10, 12, 10, 9, 5, 14],
+    [10, 9, 10, 9, 7, 9],
+];
+
+//Echoes needed to leave the levels 1 to 11, the formula of level_up_cost starts at 12
+const LEVEL_UP_COSTS: [u32; 11] = [673, 690, 707, 724, 741, 758, 775, 793, 811, 829, 847];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Stat {
+    pub name: String,
+    pub rel_offset: isize,
+    pub length: usize,
+    pub times: usize,
+    pub value: u32,
+}
+
+impl Stat {
+    /// Writes `value` to the save and updates the stat.
+    pub fn edit(&mut self, value: u32, file: &mut FileData) {
+        //Updates the stat value and saves it in a FileData instance
+        self.value = value;
+        file.edit(self.rel_offset, self.length, self.times, self.value);
+    }
+
+    /// Sets the base max value of Health or Stamina. The max keeps the bonuses of runes and gems
+    /// on top of it and the current value is kept, down to the new max. Stats with a single copy
+    /// are edited as usual.
+    pub fn set_base_max(&mut self, base: u32, file: &mut FileData) {
+        let (current, max, old_base) = match self.copies(file)[..] {
+            [current, max, old_base] => (current, max, old_base),
+            _ => return self.edit(base, file),
+        };
+        let max = base + max.saturating_sub(old_base);
+        self.value = current.min(max);
+        for (i, value) in [self.value, max, base].iter().enumerate() {
+            file.edit(self.rel_offset + 4 * i as isize, self.length, 1, *value);
+        }
+    }
+
+    /// Reads every copy of the stat. Health and Stamina keep the current value, the max
+    /// value with the bonuses of runes and gems and the base max value, in that order.
+    pub fn copies(&self, file: &FileData) -> Vec<u32> {
+        (0..self.times)
+            .map(|i| file.get_number(self.rel_offset + 4 * i as isize, self.length))
+            .collect()
+    }
+}
+
+/// The six attributes of a character, the ones the level is made of.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attributes {
+    pub vitality: u32,
+    pub endurance: u32,
+    pub strength: u32,
+    pub skill: u32,
+    pub bloodtinge: u32,
+    pub arcane: u32,
+}
+
+impl Attributes {
+    /// Reads the attributes from the parsed stats.
+    pub fn from_stats(stats: &[Stat]) -> Result<Attributes, Error> {
+        let mut values = [0; 6];
+        for (value, name) in values.iter_mut().zip(ATTRIBUTES) {
+            *value = stats
+                .iter()
+                .find(|s| s.name == name)
+                .ok_or_else(|| Error::StatNotFound {
+                    name: name.to_string(),
+                })?
+                .value;
+        }
+        Ok(Attributes::from(values))
+    }
+
+    /// Values in the order of [`ATTRIBUTES`].
+    pub fn values(&self) -> [u32; 6] {
+        [
+            self.vitality,
+            self.endurance,
+            self.strength,
+            self.skill,
+            self.bloodtinge,
+            self.arcane,
+        ]
+    }
+
+    /// Fails with the first attribute outside of 1 to 99.
+    pub fn check(&self) -> Result<(), Error> {
+        for (name, value) in ATTRIBUTES.iter().zip(self.values()) {
+            if !(1..=MAX_ATTRIBUTE).contains(&value) {
+                return Err(Error::InvalidAttribute {
+                    name: name.to_string(),
+                    value,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails with the first attribute lower than the one of `base`, the attributes of the origin.
+    pub fn check_floor(&self, base: &Attributes) -> Result<(), Error> {
+        for ((name, value), min) in ATTRIBUTES.iter().zip(self.values()).zip(base.values()) {
+            if value < min {
+                return Err(Error::AttributeBelowOrigin {
+                    name: name.to_string(),
+                    value,
+                    min,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Level, base max Health and Stamina and level up cost of a character with these attributes.
+    pub fn derived(&self) -> DerivedStats {
+        let level = level(&self.values());
+        DerivedStats {
+            level,
+            health: max_health(self.vitality),
+            stamina: max_stamina(self.endurance),
+            echoes_to_next_level: level_up_cost(level),
+        }
+    }
+}
+
+impl From<[u32; 6]> for Attributes {
+    fn from(values: [u32; 6]) -> Self {
+        let [vitality, endurance, strength, skill, bloodtinge, arcane] = values;
+        Attributes {
+            vitality,
+            endurance,
+            strength,
+            skill,
+            bloodtinge,
+            arcane,
+        }
+    }
+}
+
+/// Starting attributes of `origin`.
+pub fn origin_attributes(origin: Origin) -> Attributes {
+    Attributes::from(ORIGIN_ATTRIBUTES[origin as usize])
+}
+
+/// Origin of a character along with the attributes and the level it starts with.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct OriginInfo {
+    pub origin: Origin,
+    pub attributes: Attributes,
+    pub level: u32,
+}
+
+impl From<Origin> for OriginInfo {
+    fn from(origin: Origin) -> Self {
+        let attributes = origin_attributes(origin);
+        OriginInfo {
+            origin,
+            attributes,
+            level: attributes.derived().level,
+        }
+    }
+}
+
+/// Origin stored in the Origin stat, none if the stat is missing or its value isn't an origin.
+pub fn read_origin(stats: &[Stat]) -> Option<OriginInfo> {
+    let stat = stats.iter().find(|s| s.name == "Origin")?;
+    Origin::try_from(stat.value).ok().map(OriginInfo::from)
+}
+
+/// Redistributes the points of a character at `level`, starting from `base`, the attributes
+/// of its origin. The attributes are raised towards `goals`, in proportion to what each one
+/// is missing when there aren't enough points. The points left once every goal is reached
+/// are spread evenly.
+pub fn respec(base: &Attributes, goals: &Attributes, level: u32) -> Result<Attributes, Error> {
+    let mut values = base.values();
+    let min = self::level(&values);
+    let max = self::level(&[MAX_ATTRIBUTE; 6]);
+    if !(min..=max).contains(&level) {
+        return Err(Error::InvalidLevel { level, min, max });
+    }
+
+    let mut points = level - min;
+    let mut missing = [0; 6];
+    for (missing, (goal, value)) in missing.iter_mut().zip(goals.values().iter().zip(values)) {
+        *missing = goal.min(&MAX_ATTRIBUTE).saturating_sub(value);
+    }
+    let needed: u32 = missing.iter().sum();
+    let raise = if needed > points {
+        share(points, &missing)
+    } else {
+        missing
+    };
+    for (value, raise) in values.iter_mut().zip(raise) {
+        *value += raise;
+        points -= raise;
+    }
+
+    //Always ends, the level check leaves room for every point
+    while points > 0 {
+        for value in values.iter_mut().filter(|v| **v < MAX_ATTRIBUTE) {
+            if points == 0 {
+                break;
+            }
+            *value += 1;
+            points -= 1;
+        }
+    }
+    Ok(Attributes::from(values))
+}
+
+//Splits the points in proportion to the weights, the biggest remainders get the points left
+fn share(points: u32, weights: &[u32; 6]) -> [u32; 6] {
+    let total = weights.iter().sum::<u32>() as u64;
+    let mut shares = [0; 6];
+    let mut remainders = Vec::with_capacity(6);
+    for (i, weight) in weights.iter().enumerate() {
+        let part = points as u64 * *weight as u64;
+        shares[i] = (part / total) as u32;
+        remainders.push((part % total, i));
+    }
+    //Stable, ties go to the first attributes
+    remainders.sort_by_key(|(remainder, _)| std::cmp::Reverse(*remainder));
+    let left = points - shares.iter().sum::<u32>();
+    for (_, i) in remainders.into_iter().take(left as usize) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// Stats that follow from the attributes, see [`Attributes::derived`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DerivedStats {
+    pub level: u32,
+    pub health: u32,
+    pub stamina: u32,
+    pub echoes_to_next_level: u32,
+}
+
+/// Level of a character with the given attributes.
+pub fn level(attributes: &[u32]) -> u32 {
+    attributes.iter().sum::<u32>().saturating_sub(LEVEL_OFFSET)
+}
+
+/// Base max Health granted by `vitality`, before runes and gems.
+pub fn max_health(vitality: u32) -> u32 {
+    graph_value(&HEALTH_GRAPH, vitality)
+}
+
+/// Base max Stamina granted by `endurance`, before runes and gems.
+pub fn max_stamina(endurance: u32) -> u32 {
+    graph_value(&STAMINA_GRAPH, endurance)
+}
+
+/// Echoes needed to go from `level` to the next one.
+pub fn level_up_cost(level: u32) -> u32 {
+    match level {
+        0..=11 => LEVEL_UP_COSTS[level.saturating_sub(1) as usize],
+        _ => {
+            let x = (level + 1) as f64;
+            (0.02 * x.powi(3) + 3.06 * x.powi(2) + 105.6 * x - 895.0) as u32
+        }
+    }
+}
+
+fn graph_value(graph: &[(u32, f64, f64)], attribute: u32) -> u32 {
+    let (first, last) = (graph[0], graph[graph.len() - 1]);
+    let attribute = attribute.clamp(first.0, last.0);
+    for stage in graph.windows(2) {
+        let ((from, low, exponent), (to, high, _)) = (stage[0], stage[1]);
+        if attribute <= to {
+            let t = (attribute - from) as f64 / (to - from) as f64;
+            let t = if exponent > 0.0 {
+                t.powf(exponent)
+            } else {
+                1.0 - (1.0 - t).powf(-exponent)
+            };
+            return (low + (high - low) * t) as u32;
+        }
+    }
+    last.1 as u32
+}
+
+/// Reads every stat listed in the embedded offsets list.
+pub fn new(file: &FileData) -> Result<Vec<Stat>, Error> {
+    let offsets_str = resources::OFFSETS;
+
+    // Read the JSON contents of the file as Vec<Stat>.
+    let mut stats: Vec<Stat> = serde_json::from_str(offsets_str)?;
+    for s in &mut stats {
+        s.value = file.get_number(s.rel_offset, s.length);
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        //testsave0
+        let file_data = FileData::build("saves/testsave0").unwrap();
+        let stats = new(&file_data).unwrap();
+        assert_eq!(
+            stats[0],
+            Stat {
+                name: "Health".to_string(),
+                rel_offset: -147,
+                length: 4,
+                times: 3,
+                value: 1900,
+            }
+        );
+        assert_eq!(
+            stats[1],
+            Stat {
+                name: "Stamina".to_string(),
+                rel_offset: -119,
+                length: 4,
+                times: 3,
+                value: 91,
+            }
+        );
+        assert_eq!(
+            stats[2],
+            Stat {
+                name: "Echoes".to_string(),
+                rel_offset: -19,
+                length: 4,
+                times: 1,
+                value: 9987417,
+            }
+        );
+        assert_eq!(
+            stats[3],
+            Stat {
+                name: "Insight".to_string(),
+                rel_offset: -35,
+                length: 4,
+                times: 1,
+                value: 999,
+            }
+        );
+        assert_eq!(
+            stats[4],
+            Stat {
+                name: "Level".to_string(),
+                rel_offset: -23,
+                length: 4,
+                times: 1,
+                value: 594,
+            }
+        );
+        assert_eq!(
+            stats[5],
+            Stat {
+                name: "Vitality".to_string(),
+                rel_offset: -103,
+                length: 4,
+                times: 1,
+                value: 99,
+            }
+        );
+        assert_eq!(
+            stats[6],
+            Stat {
+                name: "Endurance".to_string(),
+                rel_offset: -95,
+                length: 4,
+                times: 1,
+                value: 99,
+            }
+        );
+        assert_eq!(
+            stats[7],
+            Stat {
+                name: "Strength".to_string(),
+                rel_offset: -79,
+                length: 4,
+                times: 1,
+                value: 99,
+            }
+        );
+        assert_eq!(
+            stats[8],
+            Stat {
+                name: "Skill".to_string(),
+                rel_offset: -71,
+                length: 4,
+                times: 1,
+                value: 99,
+            }
+        );
+        assert_eq!(
+            stats[9],
+            Stat {
+                name: "Bloodtinge".to_string(),
+                rel_offset: -63,
+                length: 4,
+                times: 1,
+                value: 99,
+            }
+        );
+        assert_eq!(
+            stats[10],
+            Stat {
+                name: "Arcane".to_string(),
+                rel_offset: -55,
+                length: 4,
+                times: 1,
+                value: 99,
+            }
+        );
+    }
+
+    #[test]
+    fn stat_edit() {
+        //testsave0
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        let mut stats = new(&file_data).unwrap();
+        assert_eq!(stats[0].value, 1900);
+        assert_eq!(stats[1].value, 91);
+        assert_eq!(stats[2].value, 9987417);
+        assert_eq!(stats[3].value, 999);
+        assert_eq!(stats[4].value, 594);
+        stats[0].edit(10, &mut file_data);
+        stats[1].edit(20, &mut file_data);
+        stats[2].edit(30, &mut file_data);
+        stats[3].edit(40, &mut file_data);
+        stats[4].edit(50, &mut file_data);
+        assert_eq!(stats[0].value, 10);
+        assert_eq!(stats[1].value, 20);
+        assert_eq!(stats[2].value, 30);
+        assert_eq!(stats[3].value, 40);
+        assert_eq!(stats[4].value, 50);
+    }
+
+    #[test]
+    fn stat_copies() {
+        //testsave2, the base max values follow Vitality 30 and Endurance 30
+        let file_data = FileData::build("saves/testsave2").unwrap();
+        let stats = new(&file_data).unwrap();
+        assert_eq!(stats[0].copies(&file_data), vec![1155, 1155, 1100]);
+        assert_eq!(stats[1].copies(&file_data), vec![152, 152, 133]);
+        assert_eq!(stats[4].copies(&file_data), vec![100]);
+    }
+
+    #[test]
+    fn test_formulas() {
+        assert_eq!(level(&[11, 10, 12, 10, 9, 8]), 10);
+        assert_eq!(level(&[49, 40, 50, 46, 7, 20]), 162);
+        assert_eq!(max_health(30), 1100);
+        assert_eq!(max_health(49), 1489);
+        assert_eq!(max_health(50), 1500);
+        assert_eq!(max_health(99), 1900);
+        assert_eq!(max_health(999), 1900);
+        assert_eq!(max_stamina(10), 91);
+        assert_eq!(max_stamina(30), 133);
+        assert_eq!(max_stamina(40), 160);
+        assert_eq!(level_up_cost(1), 673);
+        assert_eq!(level_up_cost(11), 847);
+        assert_eq!(level_up_cost(12), 1038);
+        assert_eq!(level_up_cost(100), 61591);
+    }
+
+    #[test]
+    fn attributes_derived() {
+        //testsave2
+        let file_data = FileData::build("saves/testsave2").unwrap();
+        let attributes = Attributes::from_stats(&new(&file_data).unwrap()).unwrap();
+        assert_eq!(attributes.values(), [30, 30, 46, 30, 7, 7]);
+        assert_eq!(
+            attributes.derived(),
+            DerivedStats {
+                level: 100,
+                health: 1100,
+                stamina: 133,
+                echoes_to_next_level: 61591,
+            }
+        );
+        assert!(attributes.check().is_ok());
+
+        let mut attributes = Attributes::from([99; 6]);
+        assert!(attributes.check().is_ok());
+        attributes.arcane = 100;
+        assert_eq!(attributes.check().unwrap_err().kind(), "InvalidAttribute");
+        attributes.arcane = 0;
+        assert_eq!(attributes.check().unwrap_err().kind(), "InvalidAttribute");
+    }
+
+    #[test]
+    fn test_respec() {
+        //Waste of Skin starts at level 4, every other origin at level 10
+        for value in 0..9 {
+            let info = OriginInfo::from(Origin::try_from(value).unwrap());
+            assert_eq!(info.level, if value == 8 { 4 } else { 10 });
+        }
+        assert_eq!(Origin::try_from(9).unwrap_err().kind(), "InvalidOrigin");
+
+        //Cruel Fate, Arcane 50 and Bloodtinge 40 fit at level 120 with points to spare
+        let base = origin_attributes(Origin::CruelFate);
+        let goals = Attributes::from([20, 15, 0, 0, 40, 50]);
+        let attributes = respec(&base, &goals, 120).unwrap();
+        assert_eq!(attributes.values(), [25, 20, 14, 13, 44, 54]);
+        assert_eq!(attributes.derived().level, 120);
+        assert!(attributes.check_floor(&base).is_ok());
+
+        //At level 40 the goals get what they are missing in proportion
+        let attributes = respec(&base, &goals, 40).unwrap();
+        assert_eq!(attributes.values(), [14, 13, 10, 9, 17, 27]);
+        assert_eq!(attributes.derived().level, 40);
+
+        //The base level, then every attribute at 99
+        assert_eq!(respec(&base, &goals, 10).unwrap(), base);
+        let attributes = respec(&base, &goals, 544).unwrap();
+        assert_eq!(attributes.values(), [99; 6]);
+        assert_eq!(respec(&base, &goals, 9).unwrap_err().kind(), "InvalidLevel");
+        assert_eq!(
+            respec(&base, &goals, 545).unwrap_err().kind(),
+            "InvalidLevel"
+        );
+
+        let low = Attributes::from([10, 12, 10, 9, 5, 13]);
+        assert_eq!(
+            low.check_floor(&base).unwrap_err().kind(),
+            "AttributeBelowOrigin"
+        );
+    }
+}
@@ -0,0 +1,1390 @@
+//! Blood gems and caryll runes.
+use super::{
+    constants::{NO_EFFECT, OATH_EFFECTS_START},
+    enums::{Error, Location, UpgradeRule, UpgradeType},
+    file::FileData,
+    resources,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{self, Value};
+use std::collections::HashMap;
+
+//Gems that drop from a single place, with their source, name, shape and effects
+const UNIQUE_GEMS: [(u32, &str, &str, &[u32]); 3] = [
+    (0x800249F0, "Red Blood Gem", "Droplet", &[3126204, 3045204]),
+    (0x800249F1, "Tear Blood Gem", "Droplet", &[3143408]),
+    (0x800249F2, "Gold Blood Gem", "Radial", &[3133407]),
+];
+
+//Kinds of effects each shape drops with, taken from the gems of real saves.
+//Radial gems drop with every kind and Droplet gems are the unique ones and a few dozen
+//others, too few to tell, so neither is restricted.
+const SHAPE_EFFECTS: [(&str, &[&str]); 3] = [
+    (
+        "Triangle",
+        &[
+            "Add arcane ATK",
+            "Add fire ATK",
+            "Add bolt ATK",
+            "Add physical ATK",
+            "Blunt ATK UP",
+            "Thrust ATK UP",
+            "Arcane ATK UP",
+            "Fire ATK UP",
+            "Bolt ATK UP",
+            "Physical ATK UP",
+            "ATK UP",
+            "ATK DOWN",
+            "STR scaling",
+            "SKL scaling",
+            "Bloodtinge scaling",
+            "Arcane scaling",
+            "ATK vs beasts UP",
+            "ATK vs beasts DOWN",
+            "ATK vs the kin UP",
+            "ATK vs kin UP",
+            "ATK vs the kin DOWN",
+            "Add slow poison effect",
+            "Add rapid poison effect",
+            "ATK vs open foes UP",
+            "Reduces stamina costs",
+            "Increases stamina costs",
+            "Charge ATKs UP",
+            "HP continues to recover",
+            "HP gradually depletes",
+            "Boosts rally potential",
+            "WPN durability UP",
+            "WPN durability DOWN",
+            "Phys. UP at full HP",
+            "Phys. UP near death",
+            "ATK UP at full HP",
+            "ATK UP near death",
+        ],
+    ),
+    (
+        "Waning",
+        &[
+            "Add arcane ATK",
+            "Add fire ATK",
+            "Add bolt ATK",
+            "Add physical ATK",
+            "Blunt ATK UP",
+            "Thrust ATK UP",
+            "Arcane ATK UP",
+            "Fire ATK UP",
+            "Bolt ATK UP",
+            "Physical ATK UP",
+            "ATK UP",
+            "ATK DOWN",
+            "STR scaling",
+            "SKL scaling",
+            "Bloodtinge scaling",
+            "Arcane scaling",
+            "ATK vs beasts UP",
+            "ATK vs beasts DOWN",
+            "ATK vs the kin UP",
+            "ATK vs kin UP",
+            "ATK vs the kin DOWN",
+            "Add slow poison effect",
+            "Add rapid poison effect",
+            "ATK vs open foes UP",
+            "Reduces stamina costs",
+            "Increases stamina costs",
+            "HP continues to recover",
+            "HP gradually depletes",
+            "Boosts rally potential",
+            "WPN durability UP",
+            "WPN durability DOWN",
+            "Phys. UP at full HP",
+            "Phys. UP near death",
+            "ATK UP at full HP",
+            "ATK UP near death",
+        ],
+    ),
+    (
+        "Circle",
+        &[
+            "Add blood ATK",
+            "Add physical ATK",
+            "Blood ATK UP",
+            "Physical ATK UP",
+            "Bloodtinge scaling",
+            "ATK vs the kin UP",
+            "Increases stamina costs",
+            "WPN durability DOWN",
+        ],
+    ),
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UpgradeInfo {
+    pub name: String,
+    pub effect: String,
+    pub rating: u8,
+    pub level: u8,
+    pub note: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Upgrade {
+    pub number: u8,
+    pub id: u32,
+    pub source: u32,
+    pub upgrade_type: UpgradeType,
+    pub shape: String,
+    pub effects: Vec<(u32, String)>,
+    pub info: UpgradeInfo,
+    pub index: usize, //Index of the upgrade inside the vector
+}
+
+/// A rule broken by an upgrade, `effect_index` is the effect that breaks it if there is one.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub rule: UpgradeRule,
+    pub effect_index: Option<usize>,
+    pub reason: String,
+}
+
+/// The violations of an upgrade of the save, see [`SaveData::validate_upgrades`].
+///
+/// [`SaveData::validate_upgrades`]: crate::save::SaveData::validate_upgrades
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UpgradeReport {
+    pub id: u32,
+    pub name: String,
+    pub location: Location,
+    pub equipped: bool,
+    pub violations: Vec<Violation>,
+}
+
+impl Upgrade {
+    pub fn change_shape(
+        &mut self,
+        file_data: &mut FileData,
+        new_shape: String,
+    ) -> Result<(), Error> {
+        let new_shape_number = shape_byte(&new_shape, self.upgrade_type)?;
+
+        let upgrade_offset = match file_data.find_upgrade_offset(self.id) {
+            Some(offset) => offset,
+            None => return Err(Error::UpgradeNotFound { id: self.id }),
+        };
+
+        //Update the shape
+        self.shape = new_shape;
+        file_data.bytes[upgrade_offset + 12] = new_shape_number;
+        Ok(())
+    }
+
+    /// Changes the source kept after the id. Only meant for equipped gems, a loose upgrade also
+    /// has its source as the second part of its inventory entry, which is left as is.
+    pub fn change_source(&mut self, file_data: &mut FileData, source: u32) -> Result<(), Error> {
+        let upgrade_offset = match file_data.find_upgrade_offset(self.id) {
+            Some(offset) => offset,
+            None => return Err(Error::UpgradeNotFound { id: self.id }),
+        };
+
+        self.source = source;
+        file_data.bytes[upgrade_offset + 4..upgrade_offset + 8]
+            .copy_from_slice(&source.to_le_bytes());
+        Ok(())
+    }
+
+    ///value_index must be 0..=5
+    pub fn change_effect(
+        &mut self,
+        file_data: &mut FileData,
+        new_value: u32,
+        value_index: usize,
+    ) -> Result<(), Error> {
+        let upgrades_json: Value = serde_json::from_str(resources::UPGRADES)?;
+
+        let upgrade_offset = match file_data.find_upgrade_offset(self.id) {
+            Some(offset) => offset,
+            None => return Err(Error::UpgradeNotFound { id: self.id }),
+        };
+
+        let effect_info = effect_info(&upgrades_json, self.upgrade_type, new_value)?;
+        match self.effects.get_mut(value_index) {
+            Some(e) => {
+                e.0 = new_value;
+                e.1 = effect_info.effect.clone();
+            }
+            None => return Err(Error::InvalidEffectIndex { index: value_index }),
+        };
+
+        if value_index == 0 {
+            self.info = effect_info;
+        }
+
+        let effect_offset = upgrade_offset + 16 + (value_index * 4);
+        let bytes = new_value.to_le_bytes();
+
+        file_data.bytes[effect_offset..effect_offset + 4].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    pub fn transform(&mut self, file_data: &mut FileData) -> Result<(), Error> {
+        let new_upgrade_bytes: [u8; 32] = match self.upgrade_type {
+            UpgradeType::Gem => {
+                self.upgrade_type = UpgradeType::Rune;
+                self.shape = String::from("-");
+                self.effects = vec![
+                    (1100000, String::from("More echoes from slain enemies 1")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                ];
+                self.info.name = String::from("Moon");
+                self.info.effect = String::from("More echoes from slain enemies 1");
+                self.info.rating = 0;
+                self.info.level = 0;
+                self.info.note = String::from("\"Moon\" rune. Acquire more Blood Echoes");
+                [
+                    0x02, 0x00, 0x00, 0x00, //Type
+                    0x01, 0x00, 0x00, 0x00, //Shape
+                    0xE0, 0xC8, 0x10, 0x00, //Effect 1
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 2
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 3
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 4
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 5
+                    0xFF, 0xFF, 0xFF, 0xFF,
+                ] //Effect 6
+            }
+            UpgradeType::Rune => {
+                self.upgrade_type = UpgradeType::Gem;
+                self.shape = String::from("Radial");
+                self.effects = vec![
+                    (13101, String::from("Add blood ATK +0.5")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                    (0xFFFFFFFF, String::from("No Effect")),
+                ];
+                self.info.name = String::from("Bloodtinge Blood Gemstone (1)");
+                self.info.effect = String::from("Add blood ATK +0.5");
+                self.info.rating = 1;
+                self.info.level = 1;
+                self.info.note = String::from("");
+                [
+                    0x01, 0x00, 0x00, 0x00, //Type
+                    0x01, 0x00, 0x00, 0x00, //Shape
+                    0x2D, 0x33, 0x00, 0x00, //Effect 1
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 2
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 3
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 4
+                    0xFF, 0xFF, 0xFF, 0xFF, //Effect 5
+                    0xFF, 0xFF, 0xFF, 0xFF,
+                ] //Effect 6
+            }
+        };
+        let upgrade_offset = match file_data.find_upgrade_offset(self.id) {
+            Some(offset) => offset,
+            None => return Err(Error::UpgradeNotFound { id: self.id }),
+        };
+
+        //Update everything except id and source
+        for i in upgrade_offset + 8..upgrade_offset + 40 {
+            file_data.bytes[i] = new_upgrade_bytes[i - 8 - upgrade_offset];
+        }
+        Ok(())
+    }
+
+    /// Checks the record of the upgrade against what the game drops. Every effect must be
+    /// of its type and appear once. A gem has up to 2 effects when its best effect is below
+    /// rating 6 and 3 from there, with at most one curse that goes last. Its effects must drop
+    /// on its shape, and the unique gems keep the shape and effects of their source. The effects of a rune come from a single rune,
+    /// which decides its shape.
+    pub fn validate(&self, file_data: &FileData) -> Result<Vec<Violation>, Error> {
+        let upgrades_json: Value = serde_json::from_str(resources::UPGRADES)?;
+        Ok(self.check(file_data, &upgrades_json)?.0)
+    }
+
+    /// Rewrites the effects and the shape so [`Upgrade::validate`] passes, the effects that
+    /// break a rule are dropped. Returns the violations that were fixed.
+    pub fn legitimize(&mut self, file_data: &mut FileData) -> Result<Vec<Violation>, Error> {
+        let upgrades_json: Value = serde_json::from_str(resources::UPGRADES)?;
+        let (violations, fixed) = self.check(file_data, &upgrades_json)?;
+        if violations.is_empty() {
+            return Ok(violations);
+        }
+        let (effects, shape) = fixed.ok_or(Error::UnfixableUpgrade { id: self.id })?;
+        let offset = file_data
+            .find_upgrade_offset(self.id)
+            .ok_or(Error::UpgradeNotFound { id: self.id })?;
+        file_data.bytes[offset + 12] = shape;
+        for (i, effect) in effects.iter().enumerate() {
+            let effect_offset = offset + 16 + (i * 4);
+            file_data.bytes[effect_offset..effect_offset + 4]
+                .copy_from_slice(&effect.to_le_bytes());
+        }
+
+        //The names of the effects and the info come from the parsed record
+        let (parsed, _) = parse_upgrades(file_data)?
+            .remove(&self.id)
+            .ok_or(Error::UpgradeNotFound { id: self.id })?;
+        self.shape = parsed.shape;
+        self.effects = parsed.effects;
+        self.info = parsed.info;
+        Ok(violations)
+    }
+
+    //Violations of the record, with the effects and shape byte that fix them if an effect is left
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn check(
+        &self,
+        file_data: &FileData,
+        upgrades_json: &Value,
+    ) -> Result<(Vec<Violation>, Option<([u32; 6], u8)>), Error> {
+        let offset = file_data
+            .find_upgrade_offset(self.id)
+            .ok_or(Error::UpgradeNotFound { id: self.id })?;
+        let json_effects = match self.upgrade_type {
+            UpgradeType::Gem => &upgrades_json["gemEffects"],
+            UpgradeType::Rune => &upgrades_json["runeEffects"],
+        };
+        let violation = |rule, effect_index, reason: String| Violation {
+            rule,
+            effect_index,
+            reason,
+        };
+
+        let mut violations = Vec::new();
+        let mut shape = file_data.bytes[offset + 12];
+        let source = u32::from_le_bytes([
+            file_data.bytes[offset + 4],
+            file_data.bytes[offset + 5],
+            file_data.bytes[offset + 6],
+            file_data.bytes[offset + 7],
+        ]);
+        let unique = match self.upgrade_type {
+            UpgradeType::Gem => UNIQUE_GEMS.iter().find(|gem| gem.0 == source),
+            UpgradeType::Rune => None,
+        };
+        if let Some((_, name, unique_shape, _)) = unique {
+            let expected = shape_byte(unique_shape, UpgradeType::Gem)?;
+            if shape != expected {
+                let reason = format!("The {} is {}.", name, unique_shape);
+                violations.push(violation(UpgradeRule::WrongShape, None, reason));
+                shape = expected;
+            }
+        }
+        let shape_name = get_shape(shape, self.upgrade_type).unwrap_or_default();
+
+        let mut kept: Vec<(u32, UpgradeInfo)> = Vec::new();
+        let mut curse: Option<(u32, UpgradeInfo)> = None;
+        for i in 0..6 {
+            let effect_offset = offset + 16 + (i * 4);
+            let bytes = &file_data.bytes[effect_offset..effect_offset + 4];
+            let id = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            if id == NO_EFFECT {
+                continue;
+            }
+            let info: UpgradeInfo =
+                match serde_json::from_value(json_effects[id.to_string()].clone()) {
+                    Ok(info) => info,
+                    Err(_) => {
+                        let reason = format!("{} is not a {:?} effect.", id, self.upgrade_type);
+                        violations.push(violation(UpgradeRule::WrongEffectType, Some(i), reason));
+                        continue;
+                    }
+                };
+            if self.upgrade_type == UpgradeType::Gem {
+                if let Some(reason) = source_conflict(unique, id, &info) {
+                    violations.push(violation(UpgradeRule::WrongSource, Some(i), reason));
+                    continue;
+                }
+                let allowed = SHAPE_EFFECTS.iter().find(|(name, _)| *name == shape_name);
+                if let Some((_, kinds)) = allowed {
+                    if !kinds.contains(&effect_kind(&info.effect)) {
+                        let reason =
+                            format!("\"{}\" doesn't drop on {} gems.", info.effect, shape_name);
+                        violations.push(violation(UpgradeRule::ShapeEffect, Some(i), reason));
+                        continue;
+                    }
+                }
+            }
+            //Runes have tiers of the same effect, gems can't repeat the kind of effect
+            let repeated = kept.iter().chain(curse.iter()).any(|(other, other_info)| {
+                match self.upgrade_type {
+                    UpgradeType::Gem => {
+                        effect_kind(&other_info.effect) == effect_kind(&info.effect)
+                    }
+                    UpgradeType::Rune => *other == id,
+                }
+            });
+            if repeated {
+                let reason = format!(
+                    "\"{}\" is already on the {:?}.",
+                    info.effect, self.upgrade_type
+                );
+                violations.push(violation(UpgradeRule::DuplicateEffect, Some(i), reason));
+                continue;
+            }
+
+            match self.upgrade_type {
+                UpgradeType::Gem if info.name.starts_with("Cursed") => {
+                    if curse.is_some() {
+                        let reason = String::from("A gem has a single curse.");
+                        violations.push(violation(UpgradeRule::MisplacedCurse, Some(i), reason));
+                    } else {
+                        curse = Some((id, info));
+                    }
+                }
+                UpgradeType::Gem => {
+                    if curse.is_some() {
+                        let reason = String::from("The curse must come after the other effects.");
+                        violations.push(violation(UpgradeRule::MisplacedCurse, Some(i), reason));
+                    }
+                    kept.push((id, info));
+                }
+                UpgradeType::Rune => match kept.first() {
+                    Some((_, first)) if first.name != info.name => {
+                        let reason = format!(
+                            "\"{}\" belongs to the {} rune, not to {}.",
+                            info.effect, info.name, first.name
+                        );
+                        violations.push(violation(UpgradeRule::MixedRune, Some(i), reason));
+                    }
+                    _ => kept.push((id, info)),
+                },
+            }
+        }
+
+        if kept.is_empty() {
+            let reason = match curse {
+                Some(_) => String::from("A curse needs another effect before it."),
+                None => format!("The {:?} has no effect.", self.upgrade_type),
+            };
+            violations.push(violation(UpgradeRule::NoEffects, None, reason));
+            return Ok((violations, None));
+        }
+
+        match self.upgrade_type {
+            UpgradeType::Gem => {
+                let rating = kept.iter().map(|(_, info)| info.rating).max().unwrap_or(0);
+                let max = if rating <= 5 { 2 } else { 3 };
+                let count = kept.len() + curse.iter().count();
+                if count > max {
+                    let reason = format!(
+                        "A gem of rating {} has at most {} effects, this one has {}.",
+                        rating, max, count
+                    );
+                    violations.push(violation(UpgradeRule::TooManyEffects, None, reason));
+                }
+                kept.truncate(max - curse.iter().count());
+                kept.extend(curse);
+            }
+            UpgradeType::Rune => {
+                //Oath runes are the ones from the covenants
+                let expected = if kept[0].0 >= OATH_EFFECTS_START {
+                    0x02
+                } else {
+                    0x01
+                };
+                if shape != expected {
+                    let reason = format!(
+                        "The {} rune is {}.",
+                        kept[0].1.name,
+                        get_shape(expected, UpgradeType::Rune)?
+                    );
+                    violations.push(violation(UpgradeRule::WrongShape, None, reason));
+                    shape = expected;
+                }
+            }
+        }
+
+        let mut effects = [NO_EFFECT; 6];
+        for (effect, (id, _)) in effects.iter_mut().zip(kept) {
+            *effect = id;
+        }
+        Ok((violations, Some((effects, shape))))
+    }
+}
+
+//Why a gem of the source can't have the effect, the effects of the unique gems only drop on them
+fn source_conflict(
+    unique: Option<&(u32, &str, &str, &[u32])>,
+    id: u32,
+    info: &UpgradeInfo,
+) -> Option<String> {
+    match unique {
+        Some((_, name, _, effects)) if !effects.contains(&id) => Some(format!(
+            "The {} doesn't drop with \"{}\".",
+            name, info.effect
+        )),
+        Some(_) => None,
+        None => UNIQUE_GEMS
+            .iter()
+            .find(|(.., effects)| effects.contains(&id))
+            .map(|(_, name, ..)| format!("\"{}\" only drops on the {}.", info.effect, name)),
+    }
+}
+
+//Text of an effect without its value, "Add blood ATK +0.5" gives "Add blood ATK"
+fn effect_kind(effect: &str) -> &str {
+    effect.trim_end_matches(|c: char| c.is_ascii_digit() || "+-.% ".contains(c))
+}
+
+/// Parses every gem and rune of the save, keyed by their id.
+pub fn parse_upgrades(file_data: &FileData) -> Result<HashMap<u32, (Upgrade, UpgradeType)>, Error> {
+    let mut upgrades = HashMap::new();
+    let upgrades_json: Value = serde_json::from_str(resources::UPGRADES)?;
+
+    let (start, end) = file_data.offsets.upgrades;
+
+    for i in (start..end).step_by(40) {
+        let id = u32::from_le_bytes([
+            file_data.bytes[i],
+            file_data.bytes[i + 1],
+            file_data.bytes[i + 2],
+            file_data.bytes[i + 3],
+        ]);
+
+        let source = u32::from_le_bytes([
+            file_data.bytes[i + 4],
+            file_data.bytes[i + 5],
+            file_data.bytes[i + 6],
+            file_data.bytes[i + 7],
+        ]);
+
+        let upgrade_type = UpgradeType::try_from(file_data.bytes[i + 8])?;
+
+        let mut effects: Vec<(u32, String)> = Vec::with_capacity(6);
+        let mut effects_ids = [0; 6];
+        effects_ids[0] = u32::from_le_bytes([
+            file_data.bytes[i + 16],
+            file_data.bytes[i + 17],
+            file_data.bytes[i + 18],
+            file_data.bytes[i + 19],
+        ]);
+
+        effects_ids[1] = u32::from_le_bytes([
+            file_data.bytes[i + 20],
+            file_data.bytes[i + 21],
+            file_data.bytes[i + 22],
+            file_data.bytes[i + 23],
+        ]);
+
+        effects_ids[2] = u32::from_le_bytes([
+            file_data.bytes[i + 24],
+            file_data.bytes[i + 25],
+            file_data.bytes[i + 26],
+            file_data.bytes[i + 27],
+        ]);
+
+        effects_ids[3] = u32::from_le_bytes([
+            file_data.bytes[i + 28],
+            file_data.bytes[i + 29],
+            file_data.bytes[i + 30],
+            file_data.bytes[i + 31],
+        ]);
+
+        effects_ids[4] = u32::from_le_bytes([
+            file_data.bytes[i + 32],
+            file_data.bytes[i + 33],
+            file_data.bytes[i + 34],
+            file_data.bytes[i + 35],
+        ]);
+
+        effects_ids[5] = u32::from_le_bytes([
+            file_data.bytes[i + 36],
+            file_data.bytes[i + 37],
+            file_data.bytes[i + 38],
+            file_data.bytes[i + 39],
+        ]);
+
+        let fallback: &Value;
+        let json_effects: &Value = match upgrade_type {
+            UpgradeType::Gem => {
+                fallback = &upgrades_json["runeEffects"];
+                &upgrades_json["gemEffects"]
+            }
+            UpgradeType::Rune => {
+                fallback = &upgrades_json["gemEffects"];
+                &upgrades_json["runeEffects"]
+            }
+        };
+
+        let mut info = UpgradeInfo {
+            name: String::from(""),
+            effect: String::from("No Effect"),
+            rating: 95,
+            level: 0,
+            note: String::from(""),
+        };
+
+        for e in 0..6 {
+            let mut json_effect = &json_effects[&effects_ids[e].to_string()];
+            //If the effect wasn't found, search in the other upgrade type's effects
+            if json_effect.is_null() {
+                json_effect = &fallback[&effects_ids[e].to_string()];
+            }
+
+            let effect_info: UpgradeInfo = match serde_json::from_value(json_effect.clone()) {
+                Ok(inf) => inf,
+                Err(_) => continue,
+            };
+            effects.push((effects_ids[e], effect_info.effect.clone()));
+            if e == 0 {
+                info = effect_info;
+            }
+        }
+
+        let shape = match get_shape(file_data.bytes[i + 12], upgrade_type) {
+            Ok(sha) => sha,
+            Err(_) => continue,
+        };
+
+        let upgrade = Upgrade {
+            number: 0, //It will be set correctly in Inventory::build()
+
+            id,
+            source,
+            upgrade_type,
+            shape,
+            effects,
+            info,
+            index: 0, //It will be set correctly in Inventory::build()
+        };
+        upgrades.insert(id, (upgrade, upgrade_type));
+    }
+    Ok(upgrades)
+}
+
+pub fn get_shape(shape: u8, upgrade_type: UpgradeType) -> Result<String, Error> {
+    if upgrade_type == UpgradeType::Gem {
+        let res = match shape {
+            0x01 => "Radial",
+            0x02 => "Triangle",
+            0x04 => "Waning",
+            0x08 => "Circle",
+            0x3F => "Droplet",
+            _ => {
+                return Err(Error::InvalidShape {
+                    bytes: [shape, 0, 0, 0],
+                })
+            }
+        };
+        Ok(res.to_string())
+    } else {
+        let res = match shape {
+            0x01 => "-",
+            0x02 => "Oath",
+            _ => {
+                return Err(Error::InvalidShape {
+                    bytes: [shape, 0, 0, 0],
+                })
+            }
+        };
+        Ok(res.to_string())
+    }
+}
+
+/// Byte of a shape name, the inverse of [`get_shape`].
+pub fn shape_byte(shape: &str, upgrade_type: UpgradeType) -> Result<u8, Error> {
+    let byte = match upgrade_type {
+        UpgradeType::Gem => match shape {
+            "Radial" => 0x01,
+            "Triangle" => 0x02,
+            "Waning" => 0x04,
+            "Circle" => 0x08,
+            "Droplet" => 0x3F,
+            _ => 0,
+        },
+        UpgradeType::Rune => match shape {
+            "-" => 0x01,
+            "Oath" => 0x02,
+            _ => 0,
+        },
+    };
+    match byte {
+        0 => Err(Error::InvalidShapeName {
+            name: shape.to_string(),
+            upgrade_type,
+        }),
+        byte => Ok(byte),
+    }
+}
+
+/// Info of an effect, searched first among the effects of `upgrade_type`.
+pub fn effect_info(
+    upgrades_json: &Value,
+    upgrade_type: UpgradeType,
+    id: u32,
+) -> Result<UpgradeInfo, Error> {
+    let (json_effects, fallback) = match upgrade_type {
+        UpgradeType::Gem => (&upgrades_json["gemEffects"], &upgrades_json["runeEffects"]),
+        UpgradeType::Rune => (&upgrades_json["runeEffects"], &upgrades_json["gemEffects"]),
+    };
+    let mut json_effect = &json_effects[id.to_string()];
+    //If the effect wasn't found, search in the other upgrade type's effects
+    if json_effect.is_null() {
+        json_effect = &fallback[id.to_string()];
+    }
+    serde_json::from_value(json_effect.clone()).map_err(|_| Error::EffectNotFound { id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::check_bytes;
+    use std::{thread, time::Instant};
+
+    #[test]
+    fn test_parse_upgrades() {
+        //TESTSAVE 0
+        let file_data = FileData::build("saves/testsave0").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+
+        //Item N0
+        let gem = upgrades
+            .get(&u32::from_le_bytes([0x41, 0x00, 0x80, 0xC0]))
+            .unwrap()
+            .0
+            .clone();
+        assert_eq!(gem.id, u32::from_le_bytes([0x41, 0x00, 0x80, 0xC0]));
+        assert_eq!(gem.source, u32::from_le_bytes([0x26, 0x60, 0x01, 0x80]));
+        assert_eq!(gem.upgrade_type, UpgradeType::Gem);
+        assert_eq!(
+            gem.effects,
+            vec![
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45"))
+            ]
+        );
+        assert_eq!(gem.shape, String::from("Droplet"));
+        let info = gem.info.clone();
+        assert_eq!(info.name, String::from("Tempering Abyssal Blood Gem"));
+        assert_eq!(info.effect, String::from("Add physical ATK +45"));
+        assert_eq!(info.rating, 20);
+        assert_eq!(info.level, 7);
+        assert_eq!(info.note, String::from(""));
+
+        //Item N0
+        let rune = upgrades
+            .get(&u32::from_le_bytes([0x42, 0x00, 0x80, 0xC0]))
+            .unwrap()
+            .0
+            .clone();
+        assert_eq!(rune.id, u32::from_le_bytes([0x42, 0x00, 0x80, 0xC0]));
+        assert_eq!(rune.source, u32::from_le_bytes([0xBF, 0x92, 0x01, 0x80]));
+        assert_eq!(rune.upgrade_type, UpgradeType::Rune);
+        assert_eq!(
+            rune.effects,
+            vec![
+                (0x115582, String::from("Max QS bullets held UP +3")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect"))
+            ]
+        );
+        assert_eq!(rune.shape, String::from("-"));
+        let info = rune.info.clone();
+        assert_eq!(info.name, String::from("Formless Oedon"));
+        assert_eq!(info.effect, String::from("Max QS bullets held UP +3"));
+        assert_eq!(info.rating, 2);
+        assert_eq!(info.level, 0);
+        assert_eq!(info.note, String::from("Higher Quicksilver Bullet max"));
+
+        //TESTSAVE 7
+        let file_data = FileData::build("saves/testsave7").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+
+        //Item N0
+        let gem = upgrades
+            .get(&u32::from_le_bytes([0x67, 0x00, 0x80, 0xC0]))
+            .unwrap()
+            .0
+            .clone();
+        assert_eq!(gem.id, u32::from_le_bytes([0x67, 0x00, 0x80, 0xC0]));
+        assert_eq!(gem.source, u32::from_le_bytes([0xF0, 0x49, 0x02, 0x80]));
+        assert_eq!(gem.upgrade_type, UpgradeType::Gem);
+        assert_eq!(
+            gem.effects,
+            vec![
+                (0x2FB3BC, String::from("Physical ATK UP +2.7%")),
+                (0x2E7754, String::from("Boosts rally potential +1.8%")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect"))
+            ]
+        );
+        assert_eq!(gem.shape, String::from("Droplet"));
+        let info = gem.info.clone();
+        assert_eq!(info.name, String::from("Tempering Blood Gemstone (1)"));
+        assert_eq!(info.effect, String::from("Physical ATK UP +2.7%"));
+        assert_eq!(info.rating, 4);
+        assert_eq!(info.level, 1);
+        assert_eq!(info.note, String::from(""));
+    }
+
+    #[test]
+    #[ignore] //cargo test -- --include-ignored
+    fn test_parse_upgrades_runtime() {
+        //TESTSAVE 0
+        let handle0 = thread::spawn(|| {
+            let file_data = FileData::build("saves/testsave0").unwrap();
+            let now = Instant::now();
+            parse_upgrades(&file_data).unwrap();
+            let elapsed = now.elapsed().as_millis();
+            assert!(elapsed < 1000);
+        });
+
+        //TESTSAVE 1
+        let handle1 = thread::spawn(|| {
+            let file_data = FileData::build("saves/testsave1").unwrap();
+            let now = Instant::now();
+            parse_upgrades(&file_data).unwrap();
+            let elapsed = now.elapsed().as_millis();
+            assert!(elapsed < 10000);
+        });
+
+        //TESTSAVE 2
+        let handle2 = thread::spawn(|| {
+            let file_data = FileData::build("saves/testsave2").unwrap();
+            let now = Instant::now();
+            parse_upgrades(&file_data).unwrap();
+            let elapsed = now.elapsed().as_millis();
+            assert!(elapsed < 9000);
+        });
+
+        //TESTSAVE 3
+        let handle3 = thread::spawn(|| {
+            let file_data = FileData::build("saves/testsave3").unwrap();
+            let now = Instant::now();
+            parse_upgrades(&file_data).unwrap();
+            let elapsed = now.elapsed().as_millis();
+            assert!(elapsed < 20000);
+        });
+
+        handle0.join().unwrap();
+        handle1.join().unwrap();
+        handle2.join().unwrap();
+        handle3.join().unwrap();
+    }
+
+    #[test]
+    fn upgrade_change_shape() {
+        let mut file_data = FileData::build("saves/testsave3").unwrap();
+        let upgrades1 = parse_upgrades(&file_data).unwrap();
+
+        //Droplet
+        let gem1_1 = upgrades1.get(&3229615809).unwrap().0.clone();
+        //Radial
+        let gem1_2 = upgrades1.get(&3229615814).unwrap().0.clone();
+        //Oath
+        let rune1_1 = upgrades1.get(&3229615805).unwrap().0.clone();
+        //-
+        let rune1_2 = upgrades1.get(&3229615823).unwrap().0.clone();
+
+        //Droplet
+        let mut gem2_1 = upgrades1.get(&3229615809).unwrap().0.clone();
+        //Radial
+        let mut gem2_2 = upgrades1.get(&3229615814).unwrap().0.clone();
+        //Oath
+        let mut rune2_1 = upgrades1.get(&3229615805).unwrap().0.clone();
+        //-
+        let mut rune2_2 = upgrades1.get(&3229615823).unwrap().0.clone();
+
+        //Run the function
+        let result = gem2_1.change_shape(&mut file_data, String::from("Test error"));
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Invalid shape \"Test error\" for a Gem.");
+        }
+        gem2_1
+            .change_shape(&mut file_data, String::from("Waning"))
+            .unwrap();
+        gem2_2
+            .change_shape(&mut file_data, String::from("Triangle"))
+            .unwrap();
+
+        let result = rune2_1.change_shape(&mut file_data, String::from("Test error"));
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Invalid shape \"Test error\" for a Rune.");
+        }
+        rune2_1
+            .change_shape(&mut file_data, String::from("-"))
+            .unwrap();
+        rune2_2
+            .change_shape(&mut file_data, String::from("Oath"))
+            .unwrap();
+
+        //Compare
+        let check = |upgrade_a: Upgrade, upgrade_b: Upgrade| -> bool {
+            (upgrade_a.id == upgrade_b.id)
+                && (upgrade_a.source == upgrade_b.source)
+                && (upgrade_a.upgrade_type == upgrade_b.upgrade_type)
+                && (upgrade_a.effects == upgrade_b.effects)
+                && (upgrade_a.info == upgrade_b.info)
+        };
+        assert_eq!(gem2_1.shape, "Waning");
+        assert!(check(gem1_1, gem2_1.clone()));
+
+        assert_eq!(gem2_2.shape, "Triangle");
+        assert!(check(gem1_2, gem2_2.clone()));
+
+        assert_eq!(rune2_1.shape, "-");
+        assert!(check(rune1_1, rune2_1.clone()));
+
+        assert_eq!(rune2_2.shape, "Oath");
+        assert!(check(rune1_2, rune2_2.clone()));
+
+        let upgrades2 = parse_upgrades(&file_data).unwrap();
+
+        //Waning
+        let gem3_1 = upgrades2.get(&3229615809).unwrap().0.clone();
+        //Triangle
+        let gem3_2 = upgrades2.get(&3229615814).unwrap().0.clone();
+        //-
+        let rune3_1 = upgrades2.get(&3229615805).unwrap().0.clone();
+        //Oath
+        let rune3_2 = upgrades2.get(&3229615823).unwrap().0.clone();
+
+        assert_eq!(gem2_1, gem3_1);
+        assert_eq!(gem2_2, gem3_2);
+        assert_eq!(rune2_1, rune3_1);
+        assert_eq!(rune2_2, rune3_2);
+
+        gem2_1.id = 0;
+        let result = gem2_1.change_shape(&mut file_data, String::from("Radial"));
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Failed to find the upgrade 0x0.");
+        }
+    }
+
+    #[test]
+    fn upgrade_change_effect() {
+        //TESTSAVE 0
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let gem = upgrades.get(&3229614145).unwrap().0.clone();
+        let mut gem2 = gem.clone();
+        let rune = upgrades.get(&3229614146).unwrap().0.clone();
+        let mut rune2 = rune.clone();
+
+        let result = gem2.change_effect(&mut file_data, 0x00, 0);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Failed to find info for the effect 0.");
+        }
+
+        let result = rune2.change_effect(&mut file_data, 0xFFFFFFFF, 9);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "There is no effect 9.");
+        }
+
+        let backup = rune2.id;
+        rune2.id = 0;
+        let result = rune2.change_effect(&mut file_data, 13101, 0);
+        rune2.id = backup;
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Failed to find the upgrade 0x0.");
+        }
+
+        //Change effects
+        gem2.change_effect(&mut file_data, 13101, 1).unwrap();
+        gem2.change_effect(&mut file_data, 14609, 2).unwrap();
+        gem2.change_effect(&mut file_data, 14610, 3).unwrap();
+
+        rune2.change_effect(&mut file_data, 1100000, 1).unwrap();
+        rune2.change_effect(&mut file_data, 2107001, 2).unwrap();
+        rune2.change_effect(&mut file_data, 2108001, 3).unwrap();
+
+        //Compare all but effects
+        let check = |upgrade_a: Upgrade, upgrade_b: Upgrade| -> bool {
+            (upgrade_a.id == upgrade_b.id)
+                && (upgrade_a.source == upgrade_b.source)
+                && (upgrade_a.upgrade_type == upgrade_b.upgrade_type)
+                && (upgrade_a.shape == upgrade_b.shape)
+                && (upgrade_a.info == upgrade_b.info)
+        };
+
+        //Gem
+        assert_eq!(
+            gem2.effects,
+            vec![
+                (0x440c, String::from("Add physical ATK +45")),
+                (13101, String::from("Add blood ATK +0.5")),
+                (14609, String::from("Add arcane ATK +56.3")),
+                (14610, String::from("Add arcane ATK +62.5")),
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45"))
+            ]
+        );
+        assert!(check(gem.clone(), gem2.clone()));
+
+        //Item N0
+        assert_eq!(
+            rune2.effects,
+            vec![
+                (0x115582, String::from("Max QS bullets held UP +3")),
+                (1100000, String::from("More echoes from slain enemies 1")),
+                (2107001, String::from("Vial HP recovery UP")),
+                (2108001, String::from("Cont. heal near death +1")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect"))
+            ]
+        );
+        assert!(check(rune.clone(), rune2.clone()));
+
+        //Check the write to the file data
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let gem3 = upgrades.get(&3229614145).unwrap().0.clone();
+        let rune3 = upgrades.get(&3229614146).unwrap().0.clone();
+        assert_eq!(gem2, gem3);
+        assert_eq!(rune2, rune3);
+
+        //TESTSAVE 0. Test Runes effects on gems and viceversa
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let mut gem = upgrades.get(&3229614145).unwrap().0.clone();
+        let mut rune = upgrades.get(&3229614146).unwrap().0.clone();
+
+        //Change effects
+        rune.change_effect(&mut file_data, 13101, 1).unwrap();
+        rune.change_effect(&mut file_data, 14609, 2).unwrap();
+        rune.change_effect(&mut file_data, 14610, 3).unwrap();
+
+        gem.change_effect(&mut file_data, 1100000, 1).unwrap();
+        gem.change_effect(&mut file_data, 2107001, 2).unwrap();
+        gem.change_effect(&mut file_data, 2108001, 3).unwrap();
+
+        //Gem
+        assert_eq!(
+            gem.effects,
+            vec![
+                (0x440c, String::from("Add physical ATK +45")),
+                (1100000, String::from("More echoes from slain enemies 1")),
+                (2107001, String::from("Vial HP recovery UP")),
+                (2108001, String::from("Cont. heal near death +1")),
+                (0x440c, String::from("Add physical ATK +45")),
+                (0x440c, String::from("Add physical ATK +45"))
+            ]
+        );
+
+        //Item N0
+        assert_eq!(
+            rune.effects,
+            vec![
+                (0x115582, String::from("Max QS bullets held UP +3")),
+                (13101, String::from("Add blood ATK +0.5")),
+                (14609, String::from("Add arcane ATK +56.3")),
+                (14610, String::from("Add arcane ATK +62.5")),
+                (0xffffffff, String::from("No Effect")),
+                (0xffffffff, String::from("No Effect"))
+            ]
+        );
+
+        //Check the write to the file data
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let gem3 = upgrades.get(&3229614145).unwrap().0.clone();
+        let rune3 = upgrades.get(&3229614146).unwrap().0.clone();
+        assert_eq!(gem, gem3);
+        assert_eq!(rune, rune3);
+
+        //Test with value_index 0
+        assert!(rune2.change_effect(&mut file_data, 2108001, 0).is_ok());
+    }
+
+    #[test]
+    fn upgrade_transform() {
+        //TESTSAVE 0
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let mut gem = upgrades.get(&3229614145).unwrap().0.clone();
+        let mut rune = upgrades.get(&3229614146).unwrap().0.clone();
+
+        gem.transform(&mut file_data).unwrap(); //Transform the gem into a rune
+        rune.transform(&mut file_data).unwrap(); //Transform the rune into a gem
+                                                 //Rune -> Gem
+        assert_eq!(rune.id, u32::from_le_bytes([0x42, 0x00, 0x80, 0xC0]));
+        assert_eq!(rune.source, u32::from_le_bytes([0xBF, 0x92, 0x01, 0x80]));
+        assert_eq!(rune.upgrade_type, UpgradeType::Gem);
+        assert_eq!(
+            rune.effects,
+            vec![
+                (13101, String::from("Add blood ATK +0.5")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect"))
+            ]
+        );
+        assert_eq!(rune.shape, String::from("Radial"));
+        let info = rune.info.clone();
+        assert_eq!(info.name, String::from("Bloodtinge Blood Gemstone (1)"));
+        assert_eq!(info.effect, String::from("Add blood ATK +0.5"));
+        assert_eq!(info.rating, 1);
+        assert_eq!(info.level, 1);
+        assert_eq!(info.note, String::from(""));
+
+        //Gem -> Rune
+        assert_eq!(gem.id, u32::from_le_bytes([0x41, 0x00, 0x80, 0xC0]));
+        assert_eq!(gem.source, u32::from_le_bytes([0x26, 0x60, 0x01, 0x80]));
+        assert_eq!(gem.upgrade_type, UpgradeType::Rune);
+        assert_eq!(
+            gem.effects,
+            vec![
+                (1100000, String::from("More echoes from slain enemies 1")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect")),
+                (0xFFFFFFFF, String::from("No Effect"))
+            ]
+        );
+        assert_eq!(gem.shape, String::from("-"));
+        let info = gem.info.clone();
+        assert_eq!(info.name, String::from("Moon"));
+        assert_eq!(
+            info.effect,
+            String::from("More echoes from slain enemies 1")
+        );
+        assert_eq!(info.rating, 0);
+        assert_eq!(info.level, 0);
+        assert_eq!(
+            info.note,
+            String::from("\"Moon\" rune. Acquire more Blood Echoes")
+        );
+
+        //Check if the file_data was modified correctly
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let gem2 = upgrades
+            .get(&u32::from_le_bytes([0x42, 0x00, 0x80, 0xC0]))
+            .unwrap()
+            .0
+            .clone();
+        let rune2 = upgrades
+            .get(&u32::from_le_bytes([0x41, 0x00, 0x80, 0xC0]))
+            .unwrap()
+            .0
+            .clone();
+        assert_eq!(rune, gem2);
+        assert_eq!(gem, rune2);
+
+        gem.id = 0;
+        let result = gem.transform(&mut file_data); //Transform the gem into a rune
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Failed to find the upgrade 0x0.");
+        }
+    }
+
+    #[test]
+    fn test_get_shape() {
+        let result = get_shape(255, UpgradeType::Gem);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Invalid shape [ff, 00, 00, 00].");
+        }
+
+        let result = get_shape(255, UpgradeType::Rune);
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert_eq!(e.to_string(), "Invalid shape [ff, 00, 00, 00].");
+        }
+    }
+
+    #[test]
+    fn upgrade_validate() {
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let mut gem = upgrades[&0xC0800041].0.clone();
+        let mut rune = upgrades[&0xC0800042].0.clone();
+        let rules = |violations: &[Violation]| -> Vec<(UpgradeRule, Option<usize>)> {
+            violations
+                .iter()
+                .map(|v| (v.rule, v.effect_index))
+                .collect()
+        };
+
+        //The gem has the same effect 6 times
+        let violations = gem.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            (1..6)
+                .map(|i| (UpgradeRule::DuplicateEffect, Some(i)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            violations[0].reason,
+            "\"Add physical ATK +45\" is already on the Gem."
+        );
+        assert!(rune.validate(&file_data).unwrap().is_empty());
+
+        let fixed = gem.legitimize(&mut file_data).unwrap();
+        assert_eq!(fixed, violations);
+        assert_eq!(gem.effects.len(), 6);
+        assert_eq!(gem.effects[0].0, 17420);
+        assert!(gem.effects[1..].iter().all(|e| e.0 == NO_EFFECT));
+        assert!(check_bytes(
+            &file_data,
+            file_data.find_upgrade_offset(gem.id).unwrap() + 16,
+            &[0x0C, 0x44, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF]
+        ));
+        assert!(gem.validate(&file_data).unwrap().is_empty());
+        assert!(gem.legitimize(&mut file_data).unwrap().is_empty());
+
+        //A curse before the other effects and one effect too many
+        for (i, effect) in [28215, 17420, 13120, 14620].iter().enumerate() {
+            gem.change_effect(&mut file_data, *effect, i).unwrap();
+        }
+        let violations = gem.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![
+                (UpgradeRule::MisplacedCurse, Some(1)),
+                (UpgradeRule::MisplacedCurse, Some(2)),
+                (UpgradeRule::MisplacedCurse, Some(3)),
+                (UpgradeRule::TooManyEffects, None),
+            ]
+        );
+        gem.legitimize(&mut file_data).unwrap();
+        let effects: Vec<u32> = gem.effects.iter().map(|e| e.0).collect();
+        assert_eq!(
+            effects,
+            [17420, 13120, 28215, NO_EFFECT, NO_EFFECT, NO_EFFECT]
+        );
+        assert_eq!(gem.info.name, "Tempering Abyssal Blood Gem");
+
+        //Low rated gems have 2 effects at most
+        for (i, effect) in [13101, 14101, 15101].iter().enumerate() {
+            gem.change_effect(&mut file_data, *effect, i).unwrap();
+        }
+        let violations = gem.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![(UpgradeRule::TooManyEffects, None)]
+        );
+        assert_eq!(
+            violations[0].reason,
+            "A gem of rating 1 has at most 2 effects, this one has 3."
+        );
+
+        //A rune with the shape of an oath rune and an effect of another rune
+        rune.change_shape(&mut file_data, String::from("Oath"))
+            .unwrap();
+        rune.change_effect(&mut file_data, 1100000, 1).unwrap();
+        let violations = rune.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![
+                (UpgradeRule::MixedRune, Some(1)),
+                (UpgradeRule::WrongShape, None)
+            ]
+        );
+        rune.legitimize(&mut file_data).unwrap();
+        assert_eq!(rune.shape, "-");
+        assert_eq!(rune.effects[1].0, NO_EFFECT);
+
+        //Only gem effects, nothing can be kept
+        rune.change_effect(&mut file_data, 17420, 0).unwrap();
+        let violations = rune.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![
+                (UpgradeRule::WrongEffectType, Some(0)),
+                (UpgradeRule::NoEffects, None)
+            ]
+        );
+        let result = rune.legitimize(&mut file_data);
+        assert_eq!(result.unwrap_err().kind(), "UnfixableUpgrade");
+    }
+
+    #[test]
+    fn upgrade_validate_shape_and_source() {
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
+        let upgrades = parse_upgrades(&file_data).unwrap();
+        let mut gem = upgrades[&0xC0800041].0.clone();
+        let rules = |violations: &[Violation]| -> Vec<(UpgradeRule, Option<usize>)> {
+            violations
+                .iter()
+                .map(|v| (v.rule, v.effect_index))
+                .collect()
+        };
+        for i in 1..6 {
+            gem.change_effect(&mut file_data, NO_EFFECT, i).unwrap();
+        }
+
+        //The count of effects goes by the best rated one, not the first
+        for (i, effect) in [13101, 14120, 15101].iter().enumerate() {
+            gem.change_effect(&mut file_data, *effect, i).unwrap();
+        }
+        assert!(gem.validate(&file_data).unwrap().is_empty());
+
+        //Circle gems don't drop with fire or arcane
+        gem.change_shape(&mut file_data, String::from("Circle"))
+            .unwrap();
+        let violations = gem.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![
+                (UpgradeRule::ShapeEffect, Some(1)),
+                (UpgradeRule::ShapeEffect, Some(2))
+            ]
+        );
+        assert_eq!(
+            violations[1].reason,
+            "\"Add fire ATK +1\" doesn't drop on Circle gems."
+        );
+        gem.legitimize(&mut file_data).unwrap();
+        let effects: Vec<u32> = gem.effects.iter().map(|e| e.0).collect();
+        assert_eq!(
+            effects,
+            [13101, NO_EFFECT, NO_EFFECT, NO_EFFECT, NO_EFFECT, NO_EFFECT]
+        );
+
+        //The effects of the Red Blood Gem only drop on it
+        gem.change_effect(&mut file_data, 3126204, 1).unwrap();
+        let violations = gem.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![(UpgradeRule::WrongSource, Some(1))]
+        );
+        assert_eq!(
+            violations[0].reason,
+            "\"Physical ATK UP +2.7%\" only drops on the Red Blood Gem."
+        );
+
+        //The Red Blood Gem is a Droplet with its two effects
+        gem.change_source(&mut file_data, 0x800249F0).unwrap();
+        gem.change_effect(&mut file_data, 3045204, 2).unwrap();
+        let violations = gem.validate(&file_data).unwrap();
+        assert_eq!(
+            rules(&violations),
+            vec![
+                (UpgradeRule::WrongShape, None),
+                (UpgradeRule::WrongSource, Some(0))
+            ]
+        );
+        assert_eq!(
+            violations[1].reason,
+            "The Red Blood Gem doesn't drop with \"Add blood ATK +0.5\"."
+        );
+        gem.legitimize(&mut file_data).unwrap();
+        assert_eq!(gem.shape, "Droplet");
+        let effects: Vec<u32> = gem.effects.iter().map(|e| e.0).collect();
+        assert_eq!(
+            effects,
+            [3126204, 3045204, NO_EFFECT, NO_EFFECT, NO_EFFECT, NO_EFFECT]
+        );
+        assert!(gem.validate(&file_data).unwrap().is_empty());
+    }
+}
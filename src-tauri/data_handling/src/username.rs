@@ -1,3 +1,4 @@
+//! Character name.
 use super::{enums::Error, file::FileData};
 
 use serde::{Deserialize, Serialize};
@@ -20,11 +21,12 @@ impl Username {
         Username { string }
     }
 
+    /// Writes a new name, it has to fit in the 16 characters the game reserves for it.
     pub fn set(&mut self, file_data: &mut FileData, username: String) -> Result<(), Error> {
         if !(1..=16).contains(&username.len()) {
-            return Err(Error::CustomError(
-                "The new username must have between 1 and 16 characters.",
-            ));
+            return Err(Error::InvalidUsernameLength {
+                length: username.len(),
+            });
         }
         let start = file_data.offsets.username + 1;
         let username_bytes = username.as_bytes();
@@ -42,32 +44,31 @@ impl Username {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     #[test]
     fn username_build() {
         //testsave0
-        let file_data = FileData::build("saves/testsave0", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave0").unwrap();
         let username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Proyectito"));
 
         //testsave1
-        let file_data = FileData::build("saves/testsave1", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave1").unwrap();
         let username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Toe Taster"));
 
         //testsave2
-        let file_data = FileData::build("saves/testsave2", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave2").unwrap();
         let username = Username::build(&file_data);
         assert_eq!(username.string, String::from("I'm Here To Help"));
 
         //testsave3
-        let file_data = FileData::build("saves/testsave3", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave3").unwrap();
         let username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Yeezy"));
 
         //testsave6
-        let file_data = FileData::build("saves/testsave6", PathBuf::from("resources")).unwrap();
+        let file_data = FileData::build("saves/testsave6").unwrap();
         let username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Touch Me"));
     }
@@ -75,7 +76,7 @@ mod tests {
     #[test]
     fn username_set() {
         //testsave0
-        let mut file_data = FileData::build("saves/testsave0", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
         let mut username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Proyectito"));
         username
@@ -86,7 +87,7 @@ mod tests {
         assert_eq!(username.string, String::from("testsave0"));
 
         //testsave1
-        let mut file_data = FileData::build("saves/testsave1", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave1").unwrap();
         let mut username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Toe Taster"));
         username
@@ -97,7 +98,7 @@ mod tests {
         assert_eq!(username.string, String::from("testsave1"));
 
         //testsave2
-        let mut file_data = FileData::build("saves/testsave2", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave2").unwrap();
         let mut username = Username::build(&file_data);
         assert_eq!(username.string, String::from("I'm Here To Help"));
         username
@@ -108,7 +109,7 @@ mod tests {
         assert_eq!(username.string, String::from("testsave2"));
 
         //testsave3
-        let mut file_data = FileData::build("saves/testsave3", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave3").unwrap();
         let mut username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Yeezy"));
         username
@@ -119,7 +120,7 @@ mod tests {
         assert_eq!(username.string, String::from("testsave3"));
 
         //testsave6
-        let mut file_data = FileData::build("saves/testsave6", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave6").unwrap();
         let mut username = Username::build(&file_data);
         assert_eq!(username.string, String::from("Touch Me"));
         username
@@ -130,7 +131,7 @@ mod tests {
         assert_eq!(username.string, String::from("testsave6"));
 
         //Special cases
-        let mut file_data = FileData::build("saves/testsave0", PathBuf::from("resources")).unwrap();
+        let mut file_data = FileData::build("saves/testsave0").unwrap();
         let mut username = Username::build(&file_data);
         //Test using 0 characters
         let result = username.set(&mut file_data, String::from(""));
@@ -138,7 +139,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: The new username must have between 1 and 16 characters."
+                "The new username must have between 1 and 16 characters, got 0."
             );
         }
         //Test using 17 characters
@@ -147,7 +148,7 @@ mod tests {
         if let Err(error) = result {
             assert_eq!(
                 error.to_string(),
-                "Save error: The new username must have between 1 and 16 characters."
+                "The new username must have between 1 and 16 characters, got 17."
             );
         }
         //Test using 1 characters
@@ -1,14 +1,14 @@
+//! Helpers shared by the tests.
 #[cfg(test)]
 pub mod test_utils {
-    use crate::data_handling::{file::FileData, save::SaveData};
-    use std::path::PathBuf;
+    use crate::{file::FileData, save::SaveData};
 
     pub fn build_save_data(save_path: &str) -> SaveData {
-        SaveData::build(&format!("saves/{}", save_path), PathBuf::from("resources")).unwrap()
+        SaveData::build(&format!("saves/{}", save_path)).unwrap()
     }
 
     pub fn build_file_data(save_path: &str) -> FileData {
-        FileData::build(&format!("saves/{}", save_path), PathBuf::from("resources")).unwrap()
+        FileData::build(&format!("saves/{}", save_path)).unwrap()
     }
 
     pub fn check_bytes(file_data: &FileData, index: usize, bytes: &[u8]) -> bool {
@@ -19,7 +19,7 @@ pub mod test_utils {
                 break;
             }
         }
-        if equal == false {
+        if !equal {
             println!("check_bytes failed:");
             for (i, byte) in bytes.iter().enumerate() {
                 let equal = file_data.bytes[index + i] == *byte;
@@ -1,15 +0,0 @@
-pub mod appearance;
-pub mod article;
-pub mod bosses;
-pub mod constants;
-pub mod enums;
-pub mod file;
-pub mod inventory;
-pub mod offsets;
-pub mod position;
-pub mod save;
-pub mod slots;
-pub mod stats;
-pub mod upgrades;
-pub mod username;
-pub mod utils;
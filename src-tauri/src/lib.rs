@@ -1,23 +1,36 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{error::Error, fs::File, io::BufReader, sync::Mutex};
-mod data_handling;
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    path::PathBuf,
+    sync::Mutex,
+};
 
 use data_handling::{
     appearance,
     article::Article,
-    enums::{ArticleType, Location, SlotShape, UpgradeType},
+    backup::{self, Backup, Retention},
+    enums::{ArticleType, EquipSlot, Error, Imprint, Location, Origin, SlotShape, UpgradeType},
+    file::FileData,
+    patch::{self, SaveDiff},
+    presets::{self, Preset},
+    resources,
     save::SaveData,
+    sheet,
+    stats::Attributes,
     upgrades::Upgrade,
 };
 use serde_json::{json, Value};
-use tauri::{path::BaseDirectory, Manager};
+use tauri::Manager;
 struct MutexSave {
     data: Mutex<Option<SaveData>>,
+    path: Mutex<Option<String>>,
+    retention: Mutex<Retention>,
 }
 
-pub fn run() -> Result<(), Box<dyn Error>> {
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(target_os = "linux")]
     {
         if std::env::var("WEBKIT_DISABLE_DMABUF_RENDERER").is_err() {
@@ -35,6 +48,8 @@ pub fn run() -> Result<(), Box<dyn Error>> {
         .plugin(tauri_plugin_shell::init())
         .manage(MutexSave {
             data: Mutex::new(None),
+            path: Mutex::new(None),
+            retention: Mutex::new(Retention::default()),
         })
         .invoke_handler(tauri::generate_handler![
             make_save,
@@ -47,25 +62,65 @@ pub fn run() -> Result<(), Box<dyn Error>> {
             return_rune_effects,
             transform_item,
             edit_stat,
+            get_derived_stats,
+            get_defense,
+            equip,
+            unequip,
+            assign_quick_item,
+            clear_quick_item,
+            assign_shortcut,
+            clear_shortcut,
+            ready_belt,
+            set_attributes,
+            respec,
+            change_origin,
+            list_presets,
+            save_preset,
+            delete_preset,
+            apply_preset,
             edit_effect,
             edit_shape,
             equip_gem,
             unequip_gem,
+            remove_article,
+            move_article,
             export_appearance,
             import_appearance,
+            export_sheet,
+            import_sheet,
+            diff_save,
+            apply_patch,
             set_username,
             get_version,
             add_item,
+            add_weapon,
+            add_armor,
+            create_upgrade,
+            destroy_upgrade,
+            collect_orphaned_upgrades,
+            validate,
+            validate_upgrades,
+            legitimize_upgrade,
             edit_slot,
             get_isz,
             fix_isz,
             get_playtime,
             set_playtime,
             set_flag,
+            set_boss_dead,
+            get_flags,
+            set_named_flag,
             edit_coordinates,
             teleport,
             change_weapon_level,
-            apply_mask
+            apply_mask,
+            undo,
+            redo,
+            history,
+            list_backups,
+            diff_backup,
+            restore_backup,
+            set_backup_retention
         ])
         .run(tauri::generate_context!())?;
 
@@ -76,16 +131,59 @@ pub fn run() -> Result<(), Box<dyn Error>> {
 fn set_flag(offset: usize, new_value: u8, state_save: tauri::State<MutexSave>) {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     save.file.set_flag(offset, new_value);
+    save.commit(checkpoint, "set_flag");
+}
+
+#[tauri::command]
+fn set_boss_dead(
+    name: String,
+    dead: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.set_boss_dead(&name, dead).map(|boss| boss.clone());
+    save.commit(checkpoint, "set_boss_dead");
+
+    Ok(serde_json::to_value(result?)?)
+}
+
+#[tauri::command]
+fn get_flags(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+    Ok(serde_json::to_value(save.flags()?)?)
+}
+
+#[tauri::command]
+fn set_named_flag(
+    name: String,
+    state: String,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.set_named_flag(&name, &state);
+    save.commit(checkpoint, "set_named_flag");
+
+    Ok(serde_json::to_value(result?)?)
 }
 
 #[tauri::command]
 fn apply_mask(offset: usize, mask: u8, state_save: tauri::State<MutexSave>) {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     save.file.apply_mask(offset, mask);
+    save.commit(checkpoint, "apply_mask");
 }
 
 #[tauri::command]
@@ -100,8 +198,11 @@ fn get_isz(state_save: tauri::State<MutexSave>) -> [u8; 2] {
 fn fix_isz(state_save: tauri::State<MutexSave>) -> String {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
-    save.file.fix_isz()
+    let message = save.file.fix_isz();
+    save.commit(checkpoint, "fix_isz");
+    message
 }
 
 #[tauri::command]
@@ -116,28 +217,23 @@ fn get_playtime(state_save: tauri::State<MutexSave>) -> u32 {
 fn set_playtime(new_playtime: [u8; 4], state_save: tauri::State<MutexSave>) {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     save.file.set_playtime(new_playtime);
+    save.commit(checkpoint, "set_playtime");
 }
 
 #[tauri::command]
-fn make_save(
-    path: &str,
-    state_save: tauri::State<MutexSave>,
-    handle: tauri::AppHandle,
-) -> Result<Value, String> {
-    let resource_path = handle
-        .path()
-        .resolve("resources/", BaseDirectory::Resource)
-        .unwrap();
-
-    match SaveData::build(path, resource_path) {
+fn make_save(path: &str, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let retention = state_save.retention.lock().unwrap().clone();
+    match SaveData::build_with_retention(path, &retention) {
         Ok(s) => {
             let mut data = state_save.data.lock().unwrap();
             *data = Some(s.clone());
-            Ok(serde_json::to_value(&s).map_err(|x| x.to_string())?)
+            *state_save.path.lock().unwrap() = Some(path.to_string());
+            Ok(serde_json::to_value(&s)?)
         }
-        Err(_) => Err("Failed to load file, make sure its a decrypted character.".to_string()),
+        Err(e) => Err(e),
     }
 }
 
@@ -148,37 +244,34 @@ fn edit_quantity(
     value: u32,
     is_storage: bool,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
-    if !is_storage {
-        match save
-            .inventory
+    let result = if !is_storage {
+        save.inventory
             .edit_item(&mut save.file, number, id, value, is_storage)
-        {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(e) => Err(e.to_string()),
-        }
     } else {
-        match save
-            .storage
+        save.storage
             .edit_item(&mut save.file, number, id, value, is_storage)
-        {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(e) => Err(e.to_string()),
-        }
+    };
+    save.commit(checkpoint, "edit_quantity");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
 #[tauri::command]
-fn save(path: String, state_save: tauri::State<MutexSave>) -> Result<&str, &str> {
+fn save(path: String, state_save: tauri::State<MutexSave>) -> Result<&str, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
 
-    match save.file.save(&path) {
+    match save.save(&path) {
         Ok(_) => Ok("Changes saved."),
-        Err(_) => Err("Failed to save changes."),
+        Err(e) => Err(e),
     }
 }
 
@@ -186,7 +279,7 @@ fn save(path: String, state_save: tauri::State<MutexSave>) -> Result<&str, &str>
 fn return_weapons(state_save: tauri::State<MutexSave>) -> Value {
     let save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_ref().unwrap();
-    let weapons_str = include_str!("../resources/weapons.json");
+    let weapons_str = resources::WEAPONS;
 
     let weapons: Value = serde_json::from_str(weapons_str).unwrap();
 
@@ -197,7 +290,7 @@ fn return_weapons(state_save: tauri::State<MutexSave>) -> Value {
 fn return_armors(state_save: tauri::State<MutexSave>) -> Value {
     let save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_ref().unwrap();
-    let armors_str = include_str!("../resources/armors.json");
+    let armors_str = resources::ARMORS;
 
     let armors: Value = serde_json::from_str(armors_str).unwrap();
 
@@ -208,7 +301,7 @@ fn return_armors(state_save: tauri::State<MutexSave>) -> Value {
 fn return_items(state_save: tauri::State<MutexSave>) -> Value {
     let save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_ref().unwrap();
-    let upgrades_str = include_str!("../resources/items.json");
+    let upgrades_str = resources::ITEMS;
 
     let items: Value = serde_json::from_str(upgrades_str).unwrap();
 
@@ -219,7 +312,7 @@ fn return_items(state_save: tauri::State<MutexSave>) -> Value {
 fn return_gem_effects(state_save: tauri::State<MutexSave>) -> Value {
     let save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_ref().unwrap();
-    let upgrades_str = include_str!("../resources/upgrades.json");
+    let upgrades_str = resources::UPGRADES;
 
     let upgrade_json: Value = serde_json::from_str(upgrades_str).unwrap();
 
@@ -230,7 +323,7 @@ fn return_gem_effects(state_save: tauri::State<MutexSave>) -> Value {
 fn return_rune_effects(state_save: tauri::State<MutexSave>) -> Value {
     let save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_ref().unwrap();
-    let upgrades_str = include_str!("../resources/upgrades.json");
+    let upgrades_str = resources::UPGRADES;
 
     let upgrade_json: Value = serde_json::from_str(upgrades_str).unwrap();
 
@@ -245,9 +338,10 @@ fn transform_item(
     article_type: ArticleType,
     is_storage: bool,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     let category = {
         if !is_storage {
@@ -263,31 +357,37 @@ fn transform_item(
 
     let old_type = item.article_type;
 
-    match item.transform(&mut save.file, new_id, is_storage) {
-        Ok(_) => {
-            // Check if the article type has changed
-            if item.article_type != old_type {
-                let moved_item = item.clone();
-
-                // Remove the item from the old category
-                if let Some(old_category) = save.inventory.articles.get_mut(&old_type) {
-                    old_category.retain(|x| x.index != index);
-                }
-
-                // Find or create the new category using item.article_type
-                let new_category = save
-                    .inventory
-                    .articles
-                    .entry(moved_item.article_type)
-                    .or_insert_with(Vec::new);
-
-                // Add the item to the new category
-                new_category.push(moved_item);
-            }
-
-            Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?)
+    let result = item.transform(&mut save.file, new_id, is_storage);
+
+    // Check if the article type has changed
+    if result.is_ok() && item.article_type != old_type {
+        let moved_item = item.clone();
+
+        let inventory = if is_storage {
+            &mut save.storage
+        } else {
+            &mut save.inventory
+        };
+
+        // Remove the item from the old category
+        if let Some(old_category) = inventory.articles.get_mut(&old_type) {
+            old_category.retain(|x| x.index != index);
         }
-        Err(e) => Err(e.to_string()),
+
+        // Find or create the new category using item.article_type
+        let new_category = inventory
+            .articles
+            .entry(moved_item.article_type)
+            .or_insert_with(Vec::new);
+
+        // Add the item to the new category
+        new_category.push(moved_item);
+    }
+    save.commit(checkpoint, "transform_item");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
@@ -298,11 +398,241 @@ fn edit_stat(
     times: usize,
     value: u32,
     state_save: tauri::State<MutexSave>,
-) {
+) -> Result<(), Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    //Known stats go through the model, which keeps the attributes above the origin
+    let name = save
+        .stats
+        .iter()
+        .find(|s| s.rel_offset == rel_offset)
+        .map(|s| s.name.clone());
+    match name {
+        Some(name) => {
+            save.edit_stat(&name, value)?;
+        }
+        None => save.file.edit(rel_offset, length, times, value),
+    }
+    save.commit(checkpoint, "edit_stat");
+    Ok(())
+}
+
+#[tauri::command]
+fn get_defense(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+    Ok(serde_json::to_value(save.defense()?)?)
+}
+
+#[tauri::command]
+fn equip(
+    slot: EquipSlot,
+    article_type: ArticleType,
+    article_index: usize,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.equip(slot, article_type, article_index);
+    save.commit(checkpoint, "equip");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn unequip(slot: EquipSlot, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.unequip(slot);
+    save.commit(checkpoint, "unequip");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn assign_quick_item(
+    slot: usize,
+    article_index: usize,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
-    save.file.edit(rel_offset, length, times, value);
+    let result = save.assign_quick_item(slot, article_index);
+    save.commit(checkpoint, "assign_quick_item");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn clear_quick_item(slot: usize, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.clear_quick_item(slot);
+    save.commit(checkpoint, "clear_quick_item");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn assign_shortcut(
+    slot: usize,
+    article_index: usize,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.assign_shortcut(slot, article_index);
+    save.commit(checkpoint, "assign_shortcut");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn clear_shortcut(slot: usize, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.clear_shortcut(slot);
+    save.commit(checkpoint, "clear_shortcut");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn ready_belt(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.ready_belt();
+    save.commit(checkpoint, "ready_belt");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn get_derived_stats(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+    Ok(serde_json::to_value(save.derived_stats()?)?)
+}
+
+#[tauri::command]
+fn set_attributes(
+    attributes: Attributes,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.set_attributes(&attributes);
+    save.commit(checkpoint, "set_attributes");
+
+    Ok(json!({ "derived": result?, "save": save }))
+}
+
+#[tauri::command]
+fn respec(
+    attributes: Attributes,
+    level: u32,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.respec(&attributes, level);
+    save.commit(checkpoint, "respec");
+
+    Ok(json!({ "derived": result?, "save": save }))
+}
+
+#[tauri::command]
+fn change_origin(origin: Origin, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.change_origin(origin);
+    save.commit(checkpoint, "change_origin");
+
+    Ok(json!({ "derived": result?, "save": save }))
+}
+
+//Presets are shared by every save, they are kept in the config folder of the app
+fn presets_path(app: &tauri::AppHandle) -> Result<PathBuf, Error> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| Error::IoError(io::Error::new(io::ErrorKind::NotFound, e.to_string())))?;
+    Ok(dir.join(presets::PRESETS_FILE))
+}
+
+#[tauri::command]
+fn list_presets(app: tauri::AppHandle) -> Result<Vec<Preset>, Error> {
+    presets::load(&presets_path(&app)?)
+}
+
+#[tauri::command]
+fn save_preset(preset: Preset, app: tauri::AppHandle) -> Result<Vec<Preset>, Error> {
+    presets::save(&presets_path(&app)?, preset)
+}
+
+#[tauri::command]
+fn delete_preset(name: &str, app: tauri::AppHandle) -> Result<Vec<Preset>, Error> {
+    presets::delete(&presets_path(&app)?, name)
+}
+
+#[tauri::command]
+fn apply_preset(
+    name: &str,
+    app: tauri::AppHandle,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let preset = presets::find(&presets_path(&app)?, name)?;
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.apply_preset(&preset);
+    save.commit(checkpoint, "apply_preset");
+
+    Ok(json!({ "derived": result?, "save": save }))
 }
 
 #[tauri::command]
@@ -311,9 +641,10 @@ fn edit_effect(
     index: usize,
     info: Value,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
     let upgrade: Option<*mut Upgrade>;
 
     let location: Location = {
@@ -346,11 +677,12 @@ fn edit_effect(
             .map(|u| u as *mut _);
     }
 
-    unsafe {
-        match (*upgrade.unwrap()).change_effect(&mut save.file, new_effect_id, index) {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(_) => Err("Failed to edit the upgrade's effect".to_string()),
-        }
+    let result = unsafe { (*upgrade.unwrap()).change_effect(&mut save.file, new_effect_id, index) };
+    save.commit(checkpoint, "edit_effect");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
@@ -359,9 +691,10 @@ fn edit_shape(
     new_shape: String,
     info: Value,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
     let upgrade: Option<*mut Upgrade>;
 
     let location: Location = {
@@ -394,11 +727,12 @@ fn edit_shape(
             .map(|u| u as *mut _);
     }
 
-    unsafe {
-        match (*upgrade.unwrap()).change_shape(&mut save.file, new_shape) {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(_) => Err("Failed to edit the upgrade's shape".to_string()),
-        }
+    let result = unsafe { (*upgrade.unwrap()).change_shape(&mut save.file, new_shape) };
+    save.commit(checkpoint, "edit_shape");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
@@ -410,9 +744,10 @@ fn edit_slot(
     slot_index: usize,
     new_shape: SlotShape,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     let location = if is_storage {
         Location::Storage
@@ -425,11 +760,13 @@ fn edit_slot(
         .get_article_mut(location, article_type, article_index)
         .map(|u| u as *mut _);
 
-    unsafe {
-        match (*article.unwrap()).change_slot_shape(&mut save.file, slot_index, new_shape) {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(e) => Err(e.to_string()),
-        }
+    let result =
+        unsafe { (*article.unwrap()).change_slot_shape(&mut save.file, slot_index, new_shape) };
+    save.commit(checkpoint, "edit_slot");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
@@ -441,9 +778,10 @@ fn equip_gem(
     slot_index: usize,
     is_storage: bool,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     let result = if is_storage {
         save.storage.equip_gem(
@@ -464,10 +802,11 @@ fn equip_gem(
             is_storage,
         )
     };
+    save.commit(checkpoint, "equip_gem");
 
     match result {
-        Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-        Err(e) => Err(e.to_string()),
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
@@ -478,9 +817,10 @@ fn unequip_gem(
     slot_index: usize,
     is_storage: bool,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     let result = if is_storage {
         save.storage.unequip_gem(
@@ -499,46 +839,199 @@ fn unequip_gem(
             is_storage,
         )
     };
+    save.commit(checkpoint, "unequip_gem");
 
     match result {
-        Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-        Err(e) => Err(e.to_string()),
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
 #[tauri::command]
-fn export_appearance(path: &str, state_save: tauri::State<MutexSave>) -> Result<String, String> {
+fn remove_article(
+    article_type: ArticleType,
+    article_index: usize,
+    is_storage: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.remove_article(Location::from(is_storage), article_type, article_index);
+    save.commit(checkpoint, "remove_article");
+
+    result?;
+    Ok(serde_json::to_value(&save)?)
+}
+
+#[tauri::command]
+fn destroy_upgrade(id: u32, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.destroy_upgrade(id);
+    save.commit(checkpoint, "destroy_upgrade");
+
+    result?;
+    Ok(serde_json::to_value(&save)?)
+}
+
+#[tauri::command]
+fn collect_orphaned_upgrades(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.collect_orphaned_upgrades();
+    save.commit(checkpoint, "collect_orphaned_upgrades");
+
+    Ok(json!({ "removed": result?, "save": save }))
+}
+
+#[tauri::command]
+fn validate(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+    Ok(serde_json::to_value(save.validate()?)?)
+}
+
+#[tauri::command]
+fn validate_upgrades(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+    Ok(serde_json::to_value(save.validate_upgrades()?)?)
+}
+
+#[tauri::command]
+fn legitimize_upgrade(id: u32, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.legitimize_upgrade(id);
+    save.commit(checkpoint, "legitimize_upgrade");
+
+    Ok(json!({ "fixed": result?, "save": save }))
+}
+
+#[tauri::command]
+fn move_article(
+    article_type: ArticleType,
+    article_index: usize,
+    quantity: u32,
+    is_storage: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.move_article(
+        Location::from(is_storage),
+        article_type,
+        article_index,
+        quantity,
+    );
+    save.commit(checkpoint, "move_article");
+
+    result?;
+    Ok(serde_json::to_value(&save)?)
+}
+
+#[tauri::command]
+fn export_appearance(path: &str, state_save: tauri::State<MutexSave>) -> Result<String, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
 
     match appearance::export(&save.file, path) {
         Ok(_) => Ok("Successfully exported".to_string()),
-        Err(_) => Err("There was an error exporting the face".to_string()),
+        Err(e) => Err(e),
     }
 }
 
 #[tauri::command]
-fn import_appearance(path: &str, state_save: tauri::State<MutexSave>) -> Result<String, String> {
+fn import_appearance(path: &str, state_save: tauri::State<MutexSave>) -> Result<String, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = appearance::import(&mut save.file, path);
+    save.commit(checkpoint, "import_appearance");
 
-    match appearance::import(&mut save.file, path) {
+    match result {
         Ok(_) => Ok("Successfully imported".to_string()),
-        Err(_) => Err("The imported file is not a face".to_string()),
+        Err(e) => Err(e),
     }
 }
 
+#[tauri::command]
+fn export_sheet(path: &str, state_save: tauri::State<MutexSave>) -> Result<String, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+
+    sheet::export(save, path)?;
+    Ok("Successfully exported".to_string())
+}
+
+#[tauri::command]
+fn import_sheet(path: &str, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = sheet::import(save, path);
+    save.commit(checkpoint, "import_sheet");
+
+    let report = result?;
+    Ok(json!({
+        "save": serde_json::to_value(&save)?,
+        "report": report
+    }))
+}
+
+#[tauri::command]
+fn diff_save(path: &str, state_save: tauri::State<MutexSave>) -> Result<SaveDiff, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+
+    //The other save is only read, no backup is made
+    let other = SaveData::from_file(FileData::from_bytes(std::fs::read(path)?)?)?;
+    patch::diff(&other, save)
+}
+
+#[tauri::command]
+fn apply_patch(
+    path: &str,
+    force: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    patch::import(save, path, force)?;
+    save.commit(checkpoint, "apply_patch");
+
+    Ok(serde_json::to_value(&save)?)
+}
+
 #[tauri::command]
 fn set_username(
     new_username: String,
     state_save: tauri::State<MutexSave>,
-) -> Result<String, String> {
+) -> Result<String, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.username.set(&mut save.file, new_username);
+    save.commit(checkpoint, "set_username");
 
-    match save.username.set(&mut save.file, new_username) {
+    match result {
         Ok(_) => Ok("Successfully changed name".to_string()),
-        Err(_) => Err("Failed to change name".to_string()),
+        Err(e) => Err(e),
     }
 }
 
@@ -553,48 +1046,111 @@ fn add_item(
     quantity: u32,
     is_storage: bool,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
-    if !is_storage {
-        match save
-            .inventory
+    let result = if !is_storage {
+        save.inventory
             .add_item(&mut save.file, id, quantity, is_storage)
-        {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(_) => Err("Failed to add the item".to_string()),
-        }
+            .map(|_| ())
     } else {
-        match save
-            .storage
+        save.storage
             .add_item(&mut save.file, id, quantity, is_storage)
-        {
-            Ok(_) => Ok(serde_json::to_value(&save).map_err(|x| x.to_string())?),
-            Err(_) => Err("Failed to add the item".to_string()),
-        }
+            .map(|_| ())
+    };
+    save.commit(checkpoint, "add_item");
+
+    match result {
+        Ok(_) => Ok(serde_json::to_value(&save)?),
+        Err(e) => Err(e),
     }
 }
 
+#[tauri::command]
+fn add_weapon(
+    id: u32,
+    imprint: Option<Imprint>,
+    upgrade_level: u8,
+    shapes: Vec<SlotShape>,
+    is_storage: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.add_weapon(
+        Location::from(is_storage),
+        id,
+        imprint,
+        upgrade_level,
+        &shapes,
+    );
+    save.commit(checkpoint, "add_weapon");
+
+    result?;
+    Ok(serde_json::to_value(&save)?)
+}
+
+#[tauri::command]
+fn add_armor(
+    id: u32,
+    is_storage: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let result = save.add_armor(Location::from(is_storage), id);
+    save.commit(checkpoint, "add_armor");
+
+    result?;
+    Ok(serde_json::to_value(&save)?)
+}
+
+#[tauri::command]
+fn create_upgrade(
+    upgrade_type: UpgradeType,
+    shape: String,
+    effects: [u32; 6],
+    source: u32,
+    is_storage: bool,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
+
+    let location = Location::from(is_storage);
+    let result = save.create_upgrade(location, upgrade_type, &shape, effects, source);
+    save.commit(checkpoint, "create_upgrade");
+
+    result?;
+    Ok(serde_json::to_value(&save)?)
+}
+
 #[tauri::command]
 fn edit_coordinates(x: f32, y: f32, z: f32, state_save: tauri::State<MutexSave>) {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     save.position.coordinates.edit(&mut save.file, x, y, z);
+    save.commit(checkpoint, "edit_coordinates");
 }
 
 #[tauri::command]
 fn teleport(x: f32, y: f32, z: f32, map_id: Vec<u8>, state_save: tauri::State<MutexSave>) {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save: &mut SaveData = save_option.as_mut().unwrap();
-    let le_map = [00, 00, map_id[1], map_id[0]];
+    let checkpoint = save.checkpoint();
 
-    for (i, j) in (0x04..0x08).enumerate() {
-        save.file.bytes[j] = le_map[i];
-    }
-
-    save.position.coordinates.edit(&mut save.file, x, y, z);
+    save.position
+        .teleport(&mut save.file, x, y, z, [map_id[0], map_id[1]]);
+    save.commit(checkpoint, "teleport");
 }
 
 #[tauri::command]
@@ -605,9 +1161,10 @@ fn change_weapon_level(
     is_storage: bool,
     level: u8,
     state_save: tauri::State<MutexSave>,
-) -> Result<Value, String> {
+) -> Result<Value, Error> {
     let mut save_option = state_save.inner().data.lock().unwrap();
     let save: &mut SaveData = save_option.as_mut().unwrap();
+    let checkpoint = save.checkpoint();
 
     let result = if is_storage {
         save.storage.change_weapon_level(
@@ -628,12 +1185,94 @@ fn change_weapon_level(
             level,
         )
     };
+    save.commit(checkpoint, "change_weapon_level");
 
     match result {
         Ok(weapon) => Ok(json!({
-            "save": serde_json::to_value(&save).map_err(|x| x.to_string())?,
+            "save": serde_json::to_value(&save)?,
             "weapon": weapon
         })),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e),
+    }
+}
+
+#[tauri::command]
+fn undo(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+
+    let label = save.undo()?;
+    Ok(json!({
+        "save": serde_json::to_value(&save)?,
+        "undone": label
+    }))
+}
+
+#[tauri::command]
+fn redo(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_mut().unwrap();
+
+    let label = save.redo()?;
+    Ok(json!({
+        "save": serde_json::to_value(&save)?,
+        "redone": label
+    }))
+}
+
+#[tauri::command]
+fn history(state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+
+    Ok(serde_json::to_value(&save.history)?)
+}
+
+#[tauri::command]
+fn list_backups(state_save: tauri::State<MutexSave>) -> Result<Vec<Backup>, Error> {
+    let path_option = state_save.inner().path.lock().unwrap();
+    let path = path_option.as_ref().unwrap();
+
+    backup::list(path)
+}
+
+#[tauri::command]
+fn diff_backup(id: &str, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let path_option = state_save.inner().path.lock().unwrap();
+    let path = path_option.as_ref().unwrap();
+    let save_option = state_save.inner().data.lock().unwrap();
+    let save = save_option.as_ref().unwrap();
+
+    let changes = backup::diff_with(path, id, &save.file.bytes)?;
+    Ok(serde_json::to_value(changes)?)
+}
+
+#[tauri::command]
+fn restore_backup(id: &str, state_save: tauri::State<MutexSave>) -> Result<Value, Error> {
+    let path_option = state_save.inner().path.lock().unwrap();
+    let path = path_option.as_ref().unwrap();
+    let retention = state_save.retention.lock().unwrap().clone();
+
+    backup::restore(path, id, &retention)?;
+    let restored = SaveData::build_with_retention(path, &retention)?;
+
+    let mut save_option = state_save.inner().data.lock().unwrap();
+    *save_option = Some(restored.clone());
+    Ok(serde_json::to_value(&restored)?)
+}
+
+#[tauri::command]
+fn set_backup_retention(
+    keep: usize,
+    max_age_days: Option<u64>,
+    state_save: tauri::State<MutexSave>,
+) -> Result<Vec<Backup>, Error> {
+    let retention = Retention { keep, max_age_days };
+    *state_save.retention.lock().unwrap() = retention.clone();
+
+    //Apply the new policy to the open save right away
+    match state_save.inner().path.lock().unwrap().as_ref() {
+        Some(path) => backup::prune(path, &retention),
+        None => Ok(Vec::new()),
     }
 }
@@ -52,6 +52,16 @@ function Nav({ setLoading, setSave, save }) {
     }
   }
 
+  // command is "undo" or "redo"
+  async function stepHistory(command) {
+    try {
+      const result = await invoke(command);
+      setSave(result.save);
+    } catch (error) {
+      console.log(error);
+    }
+  }
+
   return (
     <nav className="nav">
       <button id="openSave" onClick={readFile}>
@@ -61,6 +71,18 @@ function Nav({ setLoading, setSave, save }) {
       <button disabled={save == null ? true : false} onClick={saveChanges}>
         Save
       </button>
+      <button
+        disabled={save == null ? true : false}
+        onClick={() => stepHistory("undo")}
+      >
+        Undo
+      </button>
+      <button
+        disabled={save == null ? true : false}
+        onClick={() => stepHistory("redo")}
+      >
+        Redo
+      </button>
     </nav>
   );
 }
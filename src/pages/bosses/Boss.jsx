@@ -5,18 +5,8 @@ function Boss({ boss, onChange }) {
   function handleChange({ target }) {
     const option = JSON.parse(target.value);
 
-    if (option === 1) {
-      flags.forEach((f) => {
-        f.current_value = f.alive_value;
-      });
-    } else {
-      flags.forEach((f) => {
-        f.current_value = f.dead_value;
-      });
-    }
-    // boss.value = value;
     if (typeof onChange === "function") {
-      onChange(flags);
+      onChange(option === 2);
     }
   }
 
@@ -11,15 +11,14 @@ function Bosses() {
 
   const { images } = useContext(ImagesContext);
 
-  async function handleChange(e, i) {
-    e.forEach(async (x) => {
-      await invoke("set_flag", {
-        offset: x.rel_offset,
-        newValue: x.current_value,
-      });
+  async function handleChange(dead, i) {
+    //Only the bits of the boss change, the rest of the byte belongs to other events
+    const boss = await invoke("set_boss_dead", {
+      name: bosses[i].name,
+      dead: dead,
     });
 
-    bosses[i].flags = e;
+    bosses[i] = boss;
     setSave((prev) => {
       prev.bosses = bosses;
       return prev;
@@ -60,7 +60,7 @@ function Appearance() {
             }
           } catch (error) {
             console.error(error);
-            await dialog.message(error, {
+            await dialog.message(error.message, {
               type: "error",
             });
           }
@@ -8,6 +8,7 @@ import Playtime from "./Playtime";
 import { represent } from "../../utils/playtime";
 import CharacterInfo from "./CharacterInfo";
 import Appearance from "./Appearance";
+import CharacterSheet from "./CharacterSheet";
 import IszGlitch from "./IszGlitch";
 import Coordinates from "./Coordinates";
 import Teleport from "./Teleport";
@@ -99,6 +100,8 @@ function Character() {
           />
           {/* Appearance */}
           <Appearance />
+          {/* Character sheet */}
+          <CharacterSheet setSave={setSave} />
           {/* Isz glitch */}
           <IszGlitch />
           <Playtime ms={editedPlaytime} setMs={setEditedPlaytime} />
@@ -0,0 +1,88 @@
+import { invoke } from "@tauri-apps/api/core";
+import * as dialog from "@tauri-apps/plugin-dialog";
+
+const filters = [{ name: "Character sheet", extensions: ["json"] }];
+
+function CharacterSheet({ setSave }) {
+  return (
+    <div
+      style={{
+        fontSize: "25px",
+        marginTop: "5px",
+        display: "flex",
+        justifyContent: "space-between",
+      }}
+    >
+      <button
+        className="buttonBg"
+        style={{
+          padding: "0 15px",
+          fontSize: "inherit",
+          backgroundSize: "100% 100%",
+        }}
+        onClick={async () => {
+          try {
+            const path = await dialog.save({
+              title: "Save character sheet",
+              filters,
+            });
+
+            if (path) {
+              const success = await invoke("export_sheet", {
+                path,
+              });
+
+              await dialog.message(success);
+            }
+          } catch (error) {
+            console.error(error);
+            await dialog.message(error.message, {
+              type: "error",
+            });
+          }
+        }}
+      >
+        Export build
+      </button>
+      <button
+        className="buttonBg"
+        style={{
+          padding: "0 15px",
+          fontSize: "inherit",
+          backgroundSize: "100% 100%",
+        }}
+        onClick={async () => {
+          try {
+            const path = await dialog.open({
+              title: "Select a character sheet",
+              filters,
+            });
+
+            if (path) {
+              const result = await invoke("import_sheet", {
+                path,
+              });
+
+              setSave(result.save);
+              const { skipped } = result.report;
+              await dialog.message(
+                skipped.length > 0
+                  ? `Imported, skipped:\n${skipped.join("\n")}`
+                  : "Successfully imported",
+              );
+            }
+          } catch (error) {
+            console.error(error);
+            await dialog.message(error.message, {
+              type: "error",
+            });
+          }
+        }}
+      >
+        Import build
+      </button>
+    </div>
+  );
+}
+
+export default CharacterSheet;
@@ -2,7 +2,8 @@ import { invoke } from "@tauri-apps/api/core";
 import { message } from "@tauri-apps/plugin-dialog";
 import { useEffect, useRef, useState } from "react";
 
-function Flag({ label, offset, values, info, isMask = false }) {
+function Flag({ flag, onChange }) {
+  const { name, info, states, state } = flag;
   const tipRef = useRef();
   const [top, setTop] = useState(0);
   const [showTip, setShowTip] = useState(false);
@@ -13,21 +14,17 @@ function Flag({ label, offset, values, info, isMask = false }) {
     }
   }, []);
 
-  async function setFlag() {
-    for (let i = 0; i < values.length; i++) {
-      await invoke("set_flag", {
-        offset: offset + i,
-        newValue: values[i],
+  async function setFlag(newState) {
+    try {
+      const status = await invoke("set_named_flag", {
+        name: name,
+        state: newState,
       });
+      onChange(status);
+      await message("Flag applied");
+    } catch (error) {
+      await message(error.message, { type: "error" });
     }
-    await message("Flag applied");
-  }
-
-  async function applyMask() {
-    await invoke("apply_mask", {
-      offset: offset,
-      mask: values[0],
-    });
   }
 
   return (
@@ -66,19 +63,25 @@ function Flag({ label, offset, values, info, isMask = false }) {
         >
           ?
         </div>
-        <label>{label}</label>
+        <label>{name}</label>
       </div>
 
-      <button
-        style={{
-          padding: "0rem 1rem",
-          backgroundSize: "100% 100%",
-        }}
-        className="buttonBg"
-        onClick={isMask ? applyMask : setFlag}
-      >
-        Apply
-      </button>
+      <div style={{ display: "flex", gap: "0.5rem" }}>
+        {states.map((x) => (
+          <button
+            key={x.name}
+            style={{
+              padding: "0rem 1rem",
+              backgroundSize: "100% 100%",
+              opacity: state === x.name ? 0.6 : 1,
+            }}
+            className="buttonBg"
+            onClick={() => setFlag(x.name)}
+          >
+            {states.length === 1 ? "Apply" : x.name}
+          </button>
+        ))}
+      </div>
     </div>
   );
 }
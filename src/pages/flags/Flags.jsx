@@ -1,7 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+import { message } from "@tauri-apps/plugin-dialog";
+import { useEffect, useState } from "react";
 import Flag from "./Flag";
 import "./flags.css";
 
 function Flags() {
+  const [flags, setFlags] = useState([]);
+
+  useEffect(() => {
+    invoke("get_flags")
+      .then(setFlags)
+      .catch((error) => message(error.message, { type: "error" }));
+  }, []);
+
   return (
     <div
       style={{
@@ -32,34 +43,15 @@ function Flags() {
           paddingBottom: "2px",
         }}
       >
-        <Flag
-          label="Restore Maria's dialogue"
-          offset={1083}
-          values={[0, 8]}
-          info={"Restores some dialogue before fighting Maria."}
-        />
-        <Flag
-          label="Enable Doll's lullaby"
-          offset={6689}
-          values={[8, 1]}
-          info={"Enables the doll's lullaby from version 1.0."}
-        />
-        <Flag
-          label="Enable Blood addled"
-          offset={4127}
-          values={[162]}
-          info={
-            "Enables pvp when doing coop with players that have the Hunter rune equipped."
-          }
-        />
-        {/* <Flag
-          label="Enable multiplayer in Central yharnam"
-          offset={4127}
-          values={[162]}
-          info={
-            "Enables multiplayer in central yharnam when both bosses are dead."
-          }
-        /> */}
+        {flags.map((flag, i) => (
+          <Flag
+            key={flag.name}
+            flag={flag}
+            onChange={(status) =>
+              setFlags((prev) => prev.map((f, j) => (j === i ? status : f)))
+            }
+          />
+        ))}
       </div>
     </div>
   );
@@ -10,11 +10,31 @@ function AddScreen({ type, setAddScreen, isStorage }) {
   async function handleConfirm() {
     try {
       if (selected) {
-        const editedSave = await invoke("add_item", {
-          id: selected.id,
-          quantity: 1,
-          isStorage,
-        });
+        let editedSave;
+        switch (selected.article_type) {
+          case "RightHand":
+          case "LeftHand":
+            editedSave = await invoke("add_weapon", {
+              id: selected.id,
+              imprint: null,
+              upgradeLevel: 0,
+              shapes: [],
+              isStorage,
+            });
+            break;
+          case "Armor":
+            editedSave = await invoke("add_armor", {
+              id: selected.id,
+              isStorage,
+            });
+            break;
+          default:
+            editedSave = await invoke("add_item", {
+              id: selected.id,
+              quantity: 1,
+              isStorage,
+            });
+        }
 
         setSave(editedSave);
       }
@@ -2,6 +2,7 @@ import "./inventory.css";
 import { useEffect, useRef, useState, useContext } from "react";
 import { SaveContext } from "../../context/context";
 import { invoke } from "@tauri-apps/api/core";
+import * as dialog from "@tauri-apps/plugin-dialog";
 import ReplaceScreen from "../../components/ReplaceScreen";
 import { getType } from "../../utils/upgrades";
 import FilterButtons from "./FilterButtons";
@@ -77,7 +78,6 @@ function Inventory({ inv, isStorage }) {
       {/* Optional modal like screens */}
       {addScreen ? (
         <AddScreen
-          type="item"
           setAddScreen={setAddScreen}
           isStorage={isStorage}
         />
@@ -249,6 +249,69 @@ function Inventory({ inv, isStorage }) {
         >
           Add
         </button>
+        <button
+          className="buttonBg inventory-btn"
+          disabled={
+            !selected?.upgrade_type &&
+            (selected?.article_type === undefined ||
+              selected.article_type === "Key")
+          }
+          onClick={async () => {
+            const confirmed = await dialog.confirm(
+              selected.upgrade_type
+                ? `Destroy ${selected.info.name}? It is removed from the save.`
+                : `Remove ${selected.info.item_name}? Its gems are moved to the ${
+                    isStorage ? "storage" : "inventory"
+                  }.`,
+            );
+            if (!confirmed) return;
+
+            try {
+              const editedSave = selected.upgrade_type
+                ? await invoke("destroy_upgrade", { id: selected.id })
+                : await invoke("remove_article", {
+                    articleType: selected.article_type,
+                    articleIndex: selected.index,
+                    isStorage,
+                  });
+              setSave(editedSave);
+              setSelected(null);
+            } catch (error) {
+              console.error(error);
+              await dialog.message(error.message, {
+                type: "error",
+              });
+            }
+          }}
+        >
+          Remove
+        </button>
+        <button
+          className="buttonBg inventory-btn"
+          disabled={
+            selected?.article_type === undefined ||
+            selected.article_type === "Key"
+          }
+          onClick={async () => {
+            try {
+              const editedSave = await invoke("move_article", {
+                articleType: selected.article_type,
+                articleIndex: selected.index,
+                quantity: selected.amount,
+                isStorage,
+              });
+              setSave(editedSave);
+              setSelected(null);
+            } catch (error) {
+              console.error(error);
+              await dialog.message(error.message, {
+                type: "error",
+              });
+            }
+          }}
+        >
+          {isStorage ? "To inventory" : "To storage"}
+        </button>
         <button
           className="buttonBg inventory-btn"
           disabled={
@@ -88,6 +88,9 @@ function Stats() {
                   }
                 } catch (error) {
                   console.error(error);
+                  await dialog.message(error.message, {
+                    type: "error",
+                  });
                 }
               },
             );